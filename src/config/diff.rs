@@ -0,0 +1,276 @@
+use std::fmt;
+use std::io;
+
+use indexmap::IndexMap;
+use serde::Serialize;
+
+use crate::config::models::binds::{is_bind_keyword, write_key_bind, KeyBind};
+use crate::config::models::core::HyprlandConfig;
+use crate::config::models::monitors::write_monitor;
+use crate::config::models::rules::write_window_rule;
+use crate::config::parser::cst::{split_keyed, Document};
+use crate::config::utils::{CommentStyle, OptionStyle, WriteMode};
+
+/// Top-level keywords that are compared as records rather than as option paths
+const RECORD_KEYWORDS: &[&str] = &["monitor", "env", "submap", "windowrule", "windowrulev2"];
+
+/// Kind of thing a difference is about
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Category {
+    Option,
+    Keybind,
+    Monitor,
+    WindowRule,
+    Env,
+}
+
+impl fmt::Display for Category {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Category::Option => write!(f, "option"),
+            Category::Keybind => write!(f, "keybind"),
+            Category::Monitor => write!(f, "monitor"),
+            Category::WindowRule => write!(f, "windowrule"),
+            Category::Env => write!(f, "env"),
+        }
+    }
+}
+
+/// One option, bind, monitor, rule or variable that differs between two configs
+#[derive(Debug, Clone, Serialize)]
+pub struct Difference {
+    pub category: Category,
+    /// What the item is matched by: an option path, a bind's modifiers and key,
+    /// a monitor name, a rule name or an env var name
+    pub key: String,
+    /// Value in the first config, `None` when it was added
+    pub before: Option<String>,
+    /// Value in the second config, `None` when it was removed
+    pub after: Option<String>,
+}
+
+impl fmt::Display for Difference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (&self.before, &self.after) {
+            // Unnamed rules are keyed by their own text
+            (None, Some(value)) | (Some(value), None) if *value == self.key => {
+                let sign = if self.after.is_some() { '+' } else { '-' };
+                write!(f, "{} {} {}", sign, self.category, self.key)
+            }
+            (None, Some(after)) => write!(f, "+ {} {}: {}", self.category, self.key, after),
+            (Some(before), None) => write!(f, "- {} {}: {}", self.category, self.key, before),
+            (Some(before), Some(after)) => write!(f, "~ {} {}: {} -> {}", self.category, self.key, before, after),
+            (None, None) => write!(f, "  {} {}", self.category, self.key),
+        }
+    }
+}
+
+/// Compare two configs item by item, ignoring ordering, formatting and comments
+pub fn diff(before: &HyprlandConfig, after: &HyprlandConfig) -> io::Result<Vec<Difference>> {
+    let mut differences = Vec::new();
+    diff_options(&mut differences, &options(before)?, &options(after)?);
+    diff_maps(&mut differences, Category::Keybind, &keybinds(before)?, &keybinds(after)?);
+    diff_maps(&mut differences, Category::Monitor, &monitors(before)?, &monitors(after)?);
+    diff_maps(&mut differences, Category::WindowRule, &window_rules(before)?, &window_rules(after)?);
+    diff_maps(&mut differences, Category::Env, &before.environment_variables, &after.environment_variables);
+    Ok(differences)
+}
+
+/// Values of every option path, rendered in colon style so nesting does not matter
+fn options(config: &HyprlandConfig) -> io::Result<IndexMap<String, Vec<String>>> {
+    let mut buffer = Vec::new();
+    config.write_styled(&mut buffer, &CommentStyle::Hash, OptionStyle::ColonPath, WriteMode::Full)?;
+    let document = Document::parse(&String::from_utf8_lossy(&buffer));
+
+    let mut values = document.values_by_path();
+    values.retain(|path, _| {
+        let section = path.split(':').next().unwrap_or_default();
+        let block = split_keyed(section).map_or(section, |(block, _)| block);
+        !(RECORD_KEYWORDS.contains(&block) || is_bind_keyword(block))
+    });
+    Ok(values)
+}
+
+fn diff_options(
+    differences: &mut Vec<Difference>,
+    before: &IndexMap<String, Vec<String>>,
+    after: &IndexMap<String, Vec<String>>,
+) {
+    let empty = Vec::new();
+    let paths = before.keys().chain(after.keys().filter(|path| !before.contains_key(*path)));
+    for path in paths {
+        let old = before.get(path).unwrap_or(&empty);
+        let new = after.get(path).unwrap_or(&empty);
+        if old == new {
+            continue;
+        }
+
+        // Repeatable keywords like `exec-once` or `workspace` are compared as sets of lines
+        if old.len() > 1 || new.len() > 1 {
+            for value in old.iter().filter(|v| !new.contains(v)) {
+                differences.push(Difference { category: Category::Option, key: path.clone(), before: Some(value.clone()), after: None });
+            }
+            for value in new.iter().filter(|v| !old.contains(v)) {
+                differences.push(Difference { category: Category::Option, key: path.clone(), before: None, after: Some(value.clone()) });
+            }
+            continue;
+        }
+        differences.push(Difference {
+            category: Category::Option,
+            key: path.clone(),
+            before: old.first().cloned(),
+            after: new.first().cloned(),
+        });
+    }
+}
+
+fn diff_maps(
+    differences: &mut Vec<Difference>,
+    category: Category,
+    before: &IndexMap<String, String>,
+    after: &IndexMap<String, String>,
+) {
+    for (key, old) in before {
+        match after.get(key) {
+            Some(new) if new == old => {}
+            new => differences.push(Difference { category, key: key.clone(), before: Some(old.clone()), after: new.cloned() }),
+        }
+    }
+    for (key, new) in after.iter().filter(|(key, _)| !before.contains_key(*key)) {
+        differences.push(Difference { category, key: key.clone(), before: None, after: Some(new.clone()) });
+    }
+}
+
+/// Binds keyed by their submap and resolved modifiers and key
+fn keybinds(config: &HyprlandConfig) -> io::Result<IndexMap<String, String>> {
+    let global = config.binds.keybinds.iter().map(|bind| (None, bind));
+    let submaps = config.submap_definitions.iter()
+        .flat_map(|(name, binds)| binds.iter().map(move |bind| (Some(name.as_str()), bind)));
+
+    let mut keybinds: IndexMap<String, String> = IndexMap::new();
+    for (submap, bind) in global.chain(submaps) {
+        // Written with variables expanded, so `$mainMod` and `SUPER` compare equal
+        let mut bind = bind.clone();
+        if let Ok((modifiers, key)) = bind.trigger(&config.variables) {
+            bind.modifiers = modifiers;
+            bind.key = key;
+        }
+        let key = trigger(&bind, submap);
+        let line = render(|buffer| write_key_bind(buffer, &bind))?;
+        // Several binds on one trigger all fire, so they are compared together
+        keybinds.entry(key)
+            .and_modify(|lines| { lines.push_str("; "); lines.push_str(&line); })
+            .or_insert(line);
+    }
+    Ok(keybinds)
+}
+
+fn trigger(bind: &KeyBind, submap: Option<&str>) -> String {
+    let trigger = if bind.modifiers.is_empty() {
+        bind.key.to_string()
+    } else {
+        format!("{}, {}", bind.modifiers, bind.key)
+    };
+    match submap {
+        Some(submap) => format!("{} (submap {})", trigger, submap),
+        None => trigger,
+    }
+}
+
+/// Monitors keyed by output name; a later rule for the same output replaces an earlier one
+fn monitors(config: &HyprlandConfig) -> io::Result<IndexMap<String, String>> {
+    let mut monitors = IndexMap::new();
+    for monitor in &config.monitors {
+        let name = if monitor.name.is_empty() { "(default)".to_string() } else { monitor.name.clone() };
+        monitors.insert(name, render(|buffer| write_monitor(buffer, monitor, &CommentStyle::Hash))?);
+    }
+    Ok(monitors)
+}
+
+/// Window rules keyed by name, or by their own text when unnamed
+fn window_rules(config: &HyprlandConfig) -> io::Result<IndexMap<String, String>> {
+    let mut rules = IndexMap::new();
+    for rule in &config.window_rules {
+        let text = render(|buffer| write_window_rule(buffer, rule, &CommentStyle::Hash))?;
+        let key = rule.name.clone().unwrap_or_else(|| text.clone());
+        rules.insert(key, text);
+    }
+    Ok(rules)
+}
+
+/// Render a record to a single line, joining the lines of block syntax
+fn render(write: impl FnOnce(&mut Vec<u8>) -> io::Result<()>) -> io::Result<String> {
+    let mut buffer = Vec::new();
+    write(&mut buffer)?;
+    let text = String::from_utf8_lossy(&buffer);
+    let lines: Vec<&str> = text.lines().map(str::trim).filter(|line| !line.is_empty()).collect();
+    Ok(lines.join(" "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::parser::ConfigParser;
+
+    fn differences(before: &str, after: &str) -> Vec<String> {
+        let before = ConfigParser::parse_string(before).unwrap();
+        let after = ConfigParser::parse_string(after).unwrap();
+        diff(&before, &after).unwrap().iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn ordering_formatting_and_comments_are_ignored() {
+        let before = "$mainMod = SUPER\ngeneral {\n    gaps_in = 5\n    border_size = 2\n}\nbind = SUPER, Q, exec, kitty\n";
+        let after = "$mainMod = SUPER\nbind = $mainMod, q, exec, kitty # terminal\ngeneral:border_size=2\ngeneral:gaps_in = 5\n";
+        assert_eq!(differences(before, after), Vec::<String>::new());
+    }
+
+    #[test]
+    fn changes_are_reported_per_item() {
+        let before = "\
+monitor = DP-1, 1920x1080, 0x0, 1
+env = XCURSOR_SIZE, 24
+general {
+    gaps_in = 5
+}
+bind = SUPER, Q, exec, kitty
+bind = SUPER, E, exec, thunar
+";
+        let after = "\
+monitor = DP-1, 2560x1440, 0x0, 1
+monitor = HDMI-A-1, preferred, auto, 1
+env = XCURSOR_SIZE, 32
+general {
+    gaps_in = 8
+}
+bind = SUPER, Q, exec, foot
+";
+        assert_eq!(differences(before, after), [
+            "~ option general:gaps_in: 5 -> 8",
+            "~ keybind SUPER, Q: bind = SUPER, Q, exec, kitty -> bind = SUPER, Q, exec, foot",
+            "- keybind SUPER, E: bind = SUPER, E, exec, thunar",
+            "~ monitor DP-1: monitor = DP-1,1920x1080,0x0,1 -> monitor = DP-1,2560x1440,0x0,1",
+            "+ monitor HDMI-A-1: monitor = HDMI-A-1,preferred,auto,1",
+            "~ env XCURSOR_SIZE: 24 -> 32",
+        ]);
+    }
+
+    #[test]
+    fn unnamed_rules_are_keyed_by_their_text() {
+        let changes = differences("windowrulev2 = float, class:^(kitty)$\n", "windowrulev2 = float, class:^(foot)$\n");
+        assert_eq!(changes, [
+            "- windowrule windowrulev2 = float, class:^(kitty)$",
+            "+ windowrule windowrulev2 = float, class:^(foot)$",
+        ]);
+    }
+
+    #[test]
+    fn differences_serialize_with_snake_case_categories() {
+        let difference = Difference { category: Category::WindowRule, key: "k".to_string(), before: None, after: Some("v".to_string()) };
+        assert_eq!(
+            serde_json::to_string(&difference).unwrap(),
+            r#"{"category":"window_rule","key":"k","before":null,"after":"v"}"#
+        );
+    }
+}
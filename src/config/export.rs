@@ -0,0 +1,140 @@
+//! Conversion between `HyprlandConfig` and structured data (JSON, TOML, YAML).
+//!
+//! Imports are laid over Hyprland's defaults, so a document only needs the
+//! fields it wants to change.
+
+use anyhow::{anyhow, Context, Result};
+use log::debug;
+use serde_json::Value;
+
+use crate::config::models::core::HyprlandConfig;
+use crate::config::parser::source::SourceTree;
+use crate::config::parser::variables::Variables;
+use crate::config::parser::ConfigParser;
+use crate::config::utils::{CommentStyle, DataFormat};
+
+/// Serialize a config to `format`
+pub fn export(config: &HyprlandConfig, format: DataFormat) -> Result<String> {
+    let mut content = match format {
+        DataFormat::Json => serde_json::to_string_pretty(config)?,
+        DataFormat::Toml => toml::to_string_pretty(config)?,
+        // Through a JSON value, so enums are plain maps rather than YAML tags
+        DataFormat::Yaml => serde_norway::to_string(&serde_json::to_value(config)?)?,
+    };
+    if !content.ends_with('\n') {
+        content.push('\n');
+    }
+    Ok(content)
+}
+
+/// Read a config from structured data, taking Hyprland's defaults for missing fields.
+///
+/// Returns the config along with warnings about it, such as unused variables.
+pub fn import(content: &str, format: DataFormat) -> Result<(HyprlandConfig, Vec<String>)> {
+    let value: Value = match format {
+        DataFormat::Json => serde_json::from_str(content).context("Invalid JSON")?,
+        DataFormat::Toml => toml::from_str(content).context("Invalid TOML")?,
+        DataFormat::Yaml => serde_norway::from_str(content).context("Invalid YAML")?,
+    };
+    if !value.is_object() {
+        return Err(anyhow!("Expected a table of config sections at the top level"));
+    }
+
+    let mut merged = serde_json::to_value(ConfigParser::defaults())?;
+    merge(&mut merged, value, "")?;
+    let config: HyprlandConfig = serde_json::from_value(merged).context("Invalid config")?;
+
+    // Many values are kept as text in the model, so check them as Hyprland would read them
+    let mut buffer = Vec::new();
+    config.write_to(&mut buffer, &CommentStyle::Hash)?;
+    let sources = SourceTree::from_string(&String::from_utf8_lossy(&buffer));
+    let variables = Variables::from_sources(&sources);
+    let mut warnings = Vec::new();
+    for diagnostic in ConfigParser::validate(&sources) {
+        let line = sources.line(&diagnostic.file, diagnostic.line).map_or("", |l| l.raw.trim());
+        let message = format!("{} (in `{}`)", diagnostic.message, line);
+        // An undefined variable would reach Hyprland as literal `$name` text
+        let undefined = variables.undefined().iter().any(|site| site.file == diagnostic.file && site.line == diagnostic.line);
+        if diagnostic.is_error() || undefined {
+            return Err(anyhow!(message));
+        }
+        warnings.push(message);
+    }
+    debug!("Imported config from {:?}", format);
+    Ok((config, warnings))
+}
+
+/// Lay `value` over `base`, merging tables and replacing everything else.
+///
+/// Keys a section does not have are rejected, so typos are not silently dropped;
+/// maps that are empty by default, like `variables`, take any key.
+fn merge(base: &mut Value, value: Value, path: &str) -> Result<()> {
+    match (base, value) {
+        (Value::Object(base), Value::Object(value)) => {
+            let open = base.is_empty();
+            for (key, value) in value {
+                let field = if path.is_empty() { key.clone() } else { format!("{}.{}", path, key) };
+                match base.get_mut(&key) {
+                    // Options the model keeps as text, like gaps, may be given as numbers
+                    Some(existing) if existing.is_string() && (value.is_number() || value.is_boolean()) => {
+                        *existing = Value::String(value.to_string());
+                    }
+                    Some(existing) if !existing.is_null() => merge(existing, value, &field)?,
+                    Some(existing) => *existing = value,
+                    None if open => {
+                        base.insert(key, value);
+                    }
+                    None => return Err(anyhow!("Unknown field `{}`", field)),
+                }
+            }
+        }
+        (base, value) => *base = value,
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn written(config: &HyprlandConfig) -> String {
+        let mut buffer = Vec::new();
+        config.write_to(&mut buffer, &CommentStyle::Hash).unwrap();
+        String::from_utf8(buffer).unwrap()
+    }
+
+    #[test]
+    fn every_format_round_trips() {
+        let config = ConfigParser::parse_string(include_str!("../../docs/hyprland.conf")).unwrap();
+        for format in [DataFormat::Json, DataFormat::Toml, DataFormat::Yaml] {
+            let (imported, _) = import(&export(&config, format).unwrap(), format).unwrap();
+            assert_eq!(written(&imported), written(&config), "{:?}", format);
+        }
+    }
+
+    #[test]
+    fn missing_fields_take_the_defaults() {
+        let (config, warnings) = import("general:\n  border_size: 3\n  gaps_out: 8\n", DataFormat::Yaml).unwrap();
+        assert_eq!(config.general.border_size, 3);
+        assert_eq!(config.general.gaps_out, "8");
+        assert_eq!(config.binds.scroll_event_delay, 300);
+        assert!(warnings.is_empty(), "{:?}", warnings);
+    }
+
+    #[test]
+    fn unknown_fields_and_bad_values_are_rejected() {
+        let error = import(r#"{"general": {"bordersize": 3}}"#, DataFormat::Json).unwrap_err();
+        assert_eq!(error.to_string(), "Unknown field `general.bordersize`");
+        assert!(import("[general]\ngaps_out = \"wide\"\n", DataFormat::Toml).is_err());
+        assert!(import("[1, 2]", DataFormat::Json).is_err());
+    }
+
+    #[test]
+    fn undefined_variables_fail_and_unused_ones_warn() {
+        let error = import(r#"{"general": {"gaps_out": "$gap"}}"#, DataFormat::Json).unwrap_err();
+        assert!(error.to_string().contains("undefined variable `$gap`"), "{}", error);
+
+        let (_, warnings) = import(r#"{"variables": {"gap": "4"}}"#, DataFormat::Json).unwrap();
+        assert_eq!(warnings, ["unused variable `$gap` (in `$gap = 4`)"]);
+    }
+}
@@ -0,0 +1,384 @@
+//! Canonical formatting of a config file.
+//!
+//! Formatting works on the lossless `Document`, never on the model, so every
+//! comment and unknown line survives. Comment lines directly above an option or
+//! block move with it when it is sorted.
+
+use crate::config::models::binds::{is_bind_keyword, Key, Modifiers};
+use crate::config::parser::cst::{is_record_block, split_comment, Document, Line, LineKind};
+use crate::config::schema;
+
+/// Indentation of one nesting level
+const INDENT: &str = "    ";
+
+/// Format config text, returning the canonical text
+pub fn format(content: &str) -> String {
+    let document = Document::parse(content);
+    let lines = document.lines();
+    let mut index = 0;
+    let mut nodes = parse_nodes(lines, &mut index);
+    // A stray `}` ends parsing early; keep everything after it as written
+    while index < lines.len() {
+        nodes.push(Node { comments: Vec::new(), kind: NodeKind::Line(&lines[index]) });
+        index += 1;
+        nodes.extend(parse_nodes(lines, &mut index));
+    }
+
+    let nodes = sort_nodes(nodes, 0);
+    let mut out = Vec::new();
+    emit(&nodes, 0, &mut out);
+
+    while out.last().is_some_and(|l| matches!(l, Out::Blank)) {
+        out.pop();
+    }
+    align_binds(&mut out);
+
+    let mut formatted = String::new();
+    for line in &out {
+        formatted.push_str(&line.render());
+        formatted.push('\n');
+    }
+    formatted
+}
+
+/// A line or block, with the comment lines that describe it
+struct Node<'a> {
+    comments: Vec<&'a Line>,
+    kind: NodeKind<'a>,
+}
+
+enum NodeKind<'a> {
+    Blank,
+    /// A comment that is not directly above anything
+    Comment(&'a Line),
+    Line(&'a Line),
+    Block { open: &'a Line, children: Vec<Node<'a>>, close: Option<&'a Line> },
+}
+
+impl Node<'_> {
+    fn is_blank(&self) -> bool {
+        matches!(self.kind, NodeKind::Blank)
+    }
+
+    fn key(&self) -> Option<&str> {
+        match &self.kind {
+            NodeKind::Line(Line { kind: LineKind::Assignment(a), .. }) => Some(&a.key),
+            _ => None,
+        }
+    }
+
+    /// Whether this is an option the schema knows, which may be reordered freely.
+    ///
+    /// Keywords like `bezier` and `animation` are left alone, as later lines
+    /// refer to earlier ones.
+    fn is_known_option(&self) -> bool {
+        match &self.kind {
+            NodeKind::Line(line) => line.path().is_some_and(|path| schema::lookup(&path).is_some()),
+            _ => false,
+        }
+    }
+
+    /// Position of a top-level block in the schema's section order, if it may be moved
+    fn section_rank(&self) -> Option<usize> {
+        let NodeKind::Block { open: Line { kind: LineKind::BlockOpen(name), .. }, .. } = &self.kind else {
+            return None;
+        };
+        if is_record_block(name) {
+            return None;
+        }
+        let rank = schema::options().iter().position(|option| option.section().split(':').next() == Some(name.as_str()));
+        Some(rank.unwrap_or(usize::MAX))
+    }
+}
+
+/// Group lines into nodes until the end of the enclosing block
+fn parse_nodes<'a>(lines: &'a [Line], index: &mut usize) -> Vec<Node<'a>> {
+    let mut nodes = Vec::new();
+    let mut comments = Vec::new();
+    while *index < lines.len() {
+        let line = &lines[*index];
+        match &line.kind {
+            LineKind::BlockClose => break,
+            LineKind::Comment(_) => {
+                comments.push(line);
+                *index += 1;
+                continue;
+            }
+            LineKind::Blank => {
+                nodes.extend(comments.drain(..).map(|c| Node { comments: Vec::new(), kind: NodeKind::Comment(c) }));
+                nodes.push(Node { comments: Vec::new(), kind: NodeKind::Blank });
+                *index += 1;
+                continue;
+            }
+            LineKind::BlockOpen(_) => {
+                *index += 1;
+                let children = parse_nodes(lines, index);
+                let close = lines.get(*index);
+                *index += 1;
+                nodes.push(Node { comments: std::mem::take(&mut comments), kind: NodeKind::Block { open: line, children, close } });
+                continue;
+            }
+            LineKind::Assignment(_) | LineKind::Unknown => {
+                nodes.push(Node { comments: std::mem::take(&mut comments), kind: NodeKind::Line(line) });
+            }
+        }
+        *index += 1;
+    }
+    nodes.extend(comments.into_iter().map(|c| Node { comments: Vec::new(), kind: NodeKind::Comment(c) }));
+    nodes
+}
+
+/// Sort options inside blocks, and category blocks at the top level.
+///
+/// Options are only sorted within a group: blank lines, loose comments and nested
+/// blocks stay where they are. Top-level options are never moved, since variables,
+/// submaps and binds depend on their order.
+fn sort_nodes(nodes: Vec<Node>, depth: usize) -> Vec<Node> {
+    let mut nodes: Vec<Node> = nodes.into_iter().map(|mut node| {
+        if let NodeKind::Block { children, .. } = &mut node.kind {
+            *children = sort_nodes(std::mem::take(children), depth + 1);
+        }
+        node
+    }).collect();
+
+    if depth > 0 {
+        for group in nodes.split_mut(|n| n.key().is_none()) {
+            if group.iter().all(Node::is_known_option) {
+                group.sort_by(|a, b| a.key().cmp(&b.key()));
+            }
+        }
+        return nodes;
+    }
+
+    // Runs of category blocks with only blank lines between them are put in section order
+    let mut sorted = Vec::with_capacity(nodes.len());
+    let mut run = Vec::new();
+    let mut trailing_blank = false;
+    for node in nodes {
+        if node.section_rank().is_some() {
+            run.push(node);
+            trailing_blank = false;
+        } else if node.is_blank() && !run.is_empty() {
+            trailing_blank = true;
+        } else {
+            flush_blocks(&mut sorted, &mut run, trailing_blank);
+            trailing_blank = false;
+            sorted.push(node);
+        }
+    }
+    flush_blocks(&mut sorted, &mut run, trailing_blank);
+    sorted
+}
+
+fn flush_blocks<'a>(sorted: &mut Vec<Node<'a>>, run: &mut Vec<Node<'a>>, trailing_blank: bool) {
+    run.sort_by_key(|n| n.section_rank());
+    for (i, block) in run.drain(..).enumerate() {
+        if i > 0 {
+            sorted.push(Node { comments: Vec::new(), kind: NodeKind::Blank });
+        }
+        sorted.push(block);
+    }
+    if trailing_blank {
+        sorted.push(Node { comments: Vec::new(), kind: NodeKind::Blank });
+    }
+}
+
+/// A formatted output line
+enum Out {
+    Blank,
+    Text(String),
+    Bind {
+        indent: usize,
+        keyword: String,
+        modifiers: String,
+        key: String,
+        rest: Option<String>,
+        comment: Option<String>,
+        /// Widths of the keyword, modifiers and key columns
+        widths: [usize; 3],
+    },
+}
+
+impl Out {
+    fn render(&self) -> String {
+        match self {
+            Out::Blank => String::new(),
+            Out::Text(text) => text.clone(),
+            Out::Bind { indent, keyword, modifiers, key, rest, comment, widths } => {
+                let mut line = format!(
+                    "{}{:<keyword_width$} = {:<modifiers_width$} ",
+                    INDENT.repeat(*indent),
+                    keyword,
+                    format!("{},", modifiers),
+                    keyword_width = widths[0],
+                    modifiers_width = widths[1] + 1,
+                );
+                match rest {
+                    Some(rest) => line.push_str(&format!("{:<width$} {}", format!("{},", key), rest, width = widths[2] + 1)),
+                    None => line.push_str(key),
+                }
+                if let Some(comment) = comment {
+                    line.push(' ');
+                    line.push_str(comment);
+                }
+                line.trim_end().to_string()
+            }
+        }
+    }
+}
+
+fn emit(nodes: &[Node], depth: usize, out: &mut Vec<Out>) {
+    let indent = INDENT.repeat(depth);
+    for node in nodes {
+        if node.is_blank() {
+            // At most one blank line in a row, and none at the start of a block
+            let previous_blank = out.last().map_or(depth == 0, |l| matches!(l, Out::Blank));
+            let block_start = depth > 0 && out.last().is_some_and(|l| matches!(l, Out::Text(t) if t.ends_with('{')));
+            if !previous_blank && !block_start {
+                out.push(Out::Blank);
+            }
+            continue;
+        }
+        for comment in &node.comments {
+            out.push(Out::Text(format!("{}{}", indent, comment.raw.trim())));
+        }
+
+        match &node.kind {
+            NodeKind::Blank => {}
+            NodeKind::Comment(line) => out.push(Out::Text(format!("{}{}", indent, line.raw.trim()))),
+            NodeKind::Line(line) => out.push(format_line(line, depth)),
+            NodeKind::Block { open, children, close } => {
+                let name = match &open.kind {
+                    LineKind::BlockOpen(name) => name.as_str(),
+                    _ => "",
+                };
+                out.push(Out::Text(format!("{}{} {{{}", indent, name, inline_comment(open))));
+                emit(children, depth + 1, out);
+                while out.last().is_some_and(|l| matches!(l, Out::Blank)) {
+                    out.pop();
+                }
+                if let Some(close) = close {
+                    out.push(Out::Text(format!("{}}}{}", indent, inline_comment(close))));
+                }
+            }
+        }
+    }
+}
+
+/// ` # comment` after the code of a line, as written
+fn inline_comment(line: &Line) -> String {
+    match split_comment(&line.raw).1 {
+        Some(comment) => format!(" #{}", comment.trim_end()),
+        None => String::new(),
+    }
+}
+
+fn format_line(line: &Line, depth: usize) -> Out {
+    let indent = INDENT.repeat(depth);
+    let LineKind::Assignment(a) = &line.kind else {
+        return Out::Text(format!("{}{}", indent, line.raw.trim()));
+    };
+    // Keep the value as written, `##` escapes included
+    let value = &line.raw[a.value_span.clone()];
+    let comment = Some(line.raw[a.value_span.end..].trim()).filter(|c| !c.is_empty()).map(str::to_string);
+
+    if is_bind_keyword(&a.key) {
+        let mut fields = value.splitn(3, ',');
+        let (Some(modifiers), Some(key)) = (fields.next(), fields.next()) else {
+            return Out::Text(format!("{}{} = {}", indent, a.key, line.raw[a.value_span.start..].trim()));
+        };
+        // Binds that do not parse are left for `validate` to report
+        let modifiers = modifiers.parse::<Modifiers>().map_or_else(|_| modifiers.trim().to_string(), |m| m.to_string());
+        let key = key.parse::<Key>().map_or_else(|_| key.trim().to_string(), |k| k.to_string());
+        return Out::Bind {
+            indent: depth,
+            keyword: a.key.clone(),
+            modifiers,
+            key,
+            // Arguments are kept verbatim, as commas in them may matter
+            rest: fields.next().map(|rest| match rest.split_once(',') {
+                Some((dispatcher, args)) => format!("{}, {}", dispatcher.trim(), args.trim()),
+                None => rest.trim().to_string(),
+            }),
+            comment,
+            widths: [0; 3],
+        };
+    }
+
+    let mut text = format!("{}{} = {}", indent, a.key, value);
+    if let Some(comment) = comment {
+        text.push(' ');
+        text.push_str(&comment);
+    }
+    Out::Text(text.trim_end().to_string())
+}
+
+/// Line up the keyword, modifiers and key of consecutive binds in columns.
+///
+/// A run of binds ends at a blank line or any other option; comments between
+/// binds do not break it.
+fn align_binds(out: &mut [Out]) {
+    let mut start = 0;
+    while start < out.len() {
+        let end = start + out[start..].iter().position(|l| !is_comment_or_bind(l)).unwrap_or(out.len() - start);
+        let mut columns = [0; 3];
+        for line in &out[start..end] {
+            if let Out::Bind { keyword, modifiers, key, rest, .. } = line {
+                columns[0] = columns[0].max(keyword.len());
+                columns[1] = columns[1].max(modifiers.len());
+                if rest.is_some() {
+                    columns[2] = columns[2].max(key.len());
+                }
+            }
+        }
+        for line in &mut out[start..end] {
+            if let Out::Bind { widths, .. } = line {
+                *widths = columns;
+            }
+        }
+        start = end + 1;
+    }
+}
+
+fn is_comment_or_bind(line: &Out) -> bool {
+    match line {
+        Out::Bind { .. } => true,
+        Out::Text(text) => text.trim_start().starts_with('#'),
+        Out::Blank => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formatting_is_idempotent() {
+        for input in [
+            include_str!("../../docs/hyprland.conf"),
+            include_str!("../../docs/hyprland_mine.conf"),
+            include_str!("../../docs/default.conf"),
+        ] {
+            let once = format(input);
+            assert_eq!(format(&once), once);
+        }
+    }
+
+    #[test]
+    fn formatting_keeps_the_meaning() {
+        let written = |content: &str| {
+            let config = crate::config::parser::ConfigParser::parse_string(content).unwrap();
+            let mut buffer = Vec::new();
+            config.write_to(&mut buffer, &crate::config::utils::CommentStyle::Hash).unwrap();
+            String::from_utf8(buffer).unwrap()
+        };
+        let input = include_str!("../../docs/hyprland.conf");
+        assert_eq!(written(&format(input)), written(input));
+    }
+
+    #[test]
+    fn binds_are_normalised_and_blocks_indented() {
+        let input = "general {\ngaps_in=5\n}\nbind=SUPER_SHIFT,q,exec,kitty\n";
+        assert_eq!(format(input), "general {\n    gaps_in = 5\n}\nbind = SUPER SHIFT, Q, exec, kitty\n");
+    }
+}
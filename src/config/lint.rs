@@ -0,0 +1,587 @@
+//! Semantic checks that go beyond what `validate` reports.
+//!
+//! Each check is a [`Lint`] with a stable ID. A finding is dropped when the line
+//! it points at, or a comment line directly above it, carries
+//! `# hyprconf: allow(lint-id, ...)`.
+
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+
+use indexmap::IndexMap;
+
+use crate::config::models::binds::{is_bind_keyword, parse_bind, KeyBind};
+use crate::config::models::core::HyprlandConfig;
+use crate::config::models::exec::{parse_exec, ExecKind};
+use crate::config::models::rules::{
+    parse_layer_rule, parse_window_rule, parse_window_rule_v2, parse_workspace_rule, WindowRuleEffect,
+    WindowRuleMatcher,
+};
+use crate::config::parser::cst::{is_record_block, Assignment, Line, LineKind};
+use crate::config::parser::diagnostics::{key_span, value_span, Diagnostic, Severity};
+use crate::config::parser::source::SourceTree;
+use crate::config::parser::variables::Variables;
+use crate::config::parser::ConfigParser;
+use crate::config::schema;
+
+/// Words that start a shell construct rather than name a program
+const SHELL_WORDS: &[&str] = &["cd", "export", "source", ".", "eval", "exec", "if", "for", "while", "case", "[", "[[", "{", "(", "set", "unset"];
+
+/// A semantic check run over a loaded config
+pub trait Lint {
+    /// Stable ID, used in output and in `# hyprconf: allow(...)`
+    fn id(&self) -> &'static str;
+    fn severity(&self) -> Severity;
+    /// One line on what the lint looks for
+    fn description(&self) -> &'static str;
+    fn check(&self, cx: &LintContext) -> Vec<Diagnostic>;
+}
+
+/// Everything a lint may look at
+pub struct LintContext<'a> {
+    pub sources: &'a SourceTree,
+    pub config: HyprlandConfig,
+    pub variables: Variables,
+    /// Directories searched for programs started by `exec` lines
+    pub search_path: Vec<PathBuf>,
+    lines: Vec<(&'a Path, &'a Line)>,
+}
+
+impl<'a> LintContext<'a> {
+    /// Context for a loaded config, searching `$PATH` for programs
+    pub fn new(sources: &'a SourceTree) -> Self {
+        let search_path = std::env::var_os("PATH")
+            .map(|path| std::env::split_paths(&path).collect())
+            .unwrap_or_default();
+        Self {
+            sources,
+            config: ConfigParser::parse_with_diagnostics(sources).0,
+            variables: Variables::from_sources(sources),
+            search_path,
+            lines: sources.lines(),
+        }
+    }
+
+    /// Every `key = value` line, in the order Hyprland reads them
+    pub fn assignments(&self) -> impl Iterator<Item = (&'a Path, &'a Line, &'a Assignment)> + '_ {
+        self.lines.iter().filter_map(|(file, line)| match &line.kind {
+            LineKind::Assignment(a) => Some((*file, *line, a)),
+            _ => None,
+        })
+    }
+
+    /// Top-level lines using `keyword`
+    fn keyword(&self, keyword: &'static str) -> impl Iterator<Item = (&'a Path, &'a Line, &'a Assignment)> + '_ {
+        self.assignments().filter(move |(_, line, a)| line.section.is_empty() && a.key == keyword)
+    }
+
+    /// Start a finding of `lint` pointing at `span` of `line`
+    pub fn report(&self, lint: &dyn Lint, file: &Path, line: &Line, span: std::ops::Range<usize>, message: impl Into<String>) -> Diagnostic {
+        Diagnostic::new(lint.severity(), file, line, span, message).with_code(lint.id())
+    }
+
+    fn is_program(&self, program: &str) -> bool {
+        if program.contains('/') {
+            return is_executable(Path::new(shellexpand::tilde(program).as_ref()));
+        }
+        self.search_path.iter().any(|dir| is_executable(&dir.join(program)))
+    }
+
+    /// Whether an allow comment on the diagnostic's line, or just above it, names its lint
+    fn is_allowed(&self, diagnostic: &Diagnostic) -> bool {
+        let Some(code) = diagnostic.code else { return false };
+        let line = self.sources.line(&diagnostic.file, diagnostic.line);
+        let above = diagnostic.line.checked_sub(1)
+            .and_then(|number| self.sources.line(&diagnostic.file, number))
+            .filter(|line| matches!(line.kind, LineKind::Comment(_)));
+        [line, above].into_iter().flatten()
+            .filter_map(Line::comment)
+            .any(|comment| allowed_lints(comment).contains(&code))
+    }
+}
+
+/// Lint IDs named by a `hyprconf: allow(a, b)` comment
+fn allowed_lints(comment: &str) -> Vec<&str> {
+    let Some(at) = comment.find("hyprconf:") else { return Vec::new() };
+    let rest = comment[at + "hyprconf:".len()..].trim_start();
+    let Some(list) = rest.strip_prefix("allow(").and_then(|r| r.split_once(')')).map(|(list, _)| list) else {
+        return Vec::new();
+    };
+    list.split(',').map(str::trim).filter(|id| !id.is_empty()).collect()
+}
+
+fn is_executable(path: &Path) -> bool {
+    fs::metadata(path).is_ok_and(|m| m.is_file() && m.permissions().mode() & 0o111 != 0)
+}
+
+/// Every built-in lint
+pub fn lints() -> Vec<Box<dyn Lint>> {
+    vec![
+        Box::new(DuplicateBind),
+        Box::new(DuplicateOption),
+        Box::new(UndefinedVariable),
+        Box::new(UnusedVariable),
+        Box::new(UndefinedMonitor),
+        Box::new(ExecNotFound),
+        Box::new(InvalidRuleRegex),
+        Box::new(TearingWithoutImmediate),
+    ]
+}
+
+/// Run `lints` over a config, leaving out those in `allowed` and findings allowed by comments
+pub fn run(sources: &SourceTree, lints: &[Box<dyn Lint>], allowed: &[String]) -> Vec<Diagnostic> {
+    let cx = LintContext::new(sources);
+    let mut diagnostics: Vec<Diagnostic> = lints.iter()
+        .filter(|lint| !allowed.iter().any(|id| id == lint.id()))
+        .flat_map(|lint| lint.check(&cx))
+        .filter(|diagnostic| !cx.is_allowed(diagnostic))
+        .collect();
+
+    let file_order = |file: &Path| sources.files().iter().position(|f| f.path == file);
+    diagnostics.sort_by_key(|d| (file_order(&d.file), d.line, d.column));
+    diagnostics
+}
+
+/// Span of `needle` inside the value of `line`, or the whole value
+fn span_of(line: &Line, needle: &str) -> std::ops::Range<usize> {
+    let value = value_span(line);
+    match line.raw[value.clone()].find(needle).filter(|_| !needle.is_empty()) {
+        Some(at) => value.start + at..value.start + at + needle.len(),
+        None => value,
+    }
+}
+
+/// Two binds on the same modifiers and key in the same submap
+pub struct DuplicateBind;
+
+impl Lint for DuplicateBind {
+    fn id(&self) -> &'static str {
+        "duplicate-bind"
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::Warning
+    }
+
+    fn description(&self) -> &'static str {
+        "the same modifiers and key are bound twice in one submap"
+    }
+
+    fn check(&self, cx: &LintContext) -> Vec<Diagnostic> {
+        let variables = cx.variables.resolved();
+        let mut diagnostics = Vec::new();
+        let mut active: Vec<(Option<&str>, KeyBind, &Path, &Line)> = Vec::new();
+        for (file, line, a) in cx.assignments() {
+            if !line.section.is_empty() || !is_bind_keyword(&a.key) {
+                continue;
+            }
+            let Ok(bind) = parse_bind(&a.key, &a.value) else { continue };
+            let submap = line.submap.as_deref();
+
+            // An `unbind` frees the key for the binds after it
+            if bind.unbind {
+                let trigger = bind.trigger(variables).ok();
+                active.retain(|(s, b, ..)| !(*s == submap && b.trigger(variables).ok() == trigger));
+                continue;
+            }
+
+            let first = active.iter().find(|(s, b, ..)| *s == submap && b.conflicts_with(&bind, variables));
+            if let Some((_, _, first_file, first_line)) = first {
+                let value = value_span(line);
+                let trigger_end = line.raw[value.clone()].match_indices(',').nth(1).map_or(value.end, |(at, _)| value.start + at);
+                let trigger = if bind.modifiers.is_empty() {
+                    bind.key.to_string()
+                } else {
+                    format!("{}, {}", bind.modifiers, bind.key)
+                };
+                let message = match submap {
+                    Some(submap) => format!("`{}` is already bound in submap `{}`", trigger, submap),
+                    None => format!("`{}` is already bound", trigger),
+                };
+                diagnostics.push(cx.report(self, file, line, value.start..trigger_end, message)
+                    .with_suggestion(format!("first bound at {}:{}", first_file.display(), first_line.number)));
+            }
+            active.push((submap, bind, file, line));
+        }
+        diagnostics
+    }
+}
+
+/// An option set more than once, where only the last value takes effect
+pub struct DuplicateOption;
+
+impl Lint for DuplicateOption {
+    fn id(&self) -> &'static str {
+        "duplicate-option"
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::Warning
+    }
+
+    fn description(&self) -> &'static str {
+        "an option is set more than once and only the last value is used"
+    }
+
+    fn check(&self, cx: &LintContext) -> Vec<Diagnostic> {
+        let mut sites: IndexMap<String, Vec<(&Path, &Line)>> = IndexMap::new();
+        for (file, line, _) in cx.assignments() {
+            let Some(path) = line.path() else { continue };
+            if schema::lookup(&path).is_some() {
+                sites.entry(path).or_default().push((file, line));
+            }
+        }
+
+        let mut diagnostics = Vec::new();
+        for (path, sites) in sites.iter().filter(|(_, sites)| sites.len() > 1) {
+            let (last_file, last_line) = sites[sites.len() - 1];
+            for (file, line) in &sites[..sites.len() - 1] {
+                let message = format!("`{}` is set again later, so this value is ignored", path);
+                diagnostics.push(cx.report(self, file, line, key_span(line), message)
+                    .with_suggestion(format!("the value used is at {}:{}", last_file.display(), last_line.number)));
+            }
+        }
+        diagnostics
+    }
+}
+
+/// A `$variable` referenced without being defined first
+pub struct UndefinedVariable;
+
+impl Lint for UndefinedVariable {
+    fn id(&self) -> &'static str {
+        "undefined-variable"
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::Warning
+    }
+
+    fn description(&self) -> &'static str {
+        "a `$variable` is used before it is defined, or never defined"
+    }
+
+    fn check(&self, cx: &LintContext) -> Vec<Diagnostic> {
+        cx.variables.undefined().iter().filter_map(|site| {
+            let line = cx.sources.line(&site.file, site.line)?;
+            let reference = format!("${}", site.name);
+            let message = format!("undefined variable `{}`", reference);
+            Some(cx.report(self, &site.file, line, span_of(line, &reference), message)
+                .with_suggestion(format!("define `{} = ...` before this line", reference)))
+        }).collect()
+    }
+}
+
+/// A `$variable` that is defined but never used
+pub struct UnusedVariable;
+
+impl Lint for UnusedVariable {
+    fn id(&self) -> &'static str {
+        "unused-variable"
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::Warning
+    }
+
+    fn description(&self) -> &'static str {
+        "a `$variable` is defined but never used"
+    }
+
+    fn check(&self, cx: &LintContext) -> Vec<Diagnostic> {
+        cx.variables.unused().into_iter().filter_map(|site| {
+            let line = cx.sources.line(&site.file, site.line)?;
+            let message = format!("unused variable `${}`", site.name);
+            Some(cx.report(self, &site.file, line, key_span(line), message))
+        }).collect()
+    }
+}
+
+/// A workspace rule bound to a monitor that no `monitor` line configures
+pub struct UndefinedMonitor;
+
+impl Lint for UndefinedMonitor {
+    fn id(&self) -> &'static str {
+        "undefined-monitor"
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::Warning
+    }
+
+    fn description(&self) -> &'static str {
+        "a workspace rule names a monitor that has no `monitor` line"
+    }
+
+    fn check(&self, cx: &LintContext) -> Vec<Diagnostic> {
+        let mut monitors: Vec<String> = cx.keyword("monitor")
+            .filter_map(|(_, _, a)| cx.variables.resolve(&a.value).split(',').next().map(|name| name.trim().to_string()))
+            .collect();
+        monitors.extend(cx.assignments()
+            .filter(|(_, line, a)| line.section.first().map(String::as_str) == Some("monitorv2") && a.key == "output")
+            .map(|(_, _, a)| cx.variables.resolve(&a.value).trim().to_string()));
+
+        let mut diagnostics = Vec::new();
+        for (file, line, a) in cx.keyword("workspace") {
+            let Ok(rule) = parse_workspace_rule(&cx.variables.resolve(&a.value)) else { continue };
+            let Some(monitor) = rule.rules.get("monitor") else { continue };
+            if monitors.iter().any(|name| name == monitor) {
+                continue;
+            }
+            let message = format!("workspace `{}` is bound to monitor `{}`, which has no `monitor` line", rule.workspace, monitor);
+            diagnostics.push(cx.report(self, file, line, span_of(line, monitor), message)
+                .with_suggestion(format!("add `monitor = {}, preferred, auto, 1`", monitor)));
+        }
+        diagnostics
+    }
+}
+
+/// An `exec` line whose program is not installed
+pub struct ExecNotFound;
+
+impl Lint for ExecNotFound {
+    fn id(&self) -> &'static str {
+        "exec-not-found"
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::Warning
+    }
+
+    fn description(&self) -> &'static str {
+        "an `exec` or `exec-once` program is not found on `$PATH`"
+    }
+
+    fn check(&self, cx: &LintContext) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+        for (file, line, a) in cx.assignments() {
+            if !line.section.is_empty() || ExecKind::from_keyword(&a.key).is_none() {
+                continue;
+            }
+            let Ok(command) = parse_exec(&a.key, &cx.variables.resolve(&a.value)) else { continue };
+            let Some(program) = program(&command.command) else { continue };
+            if cx.is_program(program) {
+                continue;
+            }
+            let message = if program.contains('/') {
+                format!("`{}` does not exist or is not executable", program)
+            } else {
+                format!("`{}` was not found on $PATH", program)
+            };
+            diagnostics.push(cx.report(self, file, line, span_of(line, program), message));
+        }
+        diagnostics
+    }
+}
+
+/// The program a shell command starts, when it can be told without running a shell
+fn program(command: &str) -> Option<&str> {
+    let is_env_assignment = |word: &str| {
+        word.split_once('=').is_some_and(|(name, _)| {
+            !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        })
+    };
+    let word = command.split_whitespace().find(|word| !is_env_assignment(word))?;
+    let word = word.trim_matches(|c| c == '"' || c == '\'');
+    let unknown = word.is_empty() || word.contains('$') || word.contains('`') || SHELL_WORDS.contains(&word);
+    (!unknown).then_some(word)
+}
+
+/// A window rule matcher or layer rule namespace whose regex does not compile
+pub struct InvalidRuleRegex;
+
+impl InvalidRuleRegex {
+    fn report(&self, cx: &LintContext, file: &Path, line: &Line, pattern: &str, error: &regex::Error) -> Diagnostic {
+        // The regex crate explains the problem on its last line
+        let reason = error.to_string().lines().last().unwrap_or_default()
+            .trim().trim_start_matches("error: ").to_string();
+        let message = format!("`{}` is not a valid regex", pattern);
+        cx.report(self, file, line, span_of(line, pattern), message).with_suggestion(reason)
+    }
+}
+
+impl Lint for InvalidRuleRegex {
+    fn id(&self) -> &'static str {
+        "invalid-rule-regex"
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::Error
+    }
+
+    fn description(&self) -> &'static str {
+        "a window rule matcher or layer rule namespace is not a valid regex"
+    }
+
+    fn check(&self, cx: &LintContext) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+        for (file, line, a) in cx.assignments() {
+            let value = cx.variables.resolve(&a.value);
+            if a.key == "layerrule" && line.section.is_empty() {
+                let Ok(rule) = parse_layer_rule(&value) else { continue };
+                if let Some(Err(error)) = rule.namespace_regex() {
+                    diagnostics.push(self.report(cx, file, line, &rule.namespace, &error));
+                }
+                continue;
+            }
+
+            let in_block = line.section.first().is_some_and(|s| is_record_block(s) && s.starts_with("windowrule"));
+            let matchers = match a.key.as_str() {
+                "windowrule" if line.section.is_empty() => parse_window_rule(&value).map(|r| r.matchers).unwrap_or_default(),
+                "windowrulev2" if line.section.is_empty() => parse_window_rule_v2(&value).map(|r| r.matchers).unwrap_or_default(),
+                key if in_block => key.strip_prefix("match:")
+                    .and_then(|key| WindowRuleMatcher::from_parts(key, &value))
+                    .into_iter()
+                    .collect(),
+                _ => continue,
+            };
+
+            for matcher in &matchers {
+                let Some(pattern) = pattern(matcher) else { continue };
+                let Err(error) = regex::Regex::new(pattern) else { continue };
+                diagnostics.push(self.report(cx, file, line, pattern, &error));
+            }
+        }
+        diagnostics
+    }
+}
+
+/// The regex of a matcher that takes one, without a `negative:` prefix
+fn pattern(matcher: &WindowRuleMatcher) -> Option<&str> {
+    match matcher {
+        WindowRuleMatcher::Class(regex)
+        | WindowRuleMatcher::Title(regex)
+        | WindowRuleMatcher::InitialClass(regex)
+        | WindowRuleMatcher::InitialTitle(regex)
+        | WindowRuleMatcher::Tag(regex)
+        | WindowRuleMatcher::XdgTag(regex)
+        | WindowRuleMatcher::Window(regex) => Some(regex.strip_prefix("negative:").unwrap_or(regex)),
+        _ => None,
+    }
+}
+
+/// Tearing allowed while no window is marked to use it
+pub struct TearingWithoutImmediate;
+
+impl Lint for TearingWithoutImmediate {
+    fn id(&self) -> &'static str {
+        "tearing-without-immediate"
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::Warning
+    }
+
+    fn description(&self) -> &'static str {
+        "`general:allow_tearing` is on but no window rule uses `immediate`"
+    }
+
+    fn check(&self, cx: &LintContext) -> Vec<Diagnostic> {
+        let immediate = cx.config.window_rules.iter().any(|rule| rule.effects.contains(&WindowRuleEffect::Immediate));
+        if !cx.config.general.allow_tearing || immediate {
+            return Vec::new();
+        }
+        let setting = cx.assignments().filter(|(_, line, _)| line.path().as_deref() == Some("general:allow_tearing")).last();
+        let Some((file, line, _)) = setting else { return Vec::new() };
+        let message = "`general:allow_tearing` is on, but no window rule uses `immediate`";
+        vec![cx.report(self, file, line, key_span(line), message)
+            .with_suggestion("tearing only applies to windows with the `immediate` rule")]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `(lint, line)` of every finding
+    fn findings(config: &str) -> Vec<(&'static str, usize)> {
+        run(&SourceTree::from_string(config), &lints(), &[]).iter()
+            .map(|d| (d.code.unwrap(), d.line))
+            .collect()
+    }
+
+    #[test]
+    fn each_lint_fires() {
+        let cases = [
+            ("duplicate-bind", "bind = SUPER, Q, exec, kitty\nbind = SUPER, q, killactive\n", 2),
+            ("duplicate-option", "general {\n    gaps_in = 5\n}\ngeneral:gaps_in = 8\n", 2),
+            ("undefined-variable", "bind = $mainMod, Q, killactive\n", 1),
+            ("unused-variable", "$terminal = kitty\n", 1),
+            ("undefined-monitor", "workspace = 1, monitor:DP-1\n", 1),
+            ("exec-not-found", "exec-once = /nonexistent/bin/waybar\n", 1),
+            ("invalid-rule-regex", "windowrulev2 = float, class:^(kitty$\n", 1),
+            ("invalid-rule-regex", "layerrule = blur, waybar(\n", 1),
+            ("tearing-without-immediate", "general {\n    allow_tearing = true\n}\n", 2),
+        ];
+        for (lint, config, line) in cases {
+            assert_eq!(findings(config), [(lint, line)], "{}", config);
+        }
+    }
+
+    #[test]
+    fn each_lint_is_suppressed_by_an_allow_comment() {
+        let cases = [
+            "bind = SUPER, Q, exec, kitty\nbind = SUPER, q, killactive # hyprconf: allow(duplicate-bind)\n",
+            "general {\n    # hyprconf: allow(duplicate-option)\n    gaps_in = 5\n}\ngeneral:gaps_in = 8\n",
+            "bind = $mainMod, Q, killactive # hyprconf: allow(undefined-variable)\n",
+            "# hyprconf: allow(unused-variable)\n$terminal = kitty\n",
+            "workspace = 1, monitor:DP-1 # hyprconf: allow(undefined-monitor)\n",
+            "exec-once = /nonexistent/bin/waybar # hyprconf: allow(exec-not-found)\n",
+            "windowrulev2 = float, class:^(kitty$ # hyprconf: allow(invalid-rule-regex)\n",
+            "# hyprconf: allow(invalid-rule-regex)\nlayerrule = blur, waybar(\n",
+            "general {\n    allow_tearing = true # hyprconf: allow(tearing-without-immediate)\n}\n",
+        ];
+        for config in cases {
+            assert_eq!(findings(config), [], "{}", config);
+        }
+    }
+
+    #[test]
+    fn lints_can_be_turned_off_by_id() {
+        let sources = SourceTree::from_string("$a = 1\n$b = 2\n");
+        assert_eq!(run(&sources, &lints(), &[]).len(), 2);
+        assert!(run(&sources, &lints(), &["unused-variable".to_string()]).is_empty());
+    }
+
+    #[test]
+    fn allow_comments_only_reach_the_next_line() {
+        assert_eq!(findings("# hyprconf: allow(unused-variable)\n\n$terminal = kitty\n"), [("unused-variable", 3)]);
+        assert_eq!(findings("$terminal = kitty # hyprconf: allow(duplicate-bind)\n"), [("unused-variable", 1)]);
+        assert_eq!(allowed_lints(" hyprconf: allow(a, b ,)"), ["a", "b"]);
+        assert!(allowed_lints(" hyprconf allow(a)").is_empty());
+    }
+
+    #[test]
+    fn clean_configs_have_no_findings() {
+        let config = "\
+$mainMod = SUPER
+monitor = DP-1, preferred, auto, 1
+workspace = 1, monitor:DP-1
+bind = $mainMod, Q, killactive
+bind = $mainMod SHIFT, Q, exit
+unbind = $mainMod, Q
+bind = $mainMod, Q, exec, kitty
+windowrulev2 = immediate, class:^(cs2)$
+layerrule = blur, address:0x1234
+general:allow_tearing = true
+";
+        assert_eq!(findings(config), []);
+    }
+
+    #[test]
+    fn regex_findings_point_at_the_pattern() {
+        let sources = SourceTree::from_string("layerrule = blur, waybar(\n");
+        let diagnostics = run(&sources, &lints(), &[]);
+        assert_eq!(diagnostics[0].message, "`waybar(` is not a valid regex");
+        assert_eq!(diagnostics[0].column, 19);
+        assert_eq!(diagnostics[0].severity, Severity::Error);
+    }
+
+    #[test]
+    fn programs_are_found_past_env_assignments() {
+        assert_eq!(program("FOO=1 BAR=2 waybar --config x"), Some("waybar"));
+        assert_eq!(program("\"kitty\" -e htop"), Some("kitty"));
+        assert_eq!(program("$terminal"), None);
+        assert_eq!(program("cd ~ && kitty"), None);
+    }
+}
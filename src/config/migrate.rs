@@ -0,0 +1,398 @@
+//! Rewrites options Hyprland has renamed or replaced into their current form.
+//!
+//! Migrations work on the parsed model: deprecated options are taken out of the
+//! section's `extra` map and set again under their new path, so saving through
+//! `ConfigManager` only touches the lines that moved.
+
+use std::fmt;
+use std::str::FromStr;
+use anyhow::{Result, anyhow};
+use indexmap::IndexMap;
+use log::debug;
+
+use crate::config::models::core::HyprlandConfig;
+use crate::config::models::gestures::{Gesture, GestureAction, GestureDirection};
+use crate::config::models::rules::{WindowRule, WindowRuleEffect, WindowRuleMatcher, WindowRuleSyntax, WorkspaceRule};
+use crate::config::parser::ConfigParser;
+
+/// A Hyprland release, e.g. `0.45.0`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Version {
+    pub major: u32,
+    pub minor: u32,
+    pub patch: u32,
+}
+
+impl Version {
+    /// The newest version any migration targets
+    pub fn latest() -> Self {
+        RENAMES.iter().map(|r| r.version)
+            .chain([NO_GAPS_WHEN_ONLY, WINDOWRULE_V2, WINDOWRULE_BLOCK, GESTURE_LINES])
+            .filter_map(|v| v.parse().ok())
+            .max()
+            .unwrap_or(Self { major: 0, minor: 0, patch: 0 })
+    }
+}
+
+impl FromStr for Version {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let trimmed = s.trim().trim_start_matches('v');
+        let mut parts = trimmed.split('.').map(|p| p.parse::<u32>());
+        let mut next = || match parts.next() {
+            Some(Ok(n)) => Ok(n),
+            Some(Err(_)) => Err(anyhow!("invalid version `{}`: expected e.g. `0.45.0`", s)),
+            None => Ok(0),
+        };
+        let version = Self { major: next()?, minor: next()?, patch: next()? };
+        if next()? != 0 || trimmed.is_empty() {
+            return Err(anyhow!("invalid version `{}`: expected e.g. `0.45.0`", s));
+        }
+        Ok(version)
+    }
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+/// One rewrite made, or needed, by a migration
+#[derive(Debug, Clone, PartialEq)]
+pub struct Change {
+    /// Hyprland version that made the old form obsolete
+    pub version: &'static str,
+    /// The old form, e.g. `decoration:drop_shadow = true`
+    pub before: String,
+    /// What replaced it; empty when the option was dropped without a replacement
+    pub after: Vec<String>,
+    /// Set when the change could not be made automatically
+    pub manual: Option<String>,
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{}] {}", self.version, self.before)?;
+        if let Some(reason) = &self.manual {
+            return write!(f, ": needs manual migration, {}", reason);
+        }
+        match self.after.as_slice() {
+            [] => write!(f, " -> removed"),
+            [one] => write!(f, " -> {}", one),
+            many => {
+                write!(f, " ->")?;
+                for line in many {
+                    write!(f, "\n    {}", line)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// An option that moved to a new path, possibly with a different value format
+struct Rename {
+    version: &'static str,
+    from: &'static str,
+    to: &'static str,
+    convert: fn(&str) -> Option<String>,
+}
+
+const fn rename(version: &'static str, from: &'static str, to: &'static str) -> Rename {
+    Rename { version, from, to, convert: same }
+}
+
+fn same(value: &str) -> Option<String> {
+    Some(value.to_string())
+}
+
+/// `new_is_master = true` meant what `new_status = master` means now
+fn new_status(value: &str) -> Option<String> {
+    let is_master = crate::config::utils::parse_bool_value("new_is_master", value).ok()?;
+    Some(if is_master { "master" } else { "slave" }.to_string())
+}
+
+/// `no_direct_scanout` was the inverse of today's `direct_scanout`
+fn direct_scanout(value: &str) -> Option<String> {
+    let disabled = crate::config::utils::parse_bool_value("no_direct_scanout", value).ok()?;
+    Some(if disabled { "0" } else { "1" }.to_string())
+}
+
+/// Old shadow offsets were also written as `[x, y]`
+fn offset(value: &str) -> Option<String> {
+    let cleaned = value.trim().trim_start_matches('[').trim_end_matches(']').replace(',', " ");
+    Some(cleaned.split_whitespace().collect::<Vec<_>>().join(" "))
+}
+
+const RENAMES: &[Rename] = &[
+    rename("0.37.0", "general:cursor_inactive_timeout", "cursor:inactive_timeout"),
+    rename("0.37.0", "general:no_cursor_warps", "cursor:no_warps"),
+    rename("0.37.0", "misc:hide_cursor_on_touch", "cursor:hide_on_touch"),
+    Rename { version: "0.41.0", from: "master:new_is_master", to: "master:new_status", convert: new_status },
+    Rename { version: "0.42.0", from: "misc:no_direct_scanout", to: "render:direct_scanout", convert: direct_scanout },
+    rename("0.45.0", "decoration:drop_shadow", "decoration:shadow:enabled"),
+    rename("0.45.0", "decoration:shadow_range", "decoration:shadow:range"),
+    rename("0.45.0", "decoration:shadow_render_power", "decoration:shadow:render_power"),
+    rename("0.45.0", "decoration:shadow_ignore_window", "decoration:shadow:ignore_window"),
+    rename("0.45.0", "decoration:col.shadow", "decoration:shadow:color"),
+    rename("0.45.0", "decoration:col.shadow_inactive", "decoration:shadow:color_inactive"),
+    Rename { version: "0.45.0", from: "decoration:shadow_offset", to: "decoration:shadow:offset", convert: offset },
+    rename("0.45.0", "decoration:shadow_scale", "decoration:shadow:scale"),
+];
+
+/// `no_gaps_when_only` was replaced by workspace and window rules
+const NO_GAPS_WHEN_ONLY: &str = "0.45.0";
+/// Legacy `windowrule = RULE, WINDOW` lines were dropped for `windowrulev2`
+const WINDOWRULE_V2: &str = "0.48.0";
+/// Line window rules were replaced by `windowrule { }` blocks
+const WINDOWRULE_BLOCK: &str = "0.53.0";
+/// `gestures:workspace_swipe` was replaced by `gesture =` lines
+const GESTURE_LINES: &str = "0.51.0";
+
+/// Rewrite everything in `config` that is obsolete as of Hyprland `target`
+pub fn migrate(config: &mut HyprlandConfig, target: Version) -> Vec<Change> {
+    let applies = |version: &str| version.parse::<Version>().is_ok_and(|v| v <= target);
+    let mut changes = Vec::new();
+
+    for rule in RENAMES.iter().filter(|r| applies(r.version)) {
+        if let Some(change) = migrate_rename(config, rule) {
+            changes.push(change);
+        }
+    }
+    if applies(NO_GAPS_WHEN_ONLY) {
+        changes.extend(migrate_no_gaps_when_only(config));
+    }
+    if applies(WINDOWRULE_V2) {
+        changes.extend(migrate_window_rules(config, WindowRuleSyntax::V1, WindowRuleSyntax::V2, WINDOWRULE_V2));
+    }
+    if applies(WINDOWRULE_BLOCK) {
+        changes.extend(migrate_window_rules(config, WindowRuleSyntax::V2, WindowRuleSyntax::Block, WINDOWRULE_BLOCK));
+    }
+    if applies(GESTURE_LINES) && config.gestures.workspace_swipe {
+        changes.push(migrate_workspace_swipe(config));
+    }
+
+    changes.sort_by_key(|c| c.version.parse::<Version>().ok());
+    debug!("Migration to {} made {} change(s)", target, changes.len());
+    changes
+}
+
+/// Remove an unmodelled option from its section, returning its value
+fn take_extra(config: &mut HyprlandConfig, path: &str) -> Option<String> {
+    let (section, key) = path.rsplit_once(':')?;
+    config.extra_options_mut(section)?.shift_remove(key)
+}
+
+fn migrate_rename(config: &mut HyprlandConfig, rule: &Rename) -> Option<Change> {
+    let value = take_extra(config, rule.from)?;
+    let before = format!("{} = {}", rule.from, value);
+
+    let converted = (rule.convert)(&value)
+        .ok_or_else(|| anyhow!("cannot convert `{}`", value))
+        .and_then(|new| ConfigParser::apply_option(config, rule.to, &new).map(|_| new));
+    match converted {
+        Ok(new) => Some(Change {
+            version: rule.version,
+            before,
+            after: vec![format!("{} = {}", rule.to, new)],
+            manual: None,
+        }),
+        Err(e) => {
+            // Put the option back so nothing is lost
+            let (section, key) = rule.from.rsplit_once(':')?;
+            config.extra_options_mut(section)?.insert(key.to_string(), value);
+            Some(Change { version: rule.version, before, after: Vec::new(), manual: Some(format!("{:#}", e)) })
+        }
+    }
+}
+
+fn migrate_no_gaps_when_only(config: &mut HyprlandConfig) -> Vec<Change> {
+    let mut changes = Vec::new();
+    let mut enabled = false;
+    for path in ["dwindle:no_gaps_when_only", "master:no_gaps_when_only"] {
+        let Some(value) = take_extra(config, path) else { continue };
+        let value_enabled = value.trim() != "0";
+        changes.push(Change {
+            version: NO_GAPS_WHEN_ONLY,
+            before: format!("{} = {}", path, value),
+            after: Vec::new(),
+            manual: None,
+        });
+        enabled |= value_enabled;
+    }
+    if !enabled {
+        return changes;
+    }
+
+    // The replacement the Hyprland wiki gives for "smart gaps"
+    let mut added = Vec::new();
+    for workspace in ["w[tv1]", "f[1]"] {
+        if config.workspace_rules.iter().any(|r| r.workspace == workspace) {
+            continue;
+        }
+        let rules = IndexMap::from([
+            ("gapsout".to_string(), "0".to_string()),
+            ("gapsin".to_string(), "0".to_string()),
+        ]);
+        added.push(format!("workspace = {}, gapsout:0, gapsin:0", workspace));
+        config.workspace_rules.push(WorkspaceRule { workspace: workspace.to_string(), rules });
+
+        for effect in [WindowRuleEffect::BorderSize("0".to_string()), WindowRuleEffect::Rounding("0".to_string())] {
+            added.push(format!("windowrulev2 = {}, floating:0, onworkspace:{}", effect, workspace));
+            config.window_rules.push(WindowRule {
+                syntax: WindowRuleSyntax::V2,
+                name: None,
+                effects: vec![effect],
+                matchers: vec![WindowRuleMatcher::Floating(false), WindowRuleMatcher::OnWorkspace(workspace.to_string())],
+            });
+        }
+    }
+    if let Some(last) = changes.last_mut() {
+        last.after = added;
+    }
+    changes
+}
+
+fn migrate_workspace_swipe(config: &mut HyprlandConfig) -> Change {
+    let gestures = &mut config.gestures;
+    let gesture = Gesture {
+        fingers: gestures.workspace_swipe_fingers.max(0) as u32,
+        direction: GestureDirection::Horizontal,
+        modifiers: None,
+        scale: None,
+        action: GestureAction::Workspace,
+    };
+    let after = vec![format!("gesture = {}", gesture)];
+    gestures.workspace_swipe = false;
+    gestures.gestures.push(gesture);
+    Change {
+        version: GESTURE_LINES,
+        before: "gestures:workspace_swipe = true".to_string(),
+        after,
+        manual: None,
+    }
+}
+
+fn migrate_window_rules(
+    config: &mut HyprlandConfig,
+    from: WindowRuleSyntax,
+    to: WindowRuleSyntax,
+    version: &'static str,
+) -> Vec<Change> {
+    let mut changes = Vec::new();
+    let mut named = config.window_rules.iter().filter(|r| r.name.is_some()).count();
+    for rule in config.window_rules.iter_mut().filter(|r| r.syntax == from) {
+        let before = render_rule(rule);
+        for matcher in &mut rule.matchers {
+            if let WindowRuleMatcher::Window(regex) = matcher {
+                *matcher = WindowRuleMatcher::Class(std::mem::take(regex));
+            }
+        }
+        rule.syntax = to;
+        if to == WindowRuleSyntax::Block && rule.name.is_none() {
+            named += 1;
+            rule.name = Some(format!("migrated-{}", named));
+        }
+        changes.push(Change { version, before, after: vec![render_rule(rule)], manual: None });
+    }
+    changes
+}
+
+/// A window rule as it is written in the config, on one line
+fn render_rule(rule: &WindowRule) -> String {
+    let mut buffer = Vec::new();
+    let comment_style = crate::config::utils::CommentStyle::Hash;
+    if crate::config::models::rules::write_window_rule(&mut buffer, rule, &comment_style).is_err() {
+        return String::new();
+    }
+    let text = String::from_utf8_lossy(&buffer);
+    let lines: Vec<&str> = text.lines().map(str::trim).filter(|l| !l.is_empty()).collect();
+    match lines.as_slice() {
+        [open, body @ .., close] if rule.syntax == WindowRuleSyntax::Block => {
+            format!("{} {} {}", open, body.join("; "), close)
+        }
+        _ => lines.join("; "),
+    }
+}
+
+/// Parse the `--to` argument of `hyprconf migrate`, defaulting to the newest known version
+pub fn target_version(to: Option<&str>) -> Result<Version> {
+    match to {
+        Some(version) => version.parse(),
+        None => Ok(Version::latest()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ConfigManager;
+
+    fn migrated(content: &str, target: &str) -> (String, Vec<Change>) {
+        let mut manager = ConfigManager::from_string(content).unwrap();
+        let changes = migrate(manager.get_config_mut(), target.parse().unwrap());
+        (manager.generate_content().unwrap(), changes)
+    }
+
+    #[test]
+    fn versions_parse_and_order() {
+        assert_eq!("v0.45".parse::<Version>().unwrap(), Version { major: 0, minor: 45, patch: 0 });
+        assert!("0.45.0".parse::<Version>().unwrap() < "0.48.0".parse().unwrap());
+        assert!("0.45.x".parse::<Version>().is_err());
+        assert!("0.45.0.1".parse::<Version>().is_err());
+        assert_eq!(Version::latest().to_string(), WINDOWRULE_BLOCK);
+    }
+
+    #[test]
+    fn renamed_options_move_to_their_new_path() {
+        let (content, changes) = migrated(
+            "decoration {\n    rounding = 4\n    shadow_range = 8\n}\n\nmaster {\n    new_is_master = true\n}\n",
+            "0.45.0",
+        );
+        assert_eq!(content, "decoration {\n    rounding = 4\n    shadow {\n        range = 8\n    }\n}\n\nmaster {\n    new_status = master\n}\n");
+        assert_eq!(changes.len(), 2);
+        assert!(changes.iter().all(|c| c.manual.is_none()));
+    }
+
+    #[test]
+    fn renames_leave_no_empty_blocks() {
+        let (content, _) = migrated("general {\n    no_cursor_warps = true\n}\n", "0.45.0");
+        assert_eq!(content, "cursor {\n    no_warps = yes\n}\n");
+    }
+
+    #[test]
+    fn older_targets_keep_newer_forms() {
+        let content = "decoration {\n    drop_shadow = false\n}\n";
+        let (migrated_content, changes) = migrated(content, "0.44.0");
+        assert_eq!(migrated_content, content);
+        assert!(changes.is_empty());
+    }
+
+    #[test]
+    fn no_gaps_when_only_becomes_workspace_and_window_rules() {
+        let (content, changes) = migrated("dwindle {\n    no_gaps_when_only = 1\n}\n", "0.45.0");
+        assert!(!content.contains("dwindle"));
+        assert!(content.contains("workspace = w[tv1], gapsout:0, gapsin:0\n"));
+        assert!(content.contains("windowrulev2 = bordersize 0, floating:0, onworkspace:f[1]\n"));
+        assert_eq!(changes[0].after.len(), 6);
+    }
+
+    #[test]
+    fn legacy_window_rules_become_v2_then_blocks() {
+        let (content, _) = migrated("windowrule = float, ^(kitty)$\n", "0.48.0");
+        assert_eq!(content, "windowrulev2 = float, class:^(kitty)$\n");
+
+        let (content, changes) = migrated("windowrule = float, ^(kitty)$\n", "0.53.0");
+        assert!(content.contains("windowrule {\n    name = migrated-1\n    match:class = ^(kitty)$\n    float = on\n}\n"));
+        assert_eq!(changes.iter().map(|c| c.version).collect::<Vec<_>>(), [WINDOWRULE_V2, WINDOWRULE_BLOCK]);
+    }
+
+    #[test]
+    fn workspace_swipe_becomes_a_gesture_line() {
+        let (content, changes) = migrated("gestures {\n    workspace_swipe = true\n    workspace_swipe_fingers = 4\n}\n", "0.51.0");
+        assert_eq!(content, "gesture = 4, horizontal, workspace\n");
+        assert_eq!(changes[0].after, ["gesture = 4, horizontal, workspace"]);
+    }
+}
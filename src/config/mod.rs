@@ -1,91 +1,326 @@
+pub mod diff;
+pub mod export;
+pub mod format;
+pub mod lint;
+pub mod migrate;
 pub mod models;
 pub mod parser;
+pub mod schema;
 pub mod utils;
 
+use std::fs;
 use std::path::Path;
-use std::io::{self, Write};
-use std::fs::File;
+use std::io;
 use std::string::ToString;
 
+use parser::cst::Document;
+use parser::source::SourceTree;
+use parser::variables::Variables;
+
 /// Manages loading, editing, and saving a Hyprland configuration
 pub struct ConfigManager {
     config: models::core::HyprlandConfig,
     current_path: Option<String>,
+    /// Files as loaded, so saving only rewrites the lines that changed
+    sources: Option<SourceTree>,
+    /// Model as parsed from `sources`, used to work out what was edited
+    baseline: models::core::HyprlandConfig,
+    /// Which options are written when the config is rendered from scratch
+    write_mode: utils::WriteMode,
 }
 
 impl ConfigManager {
-    /// Creates a new, empty manager with default config
+    /// Creates a new manager holding Hyprland's defaults
     pub fn new() -> Self {
         Self {
-            config: models::core::HyprlandConfig::default(),
+            config: parser::ConfigParser::defaults(),
             current_path: None,
+            sources: None,
+            baseline: parser::ConfigParser::defaults(),
+            write_mode: utils::WriteMode::default(),
         }
     }
-    
-    /// Loads config from a file path
+
+    /// Loads config from a file path, along with every file it sources
     pub fn from_file<P: AsRef<Path>>(path: P) -> io::Result<Self> {
-        let config = parser::ConfigParser::parse_file(&path)
-            .map_err(|e| io::Error::new(io::ErrorKind::Other, e.to_string()))?;
-        
-        Ok(Self {
-            config,
-            current_path: Some(path.as_ref().to_string_lossy().into_owned()),
-        })
+        let sources = SourceTree::load(&path)
+            .map_err(|e| io::Error::other(format!("{:#}", e)))?;
+        let mut manager = Self::from_sources(sources)?;
+        manager.current_path = Some(path.as_ref().to_string_lossy().into_owned());
+        Ok(manager)
     }
-    
+
     /// Loads config from a raw string
     pub fn from_string(content: &str) -> io::Result<Self> {
-        let config = parser::ConfigParser::parse_string(content)
-            .map_err(|e| io::Error::new(io::ErrorKind::Other, e.to_string()))?;
-        
+        Self::from_sources(SourceTree::from_string(content))
+    }
+
+    fn from_sources(sources: SourceTree) -> io::Result<Self> {
+        let config = parser::ConfigParser::parse_sources(&sources)
+            .map_err(|e| io::Error::other(e.to_string()))?;
+
         Ok(Self {
+            baseline: config.clone(),
             config,
             current_path: None,
+            sources: Some(sources),
+            write_mode: utils::WriteMode::default(),
         })
     }
-    
+
     /// Returns a reference to the current config
     pub fn get_config(&self) -> &models::core::HyprlandConfig {
         &self.config
     }
-    
+
     /// Returns a mutable reference to the current config
     pub fn get_config_mut(&mut self) -> &mut models::core::HyprlandConfig {
         &mut self.config
     }
-    
+
     /// Returns the path from which the config was loaded, if any
     pub fn get_current_path(&self) -> Option<&String> {
         self.current_path.as_ref()
     }
-    
-    /// Saves the config back to the original path
+
+    /// Saves the config back to the original path.
+    ///
+    /// Edits to settings that came from a sourced file are written back to that file.
     pub fn save(&self) -> io::Result<()> {
-        if let Some(path) = &self.current_path {
-            self.save_to(path)
+        let Some(path) = &self.current_path else {
+            return Err(io::Error::other("No path set for saving"));
+        };
+        let sources = self.synced_sources()?;
+        let loaded_from = sources.root().path.as_path();
+        if !loaded_from.as_os_str().is_empty() && fs::canonicalize(path).ok().as_deref() == Some(loaded_from) {
+            sources.save()
         } else {
-            Err(io::Error::new(io::ErrorKind::Other, "No path set for saving"))
+            self.save_to(path)
         }
     }
-    
-    /// Saves the config to a specific file path
+
+    /// Saves the main config file to a specific path; sourced files are not written
     pub fn save_to<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
-        // Use the config's write_to_file method directly
-        self.config.write_to_file(path.as_ref().to_str().unwrap_or(""))
+        fs::write(path, self.generate_content()?)
     }
-    
+
     /// Sets or changes the path for saving
     pub fn set_path<P: AsRef<Path>>(&mut self, path: P) {
         self.current_path = Some(path.as_ref().to_string_lossy().into_owned());
     }
-    
-    /// Generates the config content as a string
+
+    /// Generates the content of the main config file as a string.
+    ///
+    /// When the config was loaded from text, edits are applied to that text so
+    /// comments, ordering and formatting of untouched lines are kept.
     pub fn generate_content(&self) -> io::Result<String> {
-        let mut buffer = Vec::new();
-        let comment_style = utils::CommentStyle::Hash;
-        self.config.write_to(&mut buffer, &comment_style)?;
-        
-        String::from_utf8(buffer)
-            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))
-    }
-}
\ No newline at end of file
+        Ok(self.synced_sources()?.root().document.to_string())
+    }
+
+    /// Sets which options are written for a config that was not loaded from a file.
+    ///
+    /// Loaded configs keep their own lines; only edited options are touched.
+    pub fn set_write_mode(&mut self, mode: utils::WriteMode) {
+        self.write_mode = mode;
+    }
+
+    /// Returns the value of an option by its colon path, e.g. `decoration:blur:size`
+    pub fn get_option(&self, path: &str) -> io::Result<Option<String>> {
+        Ok(self.synced_sources()?.get(path).map(str::to_string))
+    }
+
+    /// Returns the value of an option with `$variable` references expanded
+    pub fn get_option_resolved(&self, path: &str) -> io::Result<Option<String>> {
+        let sources = self.synced_sources()?;
+        let variables = Variables::from_sources(&sources);
+        Ok(sources.get(path).map(|value| variables.resolve(value)))
+    }
+
+    /// Sets an option by its colon path, rewriting only the line that holds it.
+    ///
+    /// The value is checked against the option's type first; the config is left
+    /// untouched if it does not fit.
+    pub fn set_option(&mut self, path: &str, value: &str) -> io::Result<()> {
+        let mut sources = self.synced_sources()?;
+        let resolved = Variables::from_sources(&sources).resolve(value);
+        if !resolved.contains('$') {
+            schema::check_option(path, &resolved)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+        }
+        sources.set(path, value);
+
+        let config = parser::ConfigParser::parse_sources(&sources)
+            .map_err(|e| io::Error::other(e.to_string()))?;
+        self.baseline = config.clone();
+        self.config = config;
+        self.sources = Some(sources);
+        Ok(())
+    }
+
+    /// Returns the value of an option as Hyprland would see it: the config's value with
+    /// `$variables` expanded, or the option's default when the config does not set it
+    pub fn get_option_effective(&self, path: &str) -> io::Result<Option<String>> {
+        match self.get_option_resolved(path)? {
+            Some(value) => Ok(Some(value)),
+            None => Ok(schema::lookup(path).filter(|o| !o.is_removed()).map(|o| o.default.to_string())),
+        }
+    }
+
+    /// Loaded files with any edits made through the model applied to them
+    fn synced_sources(&self) -> io::Result<SourceTree> {
+        match &self.sources {
+            Some(sources) => {
+                let current = Document::parse(&render(&self.config)?);
+                let mut sources = sources.clone();
+                let before = Document::parse(&render(&self.baseline)?);
+                sources.apply_changes(&before, &current);
+                Ok(sources)
+            }
+            None => {
+                let mut buffer = Vec::new();
+                let comment_style = utils::CommentStyle::Hash;
+                self.config.write_styled(&mut buffer, &comment_style, utils::OptionStyle::Block, self.write_mode)?;
+                Ok(SourceTree::from_string(&String::from_utf8_lossy(&buffer)))
+            }
+        }
+    }
+}
+
+/// Render a config in hyprconf's canonical layout
+fn render(config: &models::core::HyprlandConfig) -> io::Result<String> {
+    let mut buffer = Vec::new();
+    let comment_style = utils::CommentStyle::Hash;
+    config.write_to(&mut buffer, &comment_style)?;
+
+    String::from_utf8(buffer)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::models::permissions::PermissionMode;
+
+    const BINDS: &str = "\
+$mainMod = SUPER
+bind = $mainMod, Q, exec, kitty # terminal
+bind = $mainMod, E, exec, thunar # files
+bind = $mainMod SHIFT, M, exit, # quit
+";
+
+    #[test]
+    fn unedited_config_is_written_back_unchanged() {
+        for input in [include_str!("../../docs/hyprland.conf"), include_str!("../../docs/hyprland_mine.conf"), BINDS] {
+            let manager = ConfigManager::from_string(input).unwrap();
+            assert_eq!(manager.generate_content().unwrap(), input);
+        }
+    }
+
+    #[test]
+    fn removing_a_bind_leaves_the_others_as_written() {
+        let mut manager = ConfigManager::from_string(BINDS).unwrap();
+        manager.get_config_mut().binds.keybinds.remove(0);
+        let expected = BINDS.replace("bind = $mainMod, Q, exec, kitty # terminal\n", "");
+        assert_eq!(manager.generate_content().unwrap(), expected);
+    }
+
+    #[test]
+    fn editing_a_bind_keeps_its_comment() {
+        let mut manager = ConfigManager::from_string(BINDS).unwrap();
+        manager.get_config_mut().binds.keybinds[1].arg = "nautilus".to_string();
+        assert_eq!(manager.generate_content().unwrap(), BINDS.replace("thunar # files", "nautilus # files"));
+    }
+
+    #[test]
+    fn lines_that_failed_to_parse_survive_editing_a_sibling() {
+        let input = "\
+bind = SUPER, Q, exec, kitty
+bind = HYPER, W, exec, foo
+bind = SUPER_SHIFT, E, exit,
+gesture = 3, horizontal, workspace
+gesture = 6, swipe, close
+gesture = 4, up, fullscreen
+permission = /bin/x, camera, allow
+permission = /usr/bin/grim, screencopy, allow
+";
+        let mut manager = ConfigManager::from_string(input).unwrap();
+        manager.get_config_mut().binds.keybinds[0].arg = "foot".to_string();
+        manager.get_config_mut().gestures.gestures.remove(1);
+        manager.get_config_mut().permissions[0].mode = PermissionMode::Deny;
+        let expected = input.replace("kitty", "foot")
+            .replace("gesture = 4, up, fullscreen\n", "")
+            .replace("screencopy, allow", "screencopy, deny");
+        assert_eq!(manager.generate_content().unwrap(), expected);
+    }
+
+    #[test]
+    fn added_bind_goes_after_its_neighbour() {
+        let mut manager = ConfigManager::from_string(BINDS).unwrap();
+        let mut bind = manager.get_config().binds.keybinds[0].clone();
+        bind.key = "B".parse().unwrap();
+        bind.arg = "firefox".to_string();
+        manager.get_config_mut().binds.keybinds.insert(1, bind);
+        let expected = BINDS.replace("# terminal\n", "# terminal\nbind = $mainMod, B, exec, firefox\n");
+        assert_eq!(manager.generate_content().unwrap(), expected);
+    }
+
+    #[test]
+    fn edit_in_sourced_file_is_written_there() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().join("hyprland.conf");
+        fs::write(&root, "source = ./looks.conf\ngeneral {\n    gaps_in = 5\n}\n").unwrap();
+        fs::write(dir.path().join("looks.conf"), "decoration {\n    rounding = 4 # soft\n}\n").unwrap();
+
+        let mut manager = ConfigManager::from_file(&root).unwrap();
+        manager.get_config_mut().decoration.rounding = 8;
+        manager.save().unwrap();
+        assert_eq!(fs::read_to_string(dir.path().join("looks.conf")).unwrap(), "decoration {\n    rounding = 8 # soft\n}\n");
+        assert_eq!(fs::read_to_string(&root).unwrap(), "source = ./looks.conf\ngeneral {\n    gaps_in = 5\n}\n");
+    }
+
+    #[test]
+    fn device_edits_go_into_the_block_with_that_name() {
+        let input = "device {\n    name = mouse\n    sensitivity = -0.5\n}\n\ndevice {\n    name = kb\n    kb_layout = us\n}\n";
+        let mut manager = ConfigManager::from_string(input).unwrap();
+        manager.get_config_mut().devices[1].kb_layout = Some("de".to_string());
+        manager.get_config_mut().devices[0].natural_scroll = Some(true);
+        assert_eq!(
+            manager.generate_content().unwrap(),
+            "device {\n    name = mouse\n    sensitivity = -0.5\n    natural_scroll = yes\n}\n\ndevice {\n    name = kb\n    kb_layout = de\n}\n"
+        );
+    }
+
+    #[test]
+    fn set_option_rewrites_only_its_line() {
+        let input = "$gap = 4\ngeneral {\n    gaps_out = 20 # outer\n}\n";
+        let mut manager = ConfigManager::from_string(input).unwrap();
+        manager.set_option("general:gaps_out", "8").unwrap();
+        manager.set_option("decoration:rounding", "$gap").unwrap();
+        assert_eq!(
+            manager.generate_content().unwrap(),
+            "$gap = 4\ngeneral {\n    gaps_out = 8 # outer\n}\n\ndecoration {\n    rounding = $gap\n}\n"
+        );
+        assert_eq!(manager.get_config().decoration.rounding, 4);
+        assert_eq!(manager.get_option("decoration:rounding").unwrap().as_deref(), Some("$gap"));
+        assert_eq!(manager.get_option_resolved("decoration:rounding").unwrap().as_deref(), Some("4"));
+    }
+
+    #[test]
+    fn set_option_rejects_values_that_do_not_fit() {
+        let input = "general {\n    gaps_out = 20\n}\n";
+        let mut manager = ConfigManager::from_string(input).unwrap();
+        for (path, value) in [("general:gaps_out", "wide"), ("general:gapsout", "8"), ("general:no_cursor_warps", "true")] {
+            let error = manager.set_option(path, value).unwrap_err();
+            assert_eq!(error.kind(), io::ErrorKind::InvalidInput, "{}", path);
+        }
+        assert_eq!(manager.generate_content().unwrap(), input);
+    }
+
+    #[test]
+    fn effective_value_falls_back_to_the_default() {
+        let manager = ConfigManager::from_string("general {\n    gaps_out = 20\n}\n").unwrap();
+        assert_eq!(manager.get_option("decoration:blur:size").unwrap(), None);
+        assert_eq!(manager.get_option_effective("decoration:blur:size").unwrap().as_deref(), Some("8"));
+        assert_eq!(manager.get_option_effective("general:gaps_out").unwrap().as_deref(), Some("20"));
+    }
+}
@@ -1,19 +1,22 @@
-use std::collections::HashMap;
 use std::io::{self, Write};
 
 use anyhow::Result;
+use indexmap::IndexMap;
 use log::debug;
 use serde::{Deserialize, Serialize};
 
-use crate::config::utils::{CommentStyle, write_boolean_option, write_option, write_section_header};
-use crate::config::utils::parse_bool;
+use crate::config::utils::{CommentStyle, write_extra_options, write_boolean_option, write_option, write_section_header};
+use crate::config::utils::{parse_bool_value, parse_value};
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct AnimationsSection {
     pub enabled: bool,
     pub first_launch_animation: bool,
-    pub beziers: HashMap<String, String>,
+    pub beziers: IndexMap<String, String>,
     pub animations: Vec<Animation>,
+    /// Options hyprconf has no model for, in file order
+    #[serde(default)]
+    pub extra: IndexMap<String, String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -38,8 +41,8 @@ pub fn parse_animations_section(section: &mut AnimationsSection, content: &str)
             let value = value.trim();
             
             match key {
-                "enabled" => section.enabled = parse_bool(value),
-                "first_launch_animation" => section.first_launch_animation = parse_bool(value),
+                "enabled" => section.enabled = parse_bool_value(key, value)?,
+                "first_launch_animation" => section.first_launch_animation = parse_bool_value(key, value)?,
                 "bezier" => {
                     let parts: Vec<&str> = value.split(',').collect();
                     if parts.len() >= 2 {
@@ -66,7 +69,7 @@ pub fn parse_animations_section(section: &mut AnimationsSection, content: &str)
                                 enabled = false;
                             } else if param.starts_with("speed:") {
                                 if let Some(speed_str) = param.strip_prefix("speed:") {
-                                    speed = speed_str.trim().parse().unwrap_or(10);
+                                    speed = parse_value("speed", speed_str)?;
                                 }
                             } else if param.starts_with("curve:") {
                                 if let Some(curve_str) = param.strip_prefix("curve:") {
@@ -88,7 +91,10 @@ pub fn parse_animations_section(section: &mut AnimationsSection, content: &str)
                         });
                     }
                 },
-                _ => debug!("Unknown animations setting: {}", key),
+                _ => {
+                    debug!("Unknown animations setting: {}", key);
+                    section.extra.insert(key.to_string(), value.to_string());
+                }
             }
         }
     }
@@ -111,7 +117,7 @@ pub fn write_animations_section<W: Write>(
 
     // Write bezier curves
     for (name, curve) in &animations.beziers {
-        write_option(writer, &format!(" bezier = {}", name), curve, None, comment_style)?;
+        write_option(writer, " bezier", &format!("{},{}", name, curve), None, comment_style)?;
     }
 
     // Write animations
@@ -130,6 +136,7 @@ pub fn write_animations_section<W: Write>(
         write_option(writer, " animation", &line, None, comment_style)?;
     }
 
+    write_extra_options(writer, " ", &animations.extra, comment_style)?;
     writeln!(writer, "}}")?;
     Ok(())
 }
\ No newline at end of file
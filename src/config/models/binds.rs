@@ -1,12 +1,18 @@
-use std::collections::HashMap;
+use std::cmp::Ordering;
+use std::fmt;
+use std::hash::{Hash, Hasher};
 use std::io::{self, Write};
+use std::ops::BitOr;
+use std::str::FromStr;
 
 use anyhow::Result;
+use indexmap::IndexMap;
 use log::debug;
 use serde::{Deserialize, Serialize};
 
-use crate::config::utils::parse_bool;
-use crate::config::utils::{CommentStyle, write_boolean_option, write_option, write_section_header};
+use crate::config::parser::variables::expand_with;
+use crate::config::utils::{InvalidValue, parse_bool_value, parse_value};
+use crate::config::utils::{CommentStyle, write_extra_options, write_boolean_option, write_option, write_section_header};
 use crate::config::models::core::ConfigSection;
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -25,80 +31,425 @@ pub struct BindsSection {
     pub window_direction_monitor_fallback: bool,
     pub allow_pin_fullscreen: bool,
     pub keybinds: Vec<KeyBind>,
-    pub submaps: HashMap<String, Vec<KeyBind>>,
+    /// Options hyprconf has no model for, in file order
+    #[serde(default)]
+    pub extra: IndexMap<String, String>,
+}
+
+/// Flag letters Hyprland accepts after `bind`, e.g. `binde`, `bindlr`
+pub const BIND_FLAGS: &str = "lrcgoentimsdp";
+
+/// Modifier keys held for a bind, e.g. `SUPER SHIFT`.
+///
+/// `SUPER_SHIFT`, `SUPER SHIFT` and `SHIFT&SUPER` all read to the same set.
+/// `$variables` are kept by name until [`Modifiers::resolve`] expands them.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(into = "String", try_from = "String")]
+pub struct Modifiers {
+    mask: u8,
+    variables: Vec<String>,
+}
+
+impl Modifiers {
+    pub const NONE: Modifiers = Modifiers::from_bits(0);
+    pub const SHIFT: Modifiers = Modifiers::from_bits(1 << 0);
+    pub const CAPS: Modifiers = Modifiers::from_bits(1 << 1);
+    pub const CTRL: Modifiers = Modifiers::from_bits(1 << 2);
+    pub const ALT: Modifiers = Modifiers::from_bits(1 << 3);
+    pub const MOD2: Modifiers = Modifiers::from_bits(1 << 4);
+    pub const MOD3: Modifiers = Modifiers::from_bits(1 << 5);
+    pub const SUPER: Modifiers = Modifiers::from_bits(1 << 6);
+    pub const MOD5: Modifiers = Modifiers::from_bits(1 << 7);
+
+    pub const fn from_bits(mask: u8) -> Self {
+        Self { mask, variables: Vec::new() }
+    }
+
+    pub fn bits(&self) -> u8 {
+        self.mask
+    }
+
+    /// `$variables` used in place of modifier names, without the `$`
+    pub fn variables(&self) -> &[String] {
+        &self.variables
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.mask == 0 && self.variables.is_empty()
+    }
+
+    /// Whether every modifier in `other` is held here
+    pub fn contains(&self, other: &Modifiers) -> bool {
+        self.mask & other.mask == other.mask && other.variables.iter().all(|v| self.variables.contains(v))
+    }
+
+    /// Expand `$variables` against `variables`, so binds written with them can be compared
+    pub fn resolve(&self, variables: &IndexMap<String, String>) -> Result<Modifiers, InvalidValue> {
+        let mut resolved = Modifiers::from_bits(self.mask);
+        for name in &self.variables {
+            let (value, unresolved) = expand_with(variables, &format!("${}", name));
+            if !unresolved.is_empty() {
+                resolved.variables.push(name.clone());
+                continue;
+            }
+            resolved = resolved | value.parse::<Modifiers>()?;
+        }
+        Ok(resolved)
+    }
+}
+
+/// Names Hyprland accepts for each modifier; the first one is used when writing
+const MODIFIER_NAMES: &[(&str, Modifiers)] = &[
+    ("SUPER", Modifiers::SUPER),
+    ("CTRL", Modifiers::CTRL),
+    ("ALT", Modifiers::ALT),
+    ("SHIFT", Modifiers::SHIFT),
+    ("CAPS", Modifiers::CAPS),
+    ("MOD2", Modifiers::MOD2),
+    ("MOD3", Modifiers::MOD3),
+    ("MOD5", Modifiers::MOD5),
+    ("CONTROL", Modifiers::CTRL),
+    ("WIN", Modifiers::SUPER),
+    ("LOGO", Modifiers::SUPER),
+    ("MOD4", Modifiers::SUPER),
+    ("MOD1", Modifiers::ALT),
+];
+
+impl BitOr for Modifiers {
+    type Output = Modifiers;
+
+    fn bitor(mut self, rhs: Modifiers) -> Modifiers {
+        self.mask |= rhs.mask;
+        for name in rhs.variables {
+            if !self.variables.contains(&name) {
+                self.variables.push(name);
+            }
+        }
+        self
+    }
+}
+
+impl FromStr for Modifiers {
+    type Err = InvalidValue;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = |token: &str| InvalidValue {
+            key: "bind".to_string(),
+            value: token.to_string(),
+            expected: "modifiers such as SUPER, SHIFT, CTRL or ALT",
+        };
+
+        let mut modifiers = Modifiers::NONE;
+        let mut rest = s.trim();
+        while !rest.is_empty() {
+            if let Some(after) = rest.strip_prefix('$') {
+                let len = after.find(|c: char| !(c.is_ascii_alphanumeric() || c == '_')).unwrap_or(after.len());
+                if len == 0 {
+                    return Err(invalid(rest));
+                }
+                modifiers = modifiers | Modifiers { mask: 0, variables: vec![after[..len].to_string()] };
+                rest = &after[len..];
+            } else if rest.starts_with(|c: char| c.is_whitespace() || "_&+|".contains(c)) {
+                rest = &rest[1..];
+            } else {
+                // Names may also be run together, as in `SUPERSHIFT`
+                let upper = rest.to_ascii_uppercase();
+                let (name, modifier) = MODIFIER_NAMES.iter()
+                    .filter(|(name, _)| upper.starts_with(name))
+                    .max_by_key(|(name, _)| name.len())
+                    .ok_or_else(|| {
+                        let end = rest.find(|c: char| c.is_whitespace() || "_&+|$".contains(c)).unwrap_or(rest.len());
+                        invalid(&rest[..end])
+                    })?;
+                modifiers = modifiers | modifier.clone();
+                rest = &rest[name.len()..];
+            }
+        }
+        Ok(modifiers)
+    }
+}
+
+impl fmt::Display for Modifiers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let variables = self.variables.iter().map(|name| format!("${}", name));
+        let names = MODIFIER_NAMES[..8].iter()
+            .filter(|(_, modifier)| self.mask & modifier.mask != 0)
+            .map(|(name, _)| name.to_string());
+        let parts: Vec<String> = variables.chain(names).collect();
+        write!(f, "{}", parts.join(" "))
+    }
+}
+
+impl From<Modifiers> for String {
+    fn from(modifiers: Modifiers) -> String {
+        modifiers.to_string()
+    }
+}
+
+impl TryFrom<String> for Modifiers {
+    type Error = InvalidValue;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+/// Direction of a scroll wheel bind
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum WheelDirection {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+/// The key, button or switch that triggers a bind
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(into = "String", try_from = "String")]
+pub enum Key {
+    /// An xkb keysym name, e.g. `Q`, `Return` or `XF86AudioRaiseVolume`.
+    /// Compared without regard to case, as Hyprland looks them up.
+    Keysym(String),
+    /// A raw keycode, `code:10`
+    Keycode(u32),
+    /// A mouse button, `mouse:272`
+    MouseButton(u32),
+    /// A scroll wheel direction, `mouse_down`
+    MouseWheel(WheelDirection),
+    /// A hardware switch, `switch:Lid Switch`, or `switch:on:Lid Switch` for one state
+    Switch { state: Option<bool>, name: String },
+    /// A `$variable` holding the key, without the `$`
+    Variable(String),
+}
+
+impl Key {
+    /// Expand a `$variable` key against `variables`
+    pub fn resolve(&self, variables: &IndexMap<String, String>) -> Result<Key, InvalidValue> {
+        match self {
+            Key::Variable(name) => match expand_with(variables, &format!("${}", name)) {
+                (value, unresolved) if unresolved.is_empty() => value.parse(),
+                _ => Ok(self.clone()),
+            },
+            _ => Ok(self.clone()),
+        }
+    }
+
+    /// What equality, ordering and hashing look at
+    fn identity(&self) -> (u8, u32, String) {
+        match self {
+            Key::Keysym(name) => (0, 0, name.to_ascii_lowercase()),
+            Key::Keycode(code) => (1, *code, String::new()),
+            Key::MouseButton(button) => (2, *button, String::new()),
+            Key::MouseWheel(direction) => (3, *direction as u32, String::new()),
+            Key::Switch { state, name } => (4, state.map_or(0, |on| 1 + on as u32), name.clone()),
+            Key::Variable(name) => (5, 0, name.clone()),
+        }
+    }
+}
+
+impl PartialEq for Key {
+    fn eq(&self, other: &Self) -> bool {
+        self.identity() == other.identity()
+    }
+}
+
+impl Eq for Key {}
+
+impl Hash for Key {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.identity().hash(state);
+    }
+}
+
+impl PartialOrd for Key {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Key {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.identity().cmp(&other.identity())
+    }
+}
+
+impl FromStr for Key {
+    type Err = InvalidValue;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let invalid = |expected: &'static str| InvalidValue { key: "bind".to_string(), value: s.to_string(), expected };
+
+        if let Some(code) = s.strip_prefix("code:") {
+            return code.trim().parse().map(Key::Keycode).map_err(|_| invalid("a keycode such as `code:10`"));
+        }
+        if let Some(button) = s.strip_prefix("mouse:") {
+            return button.trim().parse().map(Key::MouseButton).map_err(|_| invalid("a mouse button such as `mouse:272`"));
+        }
+        if let Some(direction) = s.strip_prefix("mouse_") {
+            let direction = match direction {
+                "up" => WheelDirection::Up,
+                "down" => WheelDirection::Down,
+                "left" => WheelDirection::Left,
+                "right" => WheelDirection::Right,
+                _ => return Err(invalid("a scroll direction: mouse_up, mouse_down, mouse_left or mouse_right")),
+            };
+            return Ok(Key::MouseWheel(direction));
+        }
+        if let Some(switch) = s.strip_prefix("switch:") {
+            let (state, name) = match switch.split_once(':') {
+                Some(("on", name)) => (Some(true), name),
+                Some(("off", name)) => (Some(false), name),
+                _ => (None, switch),
+            };
+            if name.trim().is_empty() {
+                return Err(invalid("a switch name such as `switch:Lid Switch`"));
+            }
+            return Ok(Key::Switch { state, name: name.trim().to_string() });
+        }
+        if let Some(name) = s.strip_prefix('$') {
+            if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+                return Err(invalid("a variable name"));
+            }
+            return Ok(Key::Variable(name.to_string()));
+        }
+
+        if s.is_empty() || !s.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+            return Err(invalid("a key name such as `Q`, `Return` or `XF86AudioRaiseVolume`"));
+        }
+        // Single letters are written in upper case, as in Hyprland's examples
+        if s.len() == 1 {
+            return Ok(Key::Keysym(s.to_ascii_uppercase()));
+        }
+        Ok(Key::Keysym(s.to_string()))
+    }
+}
+
+impl fmt::Display for Key {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Key::Keysym(name) => write!(f, "{}", name),
+            Key::Keycode(code) => write!(f, "code:{}", code),
+            Key::MouseButton(button) => write!(f, "mouse:{}", button),
+            Key::MouseWheel(WheelDirection::Up) => write!(f, "mouse_up"),
+            Key::MouseWheel(WheelDirection::Down) => write!(f, "mouse_down"),
+            Key::MouseWheel(WheelDirection::Left) => write!(f, "mouse_left"),
+            Key::MouseWheel(WheelDirection::Right) => write!(f, "mouse_right"),
+            Key::Switch { state: None, name } => write!(f, "switch:{}", name),
+            Key::Switch { state: Some(true), name } => write!(f, "switch:on:{}", name),
+            Key::Switch { state: Some(false), name } => write!(f, "switch:off:{}", name),
+            Key::Variable(name) => write!(f, "${}", name),
+        }
+    }
+}
+
+impl From<Key> for String {
+    fn from(key: Key) -> String {
+        key.to_string()
+    }
+}
+
+impl TryFrom<String> for Key {
+    type Error = InvalidValue;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct KeyBind {
-    pub modifiers: String,
-    pub key: String,
+    pub modifiers: Modifiers,
+    pub key: Key,
     pub dispatchers: Vec<String>,
     pub arg: String,
+    /// Flag letters following `bind`, `None` for a plain `bind`
     pub flags: Option<String>,
+    /// Description given through the `d` flag
     pub description: Option<String>,
+    /// Whether this is an `unbind` rather than a bind
+    #[serde(default)]
+    pub unbind: bool,
+}
+
+impl KeyBind {
+    /// Modifiers and key with `$variables` expanded
+    pub fn trigger(&self, variables: &IndexMap<String, String>) -> Result<(Modifiers, Key), InvalidValue> {
+        Ok((self.modifiers.resolve(variables)?, self.key.resolve(variables)?))
+    }
+
+    /// Whether both binds fire on the same key press or release
+    pub fn conflicts_with(&self, other: &KeyBind, variables: &IndexMap<String, String>) -> bool {
+        let on_release = |bind: &KeyBind| bind.flags.as_deref().is_some_and(|f| f.contains('r'));
+        if self.unbind || other.unbind || on_release(self) != on_release(other) {
+            return false;
+        }
+        match (self.trigger(variables), other.trigger(variables)) {
+            (Ok(a), Ok(b)) => a == b,
+            _ => false,
+        }
+    }
+
+    /// Keyword this bind is written with, e.g. `bind`, `binde` or `unbind`
+    pub fn keyword(&self) -> String {
+        if self.unbind {
+            "unbind".to_string()
+        } else {
+            format!("bind{}", self.flags.as_deref().unwrap_or(""))
+        }
+    }
+}
+
+/// Whether `keyword` is `unbind` or `bind` followed by valid flag letters
+pub fn is_bind_keyword(keyword: &str) -> bool {
+    keyword == "unbind"
+        || keyword
+            .strip_prefix("bind")
+            .is_some_and(|flags| flags.chars().all(|c| BIND_FLAGS.contains(c)))
 }
 
 /// Parse the binds section
 pub fn parse_binds_section(section: &mut BindsSection, content: &str) -> Result<()> {
-    let mut current_submap: Option<String> = None;
-    
     for line in content.lines() {
         let line = line.trim();
         if line.is_empty() || line.starts_with('#') {
             continue;
         }
         
-        // Check for bind or submap definition
-        if line.starts_with("bind") || line.starts_with("unbind") {
+        // Check for a bind definition
+        let keyword = line.split_once('=').map_or("", |(k, _)| k.trim());
+        if is_bind_keyword(keyword) {
             let bind_parts: Vec<&str> = line.splitn(2, '=').collect();
             if bind_parts.len() == 2 {
                 let bind_value = bind_parts[1].trim();
                 
-                match parse_key_bind(bind_value) {
-                    Ok(keybind) => {
-                        if let Some(submap_name) = &current_submap {
-                            // Add to current submap
-                            section.submaps
-                                .entry(submap_name.clone())
-                                .or_insert_with(Vec::new)
-                                .push(keybind);
-                        } else {
-                            // Add to main keybinds
-                            section.keybinds.push(keybind);
-                        }
-                    },
+                match parse_bind(keyword, bind_value) {
+                    Ok(keybind) => section.keybinds.push(keybind),
                     Err(e) => debug!("Error parsing keybind: {}", e),
                 }
             }
-        } else if line.starts_with("submap") {
-            let submap_parts: Vec<&str> = line.splitn(2, '=').collect();
-            if submap_parts.len() == 2 {
-                let submap_name = submap_parts[1].trim();
-                if submap_name == "reset" {
-                    current_submap = None;
-                } else {
-                    current_submap = Some(submap_name.to_string());
-                }
-            }
         } else if let Some((key, value)) = line.split_once('=') {
             let key = key.trim();
             let value = value.trim();
             
             match key {
-                "pass_mouse_when_bound" => section.pass_mouse_when_bound = parse_bool(value),
-                "scroll_event_delay" => section.scroll_event_delay = value.parse().unwrap_or(300),
-                "workspace_back_and_forth" => section.workspace_back_and_forth = parse_bool(value),
-                "hide_special_on_workspace_change" => section.hide_special_on_workspace_change = parse_bool(value),
-                "allow_workspace_cycles" => section.allow_workspace_cycles = parse_bool(value),
-                "workspace_center_on" => section.workspace_center_on = value.parse().unwrap_or(0),
-                "focus_preferred_method" => section.focus_preferred_method = value.parse().unwrap_or(0),
-                "ignore_group_lock" => section.ignore_group_lock = parse_bool(value),
-                "movefocus_cycles_fullscreen" => section.movefocus_cycles_fullscreen = parse_bool(value),
-                "movefocus_cycles_groupfirst" => section.movefocus_cycles_groupfirst = parse_bool(value),
-                "disable_keybind_grabbing" => section.disable_keybind_grabbing = parse_bool(value),
-                "window_direction_monitor_fallback" => section.window_direction_monitor_fallback = parse_bool(value),
-                "allow_pin_fullscreen" => section.allow_pin_fullscreen = parse_bool(value),
-                _ => debug!("Unknown binds setting: {}", key),
+                "pass_mouse_when_bound" => section.pass_mouse_when_bound = parse_bool_value(key, value)?,
+                "scroll_event_delay" => section.scroll_event_delay = parse_value(key, value)?,
+                "workspace_back_and_forth" => section.workspace_back_and_forth = parse_bool_value(key, value)?,
+                "hide_special_on_workspace_change" => section.hide_special_on_workspace_change = parse_bool_value(key, value)?,
+                "allow_workspace_cycles" => section.allow_workspace_cycles = parse_bool_value(key, value)?,
+                "workspace_center_on" => section.workspace_center_on = parse_value(key, value)?,
+                "focus_preferred_method" => section.focus_preferred_method = parse_value(key, value)?,
+                "ignore_group_lock" => section.ignore_group_lock = parse_bool_value(key, value)?,
+                "movefocus_cycles_fullscreen" => section.movefocus_cycles_fullscreen = parse_bool_value(key, value)?,
+                "movefocus_cycles_groupfirst" => section.movefocus_cycles_groupfirst = parse_bool_value(key, value)?,
+                "disable_keybind_grabbing" => section.disable_keybind_grabbing = parse_bool_value(key, value)?,
+                "window_direction_monitor_fallback" => section.window_direction_monitor_fallback = parse_bool_value(key, value)?,
+                "allow_pin_fullscreen" => section.allow_pin_fullscreen = parse_bool_value(key, value)?,
+                _ => {
+                    debug!("Unknown binds setting: {}", key);
+                    section.extra.insert(key.to_string(), value.to_string());
+                }
             }
         }
     }
@@ -106,58 +457,61 @@ pub fn parse_binds_section(section: &mut BindsSection, content: &str) -> Result<
     Ok(())
 }
 
-/// Parse a key bind from line
-pub fn parse_key_bind(content: &str) -> Result<KeyBind> {
-    let parts: Vec<&str> = content.splitn(4, ',').collect();
+/// Parse a bind line given its keyword (`bind`, `bindel`, `unbind`, ...) and value
+pub fn parse_bind(keyword: &str, content: &str) -> Result<KeyBind> {
+    if !is_bind_keyword(keyword) {
+        return Err(anyhow::anyhow!("Unknown bind keyword '{}'", keyword));
+    }
+
+    if keyword == "unbind" {
+        let (modifiers, key) = content.split_once(',')
+            .ok_or_else(|| anyhow::anyhow!("Invalid unbind format"))?;
+        return Ok(KeyBind {
+            modifiers: modifiers.parse()?,
+            key: key.parse()?,
+            dispatchers: Vec::new(),
+            arg: String::new(),
+            flags: None,
+            description: None,
+            unbind: true,
+        });
+    }
+
+    let flags = keyword.strip_prefix("bind").unwrap_or("");
+    let has_description = flags.contains('d');
+    let field_count = if has_description { 5 } else { 4 };
+    let parts: Vec<&str> = content.splitn(field_count, ',').collect();
     
-    if parts.len() < 3 {
+    if parts.len() < field_count - 1 {
         return Err(anyhow::anyhow!("Invalid keybind format"));
     }
     
-    let modifiers = parts[0].trim().to_string();
-    let key = parts[1].trim().to_string();
+    let modifiers: Modifiers = parts[0].parse()?;
+    let key: Key = parts[1].parse()?;
+    
+    // `bindd` puts a description between the key and the dispatcher
+    let (description, rest) = if has_description {
+        (Some(parts[2].trim().to_string()), &parts[3..])
+    } else {
+        (None, &parts[2..])
+    };
     
     // Parse dispatchers and args
-    let dispatcher_part = parts[2].trim();
-    let dispatchers: Vec<String> = dispatcher_part.split_whitespace()
+    let dispatchers: Vec<String> = rest[0].split_whitespace()
         .map(|s| s.trim().to_string())
         .collect();
     
     // Get the argument (everything after the dispatcher)
-    let arg = if parts.len() > 3 {
-        parts[3].trim().to_string()
-    } else {
-        String::new()
-    };
-    
-    // Optional fields
-    let mut flags = None;
-    let mut description = None;
-    
-    // Check for flags and description at the end of arg
-    if let Some((_arg_part, meta)) = arg.split_once('#') {
-        let meta_parts: Vec<&str> = meta.split(',').collect();
-        
-        for part in meta_parts {
-            let part = part.trim();
-            if part.starts_with("flags:") {
-                flags = Some(part.strip_prefix("flags:").unwrap_or("").trim().to_string());
-            } else if part.starts_with("desc:") {
-                description = Some(part.strip_prefix("desc:").unwrap_or("").trim().to_string());
-            } else if !part.is_empty() {
-                // If it's not empty and doesn't have a prefix, assume it's a description
-                description = Some(part.to_string());
-            }
-        }
-    }
+    let arg = rest.get(1).map(|a| a.trim().to_string()).unwrap_or_default();
     
     Ok(KeyBind {
         modifiers,
         key,
         dispatchers,
         arg,
-        flags,
+        flags: if flags.is_empty() { None } else { Some(flags.to_string()) },
         description,
+        unbind: false,
     })
 }
 
@@ -185,25 +539,14 @@ pub fn write_section<W: Write>(
     write_boolean_option(writer, " window_direction_monitor_fallback", binds.window_direction_monitor_fallback, None, comment_style)?;
     write_boolean_option(writer, " allow_pin_fullscreen", binds.allow_pin_fullscreen, None, comment_style)?;
     
+    write_extra_options(writer, " ", &binds.extra, comment_style)?;
     writeln!(writer, "}}")?;
     
-    // Write the keybinds
+    // Write the keybinds as top-level bind lines
     if !binds.keybinds.is_empty() {
-        write_section_header(writer, "bind {", comment_style)?;
+        writeln!(writer)?;
         for bind in &binds.keybinds {
-            write_key_bind(writer, bind, comment_style)?;
-        }
-        writeln!(writer, "}}")?;
-    }
-    
-    // Write submaps if they exist
-    for (submap_name, submap_binds) in &binds.submaps {
-        if !submap_binds.is_empty() {
-            write_section_header(writer, &format!("submap {} {{", submap_name), comment_style)?;
-            for bind in submap_binds {
-                write_key_bind(writer, bind, comment_style)?;
-            }
-            writeln!(writer, "}}")?;
+            write_key_bind(writer, bind)?;
         }
     }
     
@@ -211,32 +554,30 @@ pub fn write_section<W: Write>(
 }
 
 /// Write a key bind to the provided writer
-pub fn write_key_bind<W: Write>(
-    writer: &mut W,
-    bind: &KeyBind,
-    comment_style: &CommentStyle,
-) -> io::Result<()> {
-    // Format is: MOD, key, dispatchers, arg
-    write!(writer, " bind = {}, {}, ", bind.modifiers, bind.key)?;
+pub fn write_key_bind<W: Write>(writer: &mut W, bind: &KeyBind) -> io::Result<()> {
+    // Format is: bindFLAGS = MOD, key, [description,] dispatchers, arg
+    write!(writer, "{} = {}, {}", bind.keyword(), bind.modifiers, bind.key)?;
+    
+    if bind.unbind {
+        writeln!(writer)?;
+        return Ok(());
+    }
+    
+    // Description is only part of the line for the `d` flag
+    if let Some(description) = &bind.description {
+        if bind.flags.as_deref().is_some_and(|f| f.contains('d')) {
+            write!(writer, ", {}", description)?;
+        }
+    }
     
     // Join all dispatchers with space
-    write!(writer, "{}", bind.dispatchers.join(" "))?;
+    write!(writer, ", {}", bind.dispatchers.join(" "))?;
     
     // Add arg if not empty
     if !bind.arg.is_empty() {
         write!(writer, ", {}", bind.arg)?;
     }
     
-    // Add flags if present
-    if let Some(flags) = &bind.flags {
-        write!(writer, " {}", flags)?;
-    }
-    
-    // Add description if present
-    if let Some(description) = &bind.description {
-        write!(writer, " {} {}", comment_style.prefix(), description)?;
-    }
-    
     writeln!(writer)?;
     Ok(())
 }
@@ -246,4 +587,114 @@ impl ConfigSection for BindsSection {
     fn write_section<W: Write>(&self, writer: &mut W, comment_style: &CommentStyle) -> io::Result<()> {
         write_section(writer, self, comment_style)
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn written(bind: &KeyBind) -> String {
+        let mut buffer = Vec::new();
+        write_key_bind(&mut buffer, bind).unwrap();
+        String::from_utf8(buffer).unwrap()
+    }
+
+    #[test]
+    fn bind_keywords() {
+        for keyword in ["bind", "binde", "bindel", "bindm", "bindd", "unbind"] {
+            assert!(is_bind_keyword(keyword), "{}", keyword);
+        }
+        for keyword in ["bindz", "bindx", "unbindr", "bin", "exec"] {
+            assert!(!is_bind_keyword(keyword), "{}", keyword);
+        }
+    }
+
+    #[test]
+    fn parse_bind_variants() {
+        let bind = parse_bind("bindel", ", XF86AudioRaiseVolume, exec, wpctl set-volume 5%+").unwrap();
+        assert_eq!(bind.flags.as_deref(), Some("el"));
+        assert!(bind.modifiers.is_empty());
+        assert_eq!(bind.dispatchers, ["exec"]);
+        assert_eq!(bind.arg, "wpctl set-volume 5%+");
+
+        let bind = parse_bind("bindd", "SUPER, Q, Open terminal, exec, kitty --title a, b").unwrap();
+        assert_eq!(bind.description.as_deref(), Some("Open terminal"));
+        assert_eq!(bind.arg, "kitty --title a, b");
+
+        let bind = parse_bind("unbind", "SUPER, Q").unwrap();
+        assert!(bind.unbind);
+        assert_eq!(bind.keyword(), "unbind");
+
+        assert!(parse_bind("bind", "SUPER").is_err());
+        assert!(parse_bind("bindx", "SUPER, Q, exec, kitty").is_err());
+    }
+
+    #[test]
+    fn write_key_bind_round_trips() {
+        for (keyword, value) in [
+            ("bind", "SUPER, Q, exec, kitty"),
+            ("bindm", "SUPER, mouse:272, movewindow"),
+            ("bindd", "SUPER, Q, Open terminal, exec, kitty"),
+            ("bindl", ", switch:on:Lid Switch, exec, hyprlock"),
+            ("unbind", "SUPER, Q"),
+        ] {
+            let bind = parse_bind(keyword, value).unwrap();
+            assert_eq!(written(&bind), format!("{} = {}\n", keyword, value));
+        }
+    }
+
+    #[test]
+    fn modifier_spellings_read_to_the_same_set() {
+        let expected = Modifiers::SUPER | Modifiers::SHIFT;
+        for text in ["SUPER SHIFT", "SUPER_SHIFT", "SHIFT&SUPER", "supershift", "MOD4 + SHIFT", "WIN|SHIFT"] {
+            assert_eq!(text.parse::<Modifiers>().unwrap(), expected, "{}", text);
+        }
+        assert_eq!(expected.to_string(), "SUPER SHIFT");
+        assert_eq!("CONTROL ALT".parse::<Modifiers>().unwrap().to_string(), "CTRL ALT");
+        assert!("".parse::<Modifiers>().unwrap().is_empty());
+        assert!("SUPER HYPER".parse::<Modifiers>().is_err());
+    }
+
+    #[test]
+    fn modifier_variables_resolve() {
+        let modifiers: Modifiers = "$mainMod SHIFT".parse().unwrap();
+        assert_eq!(modifiers.variables(), ["mainMod"]);
+        assert_eq!(modifiers.to_string(), "$mainMod SHIFT");
+
+        let variables = IndexMap::from([("mainMod".to_string(), "SUPER".to_string())]);
+        assert_eq!(modifiers.resolve(&variables).unwrap(), Modifiers::SUPER | Modifiers::SHIFT);
+        assert_eq!(modifiers.resolve(&IndexMap::new()).unwrap(), modifiers);
+        assert!(modifiers.contains(&Modifiers::SHIFT));
+    }
+
+    #[test]
+    fn keys_parse_every_kind() {
+        assert_eq!("q".parse::<Key>().unwrap(), Key::Keysym("Q".to_string()));
+        assert_eq!("return".parse::<Key>().unwrap(), "Return".parse::<Key>().unwrap());
+        assert_eq!("code:10".parse::<Key>().unwrap(), Key::Keycode(10));
+        assert_eq!("mouse:272".parse::<Key>().unwrap(), Key::MouseButton(272));
+        assert_eq!("mouse_down".parse::<Key>().unwrap(), Key::MouseWheel(WheelDirection::Down));
+        assert_eq!(
+            "switch:on:Lid Switch".parse::<Key>().unwrap(),
+            Key::Switch { state: Some(true), name: "Lid Switch".to_string() }
+        );
+        assert_eq!("$key".parse::<Key>().unwrap(), Key::Variable("key".to_string()));
+
+        for text in ["XF86AudioRaiseVolume", "code:10", "mouse_up", "switch:Lid Switch", "switch:off:Lid Switch"] {
+            assert_eq!(text.parse::<Key>().unwrap().to_string(), text);
+        }
+        for text in ["code:x", "mouse:", "mouse_sideways", "switch:", "Page Up", "$", ""] {
+            assert!(text.parse::<Key>().is_err(), "{}", text);
+        }
+    }
+
+    #[test]
+    fn conflicts_compare_resolved_triggers() {
+        let variables = IndexMap::from([("mainMod".to_string(), "SUPER".to_string())]);
+        let a = parse_bind("bind", "$mainMod, q, exec, kitty").unwrap();
+        let b = parse_bind("bind", "SUPER, Q, killactive,").unwrap();
+        let released = parse_bind("bindr", "SUPER, Q, exec, wofi").unwrap();
+        assert!(a.conflicts_with(&b, &variables));
+        assert!(!a.conflicts_with(&released, &variables));
+        assert!(!a.conflicts_with(&parse_bind("bind", "SUPER SHIFT, Q, exit,").unwrap(), &variables));
+    }
+}
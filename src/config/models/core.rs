@@ -1,13 +1,15 @@
-use std::collections::HashMap;
 use std::fs::File;
 use std::io::{self, Write};
+use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
-use crate::config::utils::CommentStyle;
+use crate::config::parser::cst::{split_keyed, Document};
+use crate::config::schema;
+use crate::config::utils::{CommentStyle, OptionStyle, WriteMode};
 use crate::config::models::{
     animations::AnimationsSection, binds::BindsSection, cursor::CursorSection, decoration::DecorationSection, debug::DebugSection, devices::DeviceConfig,
-    dwindle::DwindleSection, ecosystem::EcosystemSection, experimental::ExperimentalSection, general::GeneralSection, gestures::GesturesSection,
+    dwindle::DwindleSection, ecosystem::EcosystemSection, exec::{ExecCommand, write_exec}, experimental::ExperimentalSection, general::GeneralSection, gestures::GesturesSection,
     group::GroupSection, input::InputSection, binds::KeyBind, rules::LayerRule, master::MasterSection, misc::MiscSection, monitors::MonitorConfig,
-    opengl::OpenGLSection, permissions::Permission, render::RenderSection, rules::WindowRule, rules::WorkspaceRule, xwayland::XWaylandSection,
+    opengl::OpenGLSection, permissions::Permission, plugins::PluginSection, render::RenderSection, rules::WindowRule, rules::WorkspaceRule, xwayland::XWaylandSection,
 };
 
 /// Trait for all config sections that can be written to a writer
@@ -15,7 +17,10 @@ pub trait ConfigSection {
     fn write_section<W: Write>(&self, writer: &mut W, comment_style: &CommentStyle) -> io::Result<()>;
 }
 
-/// Represents a complete Hyprland configuration
+/// Represents a complete Hyprland configuration.
+///
+/// `Default` gives an empty config; Hyprland's defaults come from the schema
+/// through `ConfigParser::defaults`.
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct HyprlandConfig {
     pub general: GeneralSection,
@@ -38,19 +43,28 @@ pub struct HyprlandConfig {
     pub window_rules: Vec<WindowRule>,
     pub workspace_rules: Vec<WorkspaceRule>,
     pub layer_rules: Vec<LayerRule>,
-    pub variables: HashMap<String, String>,
-    pub environment_variables: HashMap<String, String>,
-    pub autostart_programs: Vec<String>,
-    pub bezier_curves: HashMap<String, String>,
-    pub submap_definitions: HashMap<String, Vec<KeyBind>>,
+    pub variables: IndexMap<String, String>,
+    pub environment_variables: IndexMap<String, String>,
+    /// `exec`, `exec-once`, ... commands in file order
+    pub autostart_programs: Vec<ExecCommand>,
+    pub bezier_curves: IndexMap<String, String>,
+    pub submap_definitions: IndexMap<String, Vec<KeyBind>>,
     pub ecosystem: EcosystemSection,
     pub experimental: ExperimentalSection,
     pub permissions: Vec<Permission>,
+    /// Plugin and other unknown blocks, kept as written
+    pub plugins: Vec<PluginSection>,
 }
 
 impl HyprlandConfig {
     /// Writes the entire config to a writer
     pub fn write_to<W: Write>(&self, writer: &mut W, comment_style: &CommentStyle) -> io::Result<()> {
+        // Variables, env vars and curves come first, as Hyprland only knows
+        // about them on the lines after they are defined
+        self.write_variables(writer)?;
+        self.write_environment_variables(writer)?;
+        self.write_bezier_curves(writer)?;
+
         // Write each section through its write_section function
         crate::config::models::animations::write_animations_section(writer, &self.animations, comment_style)?;
         crate::config::models::decoration::write_decoration_section(writer, &self.decoration, comment_style)?;
@@ -70,6 +84,11 @@ impl HyprlandConfig {
         crate::config::models::ecosystem::write_ecosystem_section(writer, &self.ecosystem, comment_style)?;
         crate::config::models::experimental::write_experimental_section(writer, &self.experimental, comment_style)?;
         
+        // Write plugin and unknown blocks
+        for plugin in &self.plugins {
+            crate::config::models::plugins::write_plugin_section(writer, plugin, comment_style)?;
+        }
+        
         // Write monitor configurations
         self.write_monitors(writer, comment_style)?;
         
@@ -78,30 +97,122 @@ impl HyprlandConfig {
         
         // Write rules
         self.write_window_rules(writer, comment_style)?;
-        self.write_workspace_rules(writer, comment_style)?;
-        self.write_layer_rules(writer, comment_style)?;
-        
-        // Write variables
-        self.write_variables(writer, comment_style)?;
-        
-        // Write environment variables
-        self.write_environment_variables(writer, comment_style)?;
+        self.write_workspace_rules(writer)?;
+        self.write_layer_rules(writer)?;
         
         // Write autostart programs
-        self.write_autostart_programs(writer, comment_style)?;
-        
-        // Write bezier curves
-        self.write_bezier_curves(writer, comment_style)?;
+        self.write_autostart_programs(writer)?;
         
         // Write submap definitions
-        self.write_submap_definitions(writer, comment_style)?;
+        self.write_submap_definitions(writer)?;
         
         // Write permissions
-        self.write_permissions(writer, comment_style)?;
+        self.write_permissions(writer)?;
         
         Ok(())
     }
     
+    /// Writes the entire config, laying out section options in the given style
+    /// and leaving out or documenting defaults as `mode` asks
+    pub fn write_styled<W: Write>(
+        &self,
+        writer: &mut W,
+        comment_style: &CommentStyle,
+        option_style: OptionStyle,
+        mode: WriteMode,
+    ) -> io::Result<()> {
+        if option_style == OptionStyle::Block && mode == WriteMode::Full {
+            return self.write_to(writer, comment_style);
+        }
+
+        let mut buffer = Vec::new();
+        self.write_to(&mut buffer, comment_style)?;
+        let mut document = Document::parse(&String::from_utf8_lossy(&buffer));
+        document = match mode {
+            WriteMode::Full => document,
+            WriteMode::Minimal => schema::strip_defaults(&document),
+            WriteMode::Explicit => schema::annotate(&document, comment_style),
+        };
+        if option_style == OptionStyle::ColonPath {
+            document = document.flatten();
+        }
+        write!(writer, "{}", document)
+    }
+    
+    /// Unmodelled options of the section at `path`, e.g. `decoration:blur` or `device[mouse]`
+    pub fn extra_options(&self, path: &str) -> Option<&IndexMap<String, String>> {
+        if let Some((block, name)) = split_keyed(path) {
+            return match block {
+                "device" => self.devices.iter().find(|d| d.name == name).map(|d| &d.extra),
+                _ => None,
+            };
+        }
+        Some(match path {
+            "general" => &self.general.extra,
+            "general:snap" => &self.general.snap.extra,
+            "decoration" => &self.decoration.extra,
+            "decoration:blur" => &self.decoration.blur.extra,
+            "decoration:shadow" => &self.decoration.shadow.extra,
+            "animations" => &self.animations.extra,
+            "input" => &self.input.extra,
+            "input:touchpad" => &self.input.touchpad.extra,
+            "input:touchdevice" => &self.input.touchdevice.extra,
+            "input:tablet" => &self.input.tablet.extra,
+            "gestures" => &self.gestures.extra,
+            "group" => &self.group.extra,
+            "group:groupbar" => &self.group.groupbar.extra,
+            "misc" => &self.misc.extra,
+            "binds" => &self.binds.extra,
+            "xwayland" => &self.xwayland.extra,
+            "opengl" => &self.opengl.extra,
+            "render" => &self.render.extra,
+            "cursor" => &self.cursor.extra,
+            "dwindle" => &self.dwindle.extra,
+            "master" => &self.master.extra,
+            "debug" => &self.debug.extra,
+            "ecosystem" => &self.ecosystem.extra,
+            "experimental" => &self.experimental.extra,
+            _ => return None,
+        })
+    }
+    
+    /// Mutable access to the unmodelled options of the section at `path`
+    pub fn extra_options_mut(&mut self, path: &str) -> Option<&mut IndexMap<String, String>> {
+        if let Some((block, name)) = split_keyed(path) {
+            return match block {
+                "device" => self.devices.iter_mut().find(|d| d.name == name).map(|d| &mut d.extra),
+                _ => None,
+            };
+        }
+        Some(match path {
+            "general" => &mut self.general.extra,
+            "general:snap" => &mut self.general.snap.extra,
+            "decoration" => &mut self.decoration.extra,
+            "decoration:blur" => &mut self.decoration.blur.extra,
+            "decoration:shadow" => &mut self.decoration.shadow.extra,
+            "animations" => &mut self.animations.extra,
+            "input" => &mut self.input.extra,
+            "input:touchpad" => &mut self.input.touchpad.extra,
+            "input:touchdevice" => &mut self.input.touchdevice.extra,
+            "input:tablet" => &mut self.input.tablet.extra,
+            "gestures" => &mut self.gestures.extra,
+            "group" => &mut self.group.extra,
+            "group:groupbar" => &mut self.group.groupbar.extra,
+            "misc" => &mut self.misc.extra,
+            "binds" => &mut self.binds.extra,
+            "xwayland" => &mut self.xwayland.extra,
+            "opengl" => &mut self.opengl.extra,
+            "render" => &mut self.render.extra,
+            "cursor" => &mut self.cursor.extra,
+            "dwindle" => &mut self.dwindle.extra,
+            "master" => &mut self.master.extra,
+            "debug" => &mut self.debug.extra,
+            "ecosystem" => &mut self.ecosystem.extra,
+            "experimental" => &mut self.experimental.extra,
+            _ => return None,
+        })
+    }
+    
     /// Convenience method to write to a file
     pub fn write_to_file(&self, path: &str) -> io::Result<()> {
         // Use None to indicate no comments, or select a specific CommentStyle
@@ -135,21 +246,21 @@ impl HyprlandConfig {
         Ok(())
     }
     
-    fn write_workspace_rules<W: Write>(&self, writer: &mut W, comment_style: &CommentStyle) -> io::Result<()> {
+    fn write_workspace_rules<W: Write>(&self, writer: &mut W) -> io::Result<()> {
         for rule in &self.workspace_rules {
-            crate::config::models::rules::write_workspace_rule(writer, rule, comment_style)?;
+            crate::config::models::rules::write_workspace_rule(writer, rule)?;
         }
         Ok(())
     }
     
-    fn write_layer_rules<W: Write>(&self, writer: &mut W, comment_style: &CommentStyle) -> io::Result<()> {
+    fn write_layer_rules<W: Write>(&self, writer: &mut W) -> io::Result<()> {
         for rule in &self.layer_rules {
-            crate::config::models::rules::write_layer_rule(writer, rule, comment_style)?;
+            crate::config::models::rules::write_layer_rule(writer, rule)?;
         }
         Ok(())
     }
     
-    fn write_variables<W: Write>(&self, writer: &mut W, comment_style: &CommentStyle) -> io::Result<()> {
+    fn write_variables<W: Write>(&self, writer: &mut W) -> io::Result<()> {
         if !self.variables.is_empty() {
             writeln!(writer)?;
             for (name, value) in &self.variables {
@@ -159,27 +270,27 @@ impl HyprlandConfig {
         Ok(())
     }
     
-    fn write_environment_variables<W: Write>(&self, writer: &mut W, comment_style: &CommentStyle) -> io::Result<()> {
+    fn write_environment_variables<W: Write>(&self, writer: &mut W) -> io::Result<()> {
         if !self.environment_variables.is_empty() {
             writeln!(writer)?;
             for (name, value) in &self.environment_variables {
-                writeln!(writer, "env = {}={}", name, value)?;
+                writeln!(writer, "env = {},{}", name, value)?;
             }
         }
         Ok(())
     }
     
-    fn write_autostart_programs<W: Write>(&self, writer: &mut W, comment_style: &CommentStyle) -> io::Result<()> {
+    fn write_autostart_programs<W: Write>(&self, writer: &mut W) -> io::Result<()> {
         if !self.autostart_programs.is_empty() {
             writeln!(writer)?;
             for program in &self.autostart_programs {
-                writeln!(writer, "exec-once = {}", program)?;
+                write_exec(writer, program)?;
             }
         }
         Ok(())
     }
     
-    fn write_bezier_curves<W: Write>(&self, writer: &mut W, comment_style: &CommentStyle) -> io::Result<()> {
+    fn write_bezier_curves<W: Write>(&self, writer: &mut W) -> io::Result<()> {
         if !self.bezier_curves.is_empty() {
             writeln!(writer)?;
             for (name, curve) in &self.bezier_curves {
@@ -189,22 +300,22 @@ impl HyprlandConfig {
         Ok(())
     }
     
-    fn write_submap_definitions<W: Write>(&self, writer: &mut W, comment_style: &CommentStyle) -> io::Result<()> {
+    fn write_submap_definitions<W: Write>(&self, writer: &mut W) -> io::Result<()> {
         for (name, binds) in &self.submap_definitions {
             writeln!(writer, "\nsubmap = {}", name)?;
             for bind in binds {
-                crate::config::models::binds::write_key_bind(writer, bind, comment_style)?;
+                crate::config::models::binds::write_key_bind(writer, bind)?;
             }
             writeln!(writer, "submap = reset")?;
         }
         Ok(())
     }
     
-    fn write_permissions<W: Write>(&self, writer: &mut W, comment_style: &CommentStyle) -> io::Result<()> {
+    fn write_permissions<W: Write>(&self, writer: &mut W) -> io::Result<()> {
         if !self.permissions.is_empty() {
             writeln!(writer)?;
             for permission in &self.permissions {
-                crate::config::models::permissions::write_permission(writer, permission, comment_style)?;
+                crate::config::models::permissions::write_permission(writer, permission)?;
             }
         }
         Ok(())
@@ -250,4 +361,60 @@ impl HyprctlBatch {
     pub fn to_string(&self) -> String {
         self.commands.join(" ; ")
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::parser::source::SourceTree;
+    use crate::config::parser::ConfigParser;
+
+    #[test]
+    fn generated_config_defines_variables_before_using_them() {
+        for input in [include_str!("../../../docs/hyprland.conf"), include_str!("../../../docs/hyprland_mine.conf")] {
+            let config = ConfigParser::parse_string(input).unwrap();
+            assert!(!config.variables.is_empty());
+
+            let mut buffer = Vec::new();
+            config.write_to(&mut buffer, &CommentStyle::Hash).unwrap();
+            let written = String::from_utf8(buffer).unwrap();
+            let problems: Vec<String> = ConfigParser::validate(&SourceTree::from_string(&written))
+                .into_iter()
+                .filter(|d| d.message.contains("variable"))
+                .map(|d| d.to_string())
+                .collect();
+            assert!(problems.is_empty(), "{}", problems.join("\n"));
+        }
+    }
+
+    fn written(config: &HyprlandConfig, option_style: OptionStyle, mode: WriteMode) -> String {
+        let mut buffer = Vec::new();
+        config.write_styled(&mut buffer, &CommentStyle::Hash, option_style, mode).unwrap();
+        String::from_utf8(buffer).unwrap()
+    }
+
+    #[test]
+    fn minimal_mode_writes_only_changed_options() {
+        assert_eq!(written(&ConfigParser::defaults(), OptionStyle::Block, WriteMode::Minimal), "");
+
+        let mut config = ConfigParser::defaults();
+        config.general.border_size = 3;
+        config.decoration.blur.size = 5;
+        let minimal = written(&config, OptionStyle::ColonPath, WriteMode::Minimal);
+        let options: Vec<&str> = minimal.lines().filter(|line| line.contains('=')).collect();
+        assert_eq!(options, ["decoration:blur:size = 5", "general:border_size = 3"]);
+    }
+
+    #[test]
+    fn minimal_output_reads_back_to_the_same_config() {
+        let config = ConfigParser::parse_string(include_str!("../../../docs/hyprland.conf")).unwrap();
+        let minimal = written(&config, OptionStyle::Block, WriteMode::Minimal);
+        let reread = ConfigParser::parse_string(&minimal).unwrap();
+        assert_eq!(written(&reread, OptionStyle::Block, WriteMode::Full), written(&config, OptionStyle::Block, WriteMode::Full));
+    }
+
+    #[test]
+    fn explicit_mode_documents_every_known_option() {
+        let explicit = written(&ConfigParser::defaults(), OptionStyle::Block, WriteMode::Explicit);
+        assert!(explicit.contains(" # Size of the border around windows (default: 1)\n border_size = 1\n"));
+    }
+}
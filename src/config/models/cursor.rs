@@ -1,9 +1,10 @@
 use std::io::{self, Write};
 use anyhow::Result;
+use indexmap::IndexMap;
 use log::debug;
 use serde::{Deserialize, Serialize};
-use crate::config::utils::parse_bool;
-use crate::config::utils::{CommentStyle, write_boolean_option, write_option, write_section_header};
+use crate::config::utils::{parse_bool_value, parse_value};
+use crate::config::utils::{CommentStyle, write_extra_options, write_boolean_option, write_option, write_section_header};
 
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -26,6 +27,9 @@ pub struct CursorSection {
     pub hide_on_touch: bool,
     pub use_cpu_buffer: i32,
     pub warp_back_after_non_mouse_input: bool,
+    /// Options hyprconf has no model for, in file order
+    #[serde(default)]
+    pub extra: IndexMap<String, String>,
 }
 
 pub fn parse_cursor_section(section: &mut CursorSection, content: &str) -> Result<()> {
@@ -40,25 +44,28 @@ pub fn parse_cursor_section(section: &mut CursorSection, content: &str) -> Resul
             let value = value.trim();
             
             match key {
-                "sync_gsettings_theme" => section.sync_gsettings_theme = parse_bool(value),
-                "no_hardware_cursors" => section.no_hardware_cursors = value.parse().unwrap_or(2),
-                "no_break_fs_vrr" => section.no_break_fs_vrr = value.parse().unwrap_or(2),
-                "min_refresh_rate" => section.min_refresh_rate = value.parse().unwrap_or(24),
-                "hotspot_padding" => section.hotspot_padding = value.parse().unwrap_or(1),
-                "inactive_timeout" => section.inactive_timeout = value.parse().unwrap_or(0.0),
-                "no_warps" => section.no_warps = parse_bool(value),
-                "persistent_warps" => section.persistent_warps = parse_bool(value),
-                "warp_on_change_workspace" => section.warp_on_change_workspace = value.parse().unwrap_or(0),
-                "warp_on_toggle_special" => section.warp_on_toggle_special = value.parse().unwrap_or(0),
+                "sync_gsettings_theme" => section.sync_gsettings_theme = parse_bool_value(key, value)?,
+                "no_hardware_cursors" => section.no_hardware_cursors = parse_value(key, value)?,
+                "no_break_fs_vrr" => section.no_break_fs_vrr = parse_value(key, value)?,
+                "min_refresh_rate" => section.min_refresh_rate = parse_value(key, value)?,
+                "hotspot_padding" => section.hotspot_padding = parse_value(key, value)?,
+                "inactive_timeout" => section.inactive_timeout = parse_value(key, value)?,
+                "no_warps" => section.no_warps = parse_bool_value(key, value)?,
+                "persistent_warps" => section.persistent_warps = parse_bool_value(key, value)?,
+                "warp_on_change_workspace" => section.warp_on_change_workspace = parse_value(key, value)?,
+                "warp_on_toggle_special" => section.warp_on_toggle_special = parse_value(key, value)?,
                 "default_monitor" => section.default_monitor = value.to_string(),
-                "zoom_factor" => section.zoom_factor = value.parse().unwrap_or(1.0),
-                "zoom_rigid" => section.zoom_rigid = parse_bool(value),
-                "enable_hyprcursor" => section.enable_hyprcursor = parse_bool(value),
-                "hide_on_key_press" => section.hide_on_key_press = parse_bool(value),
-                "hide_on_touch" => section.hide_on_touch = parse_bool(value),
-                "use_cpu_buffer" => section.use_cpu_buffer = value.parse().unwrap_or(2),
-                "warp_back_after_non_mouse_input" => section.warp_back_after_non_mouse_input = parse_bool(value),
-                _ => debug!("Unknown cursor setting: {}", key),
+                "zoom_factor" => section.zoom_factor = parse_value(key, value)?,
+                "zoom_rigid" => section.zoom_rigid = parse_bool_value(key, value)?,
+                "enable_hyprcursor" => section.enable_hyprcursor = parse_bool_value(key, value)?,
+                "hide_on_key_press" => section.hide_on_key_press = parse_bool_value(key, value)?,
+                "hide_on_touch" => section.hide_on_touch = parse_bool_value(key, value)?,
+                "use_cpu_buffer" => section.use_cpu_buffer = parse_value(key, value)?,
+                "warp_back_after_non_mouse_input" => section.warp_back_after_non_mouse_input = parse_bool_value(key, value)?,
+                _ => {
+                    debug!("Unknown cursor setting: {}", key);
+                    section.extra.insert(key.to_string(), value.to_string());
+                }
             }
         }
     }
@@ -93,6 +100,7 @@ pub fn write_section<W: Write>(
     write_option(writer, " use_cpu_buffer", &cursor.use_cpu_buffer.to_string(), None, comment_style)?;
     write_boolean_option(writer, " warp_back_after_non_mouse_input", cursor.warp_back_after_non_mouse_input, None, comment_style)?;
     
+    write_extra_options(writer, " ", &cursor.extra, comment_style)?;
     writeln!(writer, "}}")?;
     Ok(())
 }
\ No newline at end of file
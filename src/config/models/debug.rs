@@ -1,9 +1,10 @@
 use std::io::{self, Write};
 use anyhow::Result;
+use indexmap::IndexMap;
 use log::debug;
 use serde::{Deserialize, Serialize};
-use crate::config::utils::parse_bool;
-use crate::config::utils::{CommentStyle, write_boolean_option, write_option, write_section_header};
+use crate::config::utils::{parse_bool_value, parse_value};
+use crate::config::utils::{CommentStyle, write_extra_options, write_boolean_option, write_option, write_section_header};
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct DebugSection {
@@ -22,6 +23,9 @@ pub struct DebugSection {
     pub colored_stdout_logs: bool,
     pub pass: bool,
     pub full_cm_proto: bool,
+    /// Options hyprconf has no model for, in file order
+    #[serde(default)]
+    pub extra: IndexMap<String, String>,
 }
 
 pub fn parse_debug_section(section: &mut DebugSection, content: &str) -> Result<()> {
@@ -32,22 +36,25 @@ pub fn parse_debug_section(section: &mut DebugSection, content: &str) -> Result<
             let key = key.trim();
             let value = value.trim();
             match key {
-                "overlay" => section.overlay = parse_bool(value),
-                "damage_blink" => section.damage_blink = parse_bool(value),
-                "disable_logs" => section.disable_logs = parse_bool(value),
-                "disable_time" => section.disable_time = parse_bool(value),
-                "damage_tracking" => section.damage_tracking = value.parse().unwrap_or(2),
-                "enable_stdout_logs" => section.enable_stdout_logs = parse_bool(value),
-                "manual_crash" => section.manual_crash = value.parse().unwrap_or(0),
-                "suppress_errors" => section.suppress_errors = parse_bool(value),
-                "watchdog_timeout" => section.watchdog_timeout = value.parse().unwrap_or(5),
-                "disable_scale_checks" => section.disable_scale_checks = parse_bool(value),
-                "error_limit" => section.error_limit = value.parse().unwrap_or(5),
-                "error_position" => section.error_position = value.parse().unwrap_or(0),
-                "colored_stdout_logs" => section.colored_stdout_logs = parse_bool(value),
-                "pass" => section.pass = parse_bool(value),
-                "full_cm_proto" => section.full_cm_proto = parse_bool(value),
-                _ => debug!("Unknown debug setting: {}", key),
+                "overlay" => section.overlay = parse_bool_value(key, value)?,
+                "damage_blink" => section.damage_blink = parse_bool_value(key, value)?,
+                "disable_logs" => section.disable_logs = parse_bool_value(key, value)?,
+                "disable_time" => section.disable_time = parse_bool_value(key, value)?,
+                "damage_tracking" => section.damage_tracking = parse_value(key, value)?,
+                "enable_stdout_logs" => section.enable_stdout_logs = parse_bool_value(key, value)?,
+                "manual_crash" => section.manual_crash = parse_value(key, value)?,
+                "suppress_errors" => section.suppress_errors = parse_bool_value(key, value)?,
+                "watchdog_timeout" => section.watchdog_timeout = parse_value(key, value)?,
+                "disable_scale_checks" => section.disable_scale_checks = parse_bool_value(key, value)?,
+                "error_limit" => section.error_limit = parse_value(key, value)?,
+                "error_position" => section.error_position = parse_value(key, value)?,
+                "colored_stdout_logs" => section.colored_stdout_logs = parse_bool_value(key, value)?,
+                "pass" => section.pass = parse_bool_value(key, value)?,
+                "full_cm_proto" => section.full_cm_proto = parse_bool_value(key, value)?,
+                _ => {
+                    debug!("Unknown debug setting: {}", key);
+                    section.extra.insert(key.to_string(), value.to_string());
+                }
             }
         }
     }
@@ -71,6 +78,7 @@ pub fn write_section<W: Write>(writer: &mut W, debug: &DebugSection, comment_sty
     write_boolean_option(writer, " colored_stdout_logs", debug.colored_stdout_logs, None, comment_style)?;
     write_boolean_option(writer, " pass", debug.pass, None, comment_style)?;
     write_boolean_option(writer, " full_cm_proto", debug.full_cm_proto, None, comment_style)?;
+    write_extra_options(writer, " ", &debug.extra, comment_style)?;
     writeln!(writer, "}}")?;
     Ok(())
 }
@@ -1,9 +1,10 @@
 use std::io::{self, Write};
 use anyhow::Result;
+use indexmap::IndexMap;
 use log::debug;
 use serde::{Deserialize, Serialize};
-use crate::config::utils::parse_bool;
-use crate::config::utils::{CommentStyle, write_boolean_option, write_option, write_section_header};
+use crate::config::utils::{Color, parse_bool_value, parse_value};
+use crate::config::utils::{CommentStyle, write_extra_options, write_boolean_option, write_option, write_section_header};
 
 
 /// Decoration section with blur and shadow subcategories
@@ -22,6 +23,9 @@ pub struct DecorationSection {
     pub border_part_of_window: bool,
     pub blur: BlurSection,
     pub shadow: ShadowSection,
+    /// Options hyprconf has no model for, in file order
+    #[serde(default)]
+    pub extra: IndexMap<String, String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -31,10 +35,13 @@ pub struct ShadowSection {
     pub render_power: i32,
     pub sharp: bool,
     pub ignore_window: bool,
-    pub color: String,
-    pub color_inactive: String,
+    pub color: Color,
+    pub color_inactive: Color,
     pub offset: (f32, f32),
     pub scale: f32,
+    /// Options hyprconf has no model for, in file order
+    #[serde(default)]
+    pub extra: IndexMap<String, String>,
 }
 
 
@@ -56,6 +63,9 @@ pub struct BlurSection {
     pub popups_ignorealpha: f32,
     pub input_methods: bool,
     pub input_methods_ignorealpha: f32,
+    /// Options hyprconf has no model for, in file order
+    #[serde(default)]
+    pub extra: IndexMap<String, String>,
 }
 
 pub fn parse_decoration_section(section: &mut DecorationSection, content: &str) -> Result<()> {
@@ -74,18 +84,21 @@ pub fn parse_decoration_section(section: &mut DecorationSection, content: &str)
             let value = value.trim();
             
             match key {
-                "rounding" => section.rounding = value.parse().unwrap_or(0),
-                "rounding_power" => section.rounding_power = value.parse().unwrap_or(2.0),
-                "active_opacity" => section.active_opacity = value.parse().unwrap_or(1.0),
-                "inactive_opacity" => section.inactive_opacity = value.parse().unwrap_or(1.0),
-                "fullscreen_opacity" => section.fullscreen_opacity = value.parse().unwrap_or(1.0),
-                "dim_inactive" => section.dim_inactive = parse_bool(value),
-                "dim_strength" => section.dim_strength = value.parse().unwrap_or(0.5),
-                "dim_special" => section.dim_special = value.parse().unwrap_or(0.2),
-                "dim_around" => section.dim_around = value.parse().unwrap_or(0.4),
+                "rounding" => section.rounding = parse_value(key, value)?,
+                "rounding_power" => section.rounding_power = parse_value(key, value)?,
+                "active_opacity" => section.active_opacity = parse_value(key, value)?,
+                "inactive_opacity" => section.inactive_opacity = parse_value(key, value)?,
+                "fullscreen_opacity" => section.fullscreen_opacity = parse_value(key, value)?,
+                "dim_inactive" => section.dim_inactive = parse_bool_value(key, value)?,
+                "dim_strength" => section.dim_strength = parse_value(key, value)?,
+                "dim_special" => section.dim_special = parse_value(key, value)?,
+                "dim_around" => section.dim_around = parse_value(key, value)?,
                 "screen_shader" => section.screen_shader = value.to_string(),
-                "border_part_of_window" => section.border_part_of_window = parse_bool(value),
-                _ => debug!("Unknown decoration setting: {}", key),
+                "border_part_of_window" => section.border_part_of_window = parse_bool_value(key, value)?,
+                _ => {
+                    debug!("Unknown decoration setting: {}", key);
+                    section.extra.insert(key.to_string(), value.to_string());
+                }
             }
         }
     }
@@ -119,6 +132,7 @@ pub fn write_decoration_section<W: Write>(
     // Write the shadow subsection using the dedicated shadow writer
     write_shadow_section(writer, &decoration.shadow, comment_style)?;
     
+    write_extra_options(writer, " ", &decoration.extra, comment_style)?;
     writeln!(writer, "}}")?;
     
     Ok(())
@@ -138,23 +152,26 @@ pub fn parse_blur_section(section: &mut BlurSection, content: &str) -> Result<()
             let value = value.trim();
             
             match key {
-                "enabled" => section.enabled = parse_bool(value),
-                "size" => section.size = value.parse().unwrap_or(8),
-                "passes" => section.passes = value.parse().unwrap_or(1),
-                "ignore_opacity" => section.ignore_opacity = parse_bool(value),
-                "new_optimizations" => section.new_optimizations = parse_bool(value),
-                "xray" => section.xray = parse_bool(value),
-                "noise" => section.noise = value.parse().unwrap_or(0.0117),
-                "contrast" => section.contrast = value.parse().unwrap_or(0.8916),
-                "brightness" => section.brightness = value.parse().unwrap_or(0.8172),
-                "vibrancy" => section.vibrancy = value.parse().unwrap_or(0.1696),
-                "vibrancy_darkness" => section.vibrancy_darkness = value.parse().unwrap_or(0.0),
-                "special" => section.special = parse_bool(value),
-                "popups" => section.popups = parse_bool(value),
-                "popups_ignorealpha" => section.popups_ignorealpha = value.parse().unwrap_or(0.2),
-                "input_methods" => section.input_methods = parse_bool(value),
-                "input_methods_ignorealpha" => section.input_methods_ignorealpha = value.parse().unwrap_or(0.2),
-                _ => debug!("Unknown blur setting: {}", key),
+                "enabled" => section.enabled = parse_bool_value(key, value)?,
+                "size" => section.size = parse_value(key, value)?,
+                "passes" => section.passes = parse_value(key, value)?,
+                "ignore_opacity" => section.ignore_opacity = parse_bool_value(key, value)?,
+                "new_optimizations" => section.new_optimizations = parse_bool_value(key, value)?,
+                "xray" => section.xray = parse_bool_value(key, value)?,
+                "noise" => section.noise = parse_value(key, value)?,
+                "contrast" => section.contrast = parse_value(key, value)?,
+                "brightness" => section.brightness = parse_value(key, value)?,
+                "vibrancy" => section.vibrancy = parse_value(key, value)?,
+                "vibrancy_darkness" => section.vibrancy_darkness = parse_value(key, value)?,
+                "special" => section.special = parse_bool_value(key, value)?,
+                "popups" => section.popups = parse_bool_value(key, value)?,
+                "popups_ignorealpha" => section.popups_ignorealpha = parse_value(key, value)?,
+                "input_methods" => section.input_methods = parse_bool_value(key, value)?,
+                "input_methods_ignorealpha" => section.input_methods_ignorealpha = parse_value(key, value)?,
+                _ => {
+                    debug!("Unknown blur setting: {}", key);
+                    section.extra.insert(key.to_string(), value.to_string());
+                }
             }
         }
     }
@@ -189,6 +206,7 @@ pub fn write_blur_section<W: Write>(
     write_boolean_option(writer, " input_methods", blur.input_methods, None, comment_style)?;
     write_option(writer, " input_methods_ignorealpha", &blur.input_methods_ignorealpha.to_string(), None, comment_style)?;
     
+    write_extra_options(writer, " ", &blur.extra, comment_style)?;
     writeln!(writer, "}}")?;
     Ok(())
 }
@@ -206,23 +224,26 @@ pub fn parse_shadow_section(section: &mut ShadowSection, content: &str) -> Resul
             let value = value.trim();
             
             match key {
-                "enabled" => section.enabled = parse_bool(value),
-                "range" => section.range = value.parse().unwrap_or(4),
-                "render_power" => section.render_power = value.parse().unwrap_or(3),
-                "sharp" => section.sharp = parse_bool(value),
-                "ignore_window" => section.ignore_window = parse_bool(value),
-                "color" => section.color = value.to_string(),
-                "color_inactive" => section.color_inactive = value.to_string(),
-                "scale" => section.scale = value.parse().unwrap_or(1.0),
+                "enabled" => section.enabled = parse_bool_value(key, value)?,
+                "range" => section.range = parse_value(key, value)?,
+                "render_power" => section.render_power = parse_value(key, value)?,
+                "sharp" => section.sharp = parse_bool_value(key, value)?,
+                "ignore_window" => section.ignore_window = parse_bool_value(key, value)?,
+                "color" => section.color = parse_value(key, value)?,
+                "color_inactive" => section.color_inactive = parse_value(key, value)?,
+                "scale" => section.scale = parse_value(key, value)?,
                 "offset" => {
                     let parts: Vec<&str> = value.split_whitespace().collect();
                     if parts.len() == 2 {
-                        let x = parts[0].parse().unwrap_or(0.0);
-                        let y = parts[1].parse().unwrap_or(0.0);
+                        let x = parse_value(key, parts[0])?;
+                        let y = parse_value(key, parts[1])?;
                         section.offset = (x, y);
                     }
                 },
-                _ => debug!("Unknown shadow setting: {}", key),
+                _ => {
+                    debug!("Unknown shadow setting: {}", key);
+                    section.extra.insert(key.to_string(), value.to_string());
+                }
             }
         }
     }
@@ -247,6 +268,7 @@ pub fn write_shadow_section<W: Write>(
     write_option(writer, " offset", &format!("{} {}", shadow.offset.0, shadow.offset.1), None, comment_style)?;
     write_option(writer, " scale", &shadow.scale.to_string(), None, comment_style)?;
     
+    write_extra_options(writer, " ", &shadow.extra, comment_style)?;
     writeln!(writer, "}}")?;
     Ok(())
 }
\ No newline at end of file
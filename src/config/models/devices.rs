@@ -1,7 +1,9 @@
 use std::io::{self, Write};
 use anyhow::Result;
+use indexmap::IndexMap;
+use log::debug;
 use serde::{Deserialize, Serialize};
-use crate::config::utils::{CommentStyle, parse_bool};
+use crate::config::utils::{CommentStyle, parse_bool_value, parse_value, write_boolean_option, write_extra_options, write_option, write_section_header};
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct DeviceConfig {
@@ -29,8 +31,61 @@ pub struct DeviceConfig {
     pub output: Option<String>,
     pub enabled: Option<bool>,
     pub keybinds: Option<bool>,
+    /// Options hyprconf has no model for, in file order
+    #[serde(default)]
+    pub extra: IndexMap<String, String>,
 }
 
+impl DeviceConfig {
+    /// Take every option set in `other`, keeping ours where `other` leaves one unset
+    pub fn merge(&mut self, other: DeviceConfig) {
+        self.sensitivity = other.sensitivity.or(self.sensitivity);
+        self.accel_profile = other.accel_profile.or(self.accel_profile.take());
+        self.kb_layout = other.kb_layout.or(self.kb_layout.take());
+        self.kb_model = other.kb_model.or(self.kb_model.take());
+        self.kb_options = other.kb_options.or(self.kb_options.take());
+        self.kb_rules = other.kb_rules.or(self.kb_rules.take());
+        self.kb_variant = other.kb_variant.or(self.kb_variant.take());
+        self.repeat_delay = other.repeat_delay.or(self.repeat_delay);
+        self.repeat_rate = other.repeat_rate.or(self.repeat_rate);
+        self.natural_scroll = other.natural_scroll.or(self.natural_scroll);
+        self.tap_and_drag = other.tap_and_drag.or(self.tap_and_drag);
+        self.tap_button_map = other.tap_button_map.or(self.tap_button_map.take());
+        self.tap_to_click = other.tap_to_click.or(self.tap_to_click);
+        self.middle_button_emulation = other.middle_button_emulation.or(self.middle_button_emulation);
+        self.clickfinger_behavior = other.clickfinger_behavior.or(self.clickfinger_behavior);
+        self.drag_lock = other.drag_lock.or(self.drag_lock);
+        self.left_handed = other.left_handed.or(self.left_handed);
+        self.scroll_button = other.scroll_button.or(self.scroll_button);
+        self.scroll_method = other.scroll_method.or(self.scroll_method.take());
+        self.transform = other.transform.or(self.transform);
+        self.output = other.output.or(self.output.take());
+        self.enabled = other.enabled.or(self.enabled);
+        self.keybinds = other.keybinds.or(self.keybinds);
+        self.extra.extend(other.extra);
+    }
+}
+
+/// Parse the body of a `device { ... }` block
+pub fn parse_device_section(content: &str) -> Result<DeviceConfig> {
+    let mut device = DeviceConfig::default();
+    for line in content.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if let Some((key, value)) = line.split_once('=') {
+            set_device_option(&mut device, key.trim(), value.trim())?;
+        }
+    }
+    
+    if device.name.is_empty() {
+        return Err(anyhow::anyhow!("Device block has no name"));
+    }
+    Ok(device)
+}
+
+/// Parse the legacy `device = name,key:value,...` form
 pub fn parse_device_config(content: &str) -> Result<DeviceConfig> {
     let mut device = DeviceConfig::default();
     let parts: Vec<&str> = content.split(',').collect();
@@ -39,163 +94,128 @@ pub fn parse_device_config(content: &str) -> Result<DeviceConfig> {
     }
     device.name = parts[0].trim().to_string();
     for part in &parts[1..] {
-        let part = part.trim();
-        if let Some(value) = part.strip_prefix("accel_profile:") {
-            device.accel_profile = Some(value.trim().to_string());
-        } else if let Some(value) = part.strip_prefix("clickfinger_behavior:") {
-            device.clickfinger_behavior = Some(parse_bool(value.trim()));
-        } else if let Some(value) = part.strip_prefix("drag_lock:") {
-            device.drag_lock = Some(parse_bool(value.trim()));
-        } else if let Some(value) = part.strip_prefix("enabled:") {
-            device.enabled = Some(parse_bool(value.trim()));
-        } else if let Some(value) = part.strip_prefix("kb_layout:") {
-            device.kb_layout = Some(value.trim().to_string());
-        } else if let Some(value) = part.strip_prefix("kb_model:") {
-            device.kb_model = Some(value.trim().to_string());
-        } else if let Some(value) = part.strip_prefix("kb_options:") {
-            device.kb_options = Some(value.trim().to_string());
-        } else if let Some(value) = part.strip_prefix("kb_rules:") {
-            device.kb_rules = Some(value.trim().to_string());
-        } else if let Some(value) = part.strip_prefix("kb_variant:") {
-            device.kb_variant = Some(value.trim().to_string());
-        } else if let Some(value) = part.strip_prefix("keybinds:") {
-            device.keybinds = Some(parse_bool(value.trim()));
-        } else if let Some(value) = part.strip_prefix("left_handed:") {
-            device.left_handed = Some(parse_bool(value.trim()));
-        } else if let Some(value) = part.strip_prefix("middle_button_emulation:") {
-            device.middle_button_emulation = Some(parse_bool(value.trim()));
-        } else if let Some(value) = part.strip_prefix("natural_scroll:") {
-            device.natural_scroll = Some(parse_bool(value.trim()));
-        } else if let Some(value) = part.strip_prefix("output:") {
-            device.output = Some(value.trim().to_string());
-        } else if let Some(value) = part.strip_prefix("repeat_delay:") {
-            device.repeat_delay = Some(value.trim().parse().unwrap_or(600));
-        } else if let Some(value) = part.strip_prefix("repeat_rate:") {
-            device.repeat_rate = Some(value.trim().parse().unwrap_or(25));
-        } else if let Some(value) = part.strip_prefix("scroll_button:") {
-            device.scroll_button = Some(value.trim().parse().unwrap_or(0));
-        } else if let Some(value) = part.strip_prefix("scroll_method:") {
-            device.scroll_method = Some(value.trim().to_string());
-        } else if let Some(value) = part.strip_prefix("sensitivity:") {
-            device.sensitivity = Some(value.trim().parse().unwrap_or(0.0));
-        } else if let Some(value) = part.strip_prefix("tap_and_drag:") {
-            device.tap_and_drag = Some(parse_bool(value.trim()));
-        } else if let Some(value) = part.strip_prefix("tap_button_map:") {
-            device.tap_button_map = Some(value.trim().to_string());
-        } else if let Some(value) = part.strip_prefix("tap_to_click:") {
-            device.tap_to_click = Some(parse_bool(value.trim()));
-        } else if let Some(value) = part.strip_prefix("transform:") {
-            device.transform = Some(value.trim().parse().unwrap_or(0));
+        if let Some((key, value)) = part.split_once(':') {
+            set_device_option(&mut device, key.trim(), value.trim())?;
         }
     }
     Ok(device)
 }
 
-/// Write a device configuration to the provided writer
+fn set_device_option(device: &mut DeviceConfig, key: &str, value: &str) -> Result<()> {
+    match key {
+        "name" => device.name = value.to_string(),
+        "sensitivity" => device.sensitivity = Some(parse_value(key, value)?),
+        "accel_profile" => device.accel_profile = Some(value.to_string()),
+        "kb_layout" => device.kb_layout = Some(value.to_string()),
+        "kb_model" => device.kb_model = Some(value.to_string()),
+        "kb_options" => device.kb_options = Some(value.to_string()),
+        "kb_rules" => device.kb_rules = Some(value.to_string()),
+        "kb_variant" => device.kb_variant = Some(value.to_string()),
+        "repeat_delay" => device.repeat_delay = Some(parse_value(key, value)?),
+        "repeat_rate" => device.repeat_rate = Some(parse_value(key, value)?),
+        "natural_scroll" => device.natural_scroll = Some(parse_bool_value(key, value)?),
+        "tap_and_drag" => device.tap_and_drag = Some(parse_bool_value(key, value)?),
+        "tap_button_map" => device.tap_button_map = Some(value.to_string()),
+        "tap-to-click" | "tap_to_click" => device.tap_to_click = Some(parse_bool_value(key, value)?),
+        "middle_button_emulation" => device.middle_button_emulation = Some(parse_bool_value(key, value)?),
+        "clickfinger_behavior" => device.clickfinger_behavior = Some(parse_bool_value(key, value)?),
+        "drag_lock" => device.drag_lock = Some(parse_bool_value(key, value)?),
+        "left_handed" => device.left_handed = Some(parse_bool_value(key, value)?),
+        "scroll_button" => device.scroll_button = Some(parse_value(key, value)?),
+        "scroll_method" => device.scroll_method = Some(value.to_string()),
+        "transform" => device.transform = Some(parse_value(key, value)?),
+        "output" => device.output = Some(value.to_string()),
+        "enabled" => device.enabled = Some(parse_bool_value(key, value)?),
+        "keybinds" => device.keybinds = Some(parse_bool_value(key, value)?),
+        _ => {
+            debug!("Unknown device setting: {}", key);
+            device.extra.insert(key.to_string(), value.to_string());
+        }
+    }
+    Ok(())
+}
+
+/// Write a device configuration as a `device { ... }` block
 pub fn write_device<W: Write>(
     writer: &mut W,
     device: &DeviceConfig,
     comment_style: &CommentStyle,
 ) -> io::Result<()> {
-    // Start with the device name
-    let mut device_str = format!("device = {}", device.name);
+    write_section_header(writer, "device {", comment_style)?;
+    write_option(writer, " name", &device.name, None, comment_style)?;
     
-    // Add optional parameters with appropriate prefixes
     if let Some(sensitivity) = device.sensitivity {
-        device_str.push_str(&format!(",sensitivity:{}", sensitivity));
+        write_option(writer, " sensitivity", &sensitivity, None, comment_style)?;
     }
-    
     if let Some(accel_profile) = &device.accel_profile {
-        device_str.push_str(&format!(",accel_profile:{}", accel_profile));
+        write_option(writer, " accel_profile", accel_profile, None, comment_style)?;
     }
-    
     if let Some(kb_layout) = &device.kb_layout {
-        device_str.push_str(&format!(",kb_layout:{}", kb_layout));
+        write_option(writer, " kb_layout", kb_layout, None, comment_style)?;
     }
-    
     if let Some(kb_model) = &device.kb_model {
-        device_str.push_str(&format!(",kb_model:{}", kb_model));
+        write_option(writer, " kb_model", kb_model, None, comment_style)?;
     }
-    
     if let Some(kb_options) = &device.kb_options {
-        device_str.push_str(&format!(",kb_options:{}", kb_options));
+        write_option(writer, " kb_options", kb_options, None, comment_style)?;
     }
-    
     if let Some(kb_rules) = &device.kb_rules {
-        device_str.push_str(&format!(",kb_rules:{}", kb_rules));
+        write_option(writer, " kb_rules", kb_rules, None, comment_style)?;
     }
-    
     if let Some(kb_variant) = &device.kb_variant {
-        device_str.push_str(&format!(",kb_variant:{}", kb_variant));
+        write_option(writer, " kb_variant", kb_variant, None, comment_style)?;
     }
-    
     if let Some(repeat_delay) = device.repeat_delay {
-        device_str.push_str(&format!(",repeat_delay:{}", repeat_delay));
+        write_option(writer, " repeat_delay", &repeat_delay, None, comment_style)?;
     }
-    
     if let Some(repeat_rate) = device.repeat_rate {
-        device_str.push_str(&format!(",repeat_rate:{}", repeat_rate));
+        write_option(writer, " repeat_rate", &repeat_rate, None, comment_style)?;
     }
-    
     if let Some(natural_scroll) = device.natural_scroll {
-        device_str.push_str(&format!(",natural_scroll:{}", natural_scroll));
+        write_boolean_option(writer, " natural_scroll", natural_scroll, None, comment_style)?;
     }
-    
     if let Some(tap_and_drag) = device.tap_and_drag {
-        device_str.push_str(&format!(",tap_and_drag:{}", tap_and_drag));
+        write_boolean_option(writer, " tap_and_drag", tap_and_drag, None, comment_style)?;
     }
-    
     if let Some(tap_button_map) = &device.tap_button_map {
-        device_str.push_str(&format!(",tap_button_map:{}", tap_button_map));
+        write_option(writer, " tap_button_map", tap_button_map, None, comment_style)?;
     }
-    
     if let Some(tap_to_click) = device.tap_to_click {
-        device_str.push_str(&format!(",tap_to_click:{}", tap_to_click));
+        write_boolean_option(writer, " tap-to-click", tap_to_click, None, comment_style)?;
     }
-    
     if let Some(middle_button_emulation) = device.middle_button_emulation {
-        device_str.push_str(&format!(",middle_button_emulation:{}", middle_button_emulation));
+        write_boolean_option(writer, " middle_button_emulation", middle_button_emulation, None, comment_style)?;
     }
-    
     if let Some(clickfinger_behavior) = device.clickfinger_behavior {
-        device_str.push_str(&format!(",clickfinger_behavior:{}", clickfinger_behavior));
+        write_boolean_option(writer, " clickfinger_behavior", clickfinger_behavior, None, comment_style)?;
     }
-    
     if let Some(drag_lock) = device.drag_lock {
-        device_str.push_str(&format!(",drag_lock:{}", drag_lock));
+        write_boolean_option(writer, " drag_lock", drag_lock, None, comment_style)?;
     }
-    
     if let Some(left_handed) = device.left_handed {
-        device_str.push_str(&format!(",left_handed:{}", left_handed));
+        write_boolean_option(writer, " left_handed", left_handed, None, comment_style)?;
     }
-    
     if let Some(scroll_button) = device.scroll_button {
-        device_str.push_str(&format!(",scroll_button:{}", scroll_button));
+        write_option(writer, " scroll_button", &scroll_button, None, comment_style)?;
     }
-    
     if let Some(scroll_method) = &device.scroll_method {
-        device_str.push_str(&format!(",scroll_method:{}", scroll_method));
+        write_option(writer, " scroll_method", scroll_method, None, comment_style)?;
     }
-    
     if let Some(transform) = device.transform {
-        device_str.push_str(&format!(",transform:{}", transform));
+        write_option(writer, " transform", &transform, None, comment_style)?;
     }
-    
     if let Some(output) = &device.output {
-        device_str.push_str(&format!(",output:{}", output));
+        write_option(writer, " output", output, None, comment_style)?;
     }
-    
     if let Some(enabled) = device.enabled {
-        device_str.push_str(&format!(",enabled:{}", enabled));
+        write_boolean_option(writer, " enabled", enabled, None, comment_style)?;
     }
-    
     if let Some(keybinds) = device.keybinds {
-        device_str.push_str(&format!(",keybinds:{}", keybinds));
+        write_boolean_option(writer, " keybinds", keybinds, None, comment_style)?;
     }
+    write_extra_options(writer, " ", &device.extra, comment_style)?;
     
-    // Write the completed device string
-    writeln!(writer, "{}", device_str)?;
-    
+    writeln!(writer, "}}")?;
     Ok(())
 }
 
@@ -207,4 +227,37 @@ impl crate::config::models::core::ConfigSection for Vec<DeviceConfig> {
         }
         Ok(())
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn device_block_needs_a_name() {
+        let device = parse_device_section("name = epic-mouse-v1\nsensitivity = -0.5\nkb_layout = us\nsome_new_option = 1\n").unwrap();
+        assert_eq!(device.name, "epic-mouse-v1");
+        assert_eq!(device.sensitivity, Some(-0.5));
+        assert_eq!(device.kb_layout.as_deref(), Some("us"));
+        assert_eq!(device.extra["some_new_option"], "1");
+        assert!(parse_device_section("sensitivity = 1\n").is_err());
+    }
+
+    #[test]
+    fn merge_keeps_options_the_later_block_leaves_unset() {
+        let mut device = parse_device_section("name = kb\nkb_layout = us\nrepeat_rate = 30\n").unwrap();
+        device.merge(parse_device_section("name = kb\nkb_layout = de\n").unwrap());
+        assert_eq!(device.kb_layout.as_deref(), Some("de"));
+        assert_eq!(device.repeat_rate, Some(30));
+    }
+
+    #[test]
+    fn device_is_written_as_a_block() {
+        let device = parse_device_section("name = kb\nkb_layout = us\nnatural_scroll = true\n").unwrap();
+        let mut buffer = Vec::new();
+        write_device(&mut buffer, &device, &CommentStyle::Hash).unwrap();
+        let written = String::from_utf8(buffer).unwrap();
+        let body = written.trim_start_matches("device {\n").trim_end_matches("}\n");
+        let again = parse_device_section(body).unwrap();
+        assert_eq!((again.name, again.kb_layout, again.natural_scroll), (device.name, device.kb_layout, device.natural_scroll));
+    }
+}
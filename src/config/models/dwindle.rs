@@ -1,9 +1,10 @@
 use std::io::{self, Write};
 use anyhow::Result;
+use indexmap::IndexMap;
 use log::debug;
 use serde::{Deserialize, Serialize};
-use crate::config::utils::parse_bool;
-use crate::config::utils::{CommentStyle, write_boolean_option, write_option, write_section_header};
+use crate::config::utils::{parse_bool_value, parse_value};
+use crate::config::utils::{CommentStyle, write_extra_options, write_boolean_option, write_option, write_section_header};
 
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -19,6 +20,9 @@ pub struct DwindleSection {
     pub default_split_ratio: f32,
     pub split_bias: i32,
     pub smart_resizing: bool,
+    /// Options hyprconf has no model for, in file order
+    #[serde(default)]
+    pub extra: IndexMap<String, String>,
 }
 
 pub fn parse_dwindle_section(section: &mut DwindleSection, content: &str) -> Result<()> {
@@ -33,18 +37,21 @@ pub fn parse_dwindle_section(section: &mut DwindleSection, content: &str) -> Res
             let value = value.trim();
             
             match key {
-                "pseudotile" => section.pseudotile = parse_bool(value),
-                "preserve_split" => section.preserve_split = parse_bool(value),
-                "smart_split" => section.smart_split = parse_bool(value),
-                "force_split" => section.force_split = value.parse().unwrap_or(0),
-                "permanent_direction_override" => section.permanent_direction_override = parse_bool(value),
-                "special_scale_factor" => section.special_scale_factor = value.parse().unwrap_or(0.8),
-                "split_width_multiplier" => section.split_width_multiplier = value.parse().unwrap_or(1.0),
-                "use_active_for_splits" => section.use_active_for_splits = parse_bool(value),
-                "default_split_ratio" => section.default_split_ratio = value.parse().unwrap_or(1.0),
-                "split_bias" => section.split_bias = value.parse().unwrap_or(0),
-                "smart_resizing" => section.smart_resizing = parse_bool(value),
-                _ => debug!("Unknown dwindle setting: {}", key),
+                "pseudotile" => section.pseudotile = parse_bool_value(key, value)?,
+                "preserve_split" => section.preserve_split = parse_bool_value(key, value)?,
+                "smart_split" => section.smart_split = parse_bool_value(key, value)?,
+                "force_split" => section.force_split = parse_value(key, value)?,
+                "permanent_direction_override" => section.permanent_direction_override = parse_bool_value(key, value)?,
+                "special_scale_factor" => section.special_scale_factor = parse_value(key, value)?,
+                "split_width_multiplier" => section.split_width_multiplier = parse_value(key, value)?,
+                "use_active_for_splits" => section.use_active_for_splits = parse_bool_value(key, value)?,
+                "default_split_ratio" => section.default_split_ratio = parse_value(key, value)?,
+                "split_bias" => section.split_bias = parse_value(key, value)?,
+                "smart_resizing" => section.smart_resizing = parse_bool_value(key, value)?,
+                _ => {
+                    debug!("Unknown dwindle setting: {}", key);
+                    section.extra.insert(key.to_string(), value.to_string());
+                }
             }
         }
     }
@@ -72,6 +79,7 @@ pub fn write_section<W: Write>(
     write_option(writer, " split_bias", &dwindle.split_bias.to_string(), None, comment_style)?;
     write_boolean_option(writer, " smart_resizing", dwindle.smart_resizing, None, comment_style)?;
     
+    write_extra_options(writer, " ", &dwindle.extra, comment_style)?;
     writeln!(writer, "}}")?;
     Ok(())
 }
\ No newline at end of file
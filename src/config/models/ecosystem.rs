@@ -1,8 +1,9 @@
 use std::io::{self, Write};
 use anyhow::Result;
+use indexmap::IndexMap;
 use log::debug;
 use serde::{Deserialize, Serialize};
-use crate::config::utils::{CommentStyle, parse_bool, write_boolean_option, write_section_header};
+use crate::config::utils::{CommentStyle, write_extra_options, parse_bool_value, write_boolean_option, write_section_header};
 use crate::config::models::core::ConfigSection;
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -10,6 +11,9 @@ pub struct EcosystemSection {
     pub no_update_news: bool,
     pub no_donation_nag: bool,
     pub enforce_permissions: bool,
+    /// Options hyprconf has no model for, in file order
+    #[serde(default)]
+    pub extra: IndexMap<String, String>,
 }
 
 pub fn parse_ecosystem_section(section: &mut EcosystemSection, content: &str) -> Result<()> {
@@ -22,10 +26,13 @@ pub fn parse_ecosystem_section(section: &mut EcosystemSection, content: &str) ->
             let value = value.trim();
             
             match key {
-                "no_update_news" => section.no_update_news = parse_bool(value),
-                "no_donation_nag" => section.no_donation_nag = parse_bool(value),
-                "enforce_permissions" => section.enforce_permissions = parse_bool(value),
-                _ => debug!("Unknown ecosystem setting: {}", key),
+                "no_update_news" => section.no_update_news = parse_bool_value(key, value)?,
+                "no_donation_nag" => section.no_donation_nag = parse_bool_value(key, value)?,
+                "enforce_permissions" => section.enforce_permissions = parse_bool_value(key, value)?,
+                _ => {
+                    debug!("Unknown ecosystem setting: {}", key);
+                    section.extra.insert(key.to_string(), value.to_string());
+                }
             }
         }
     }
@@ -47,6 +54,7 @@ pub fn write_ecosystem_section<W: Write>(
     write_boolean_option(writer, " enforce_permissions", ecosystem.enforce_permissions, None, comment_style)?;
     
     // Close the section
+    write_extra_options(writer, " ", &ecosystem.extra, comment_style)?;
     writeln!(writer, "}}")?;
     
     Ok(())
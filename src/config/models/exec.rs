@@ -0,0 +1,155 @@
+use std::io::{self, Write};
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::config::utils::CommentStyle;
+use crate::config::models::core::ConfigSection;
+use crate::config::models::rules::WindowRuleEffect;
+
+/// Which `exec` keyword a command was given with
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum ExecKind {
+    /// `exec`, run on every config reload
+    Exec,
+    /// `exec-once`, run at launch only
+    ExecOnce,
+    /// `execr`, like `exec` but without rule prefix handling
+    ExecR,
+    /// `execr-once`, like `exec-once` but without rule prefix handling
+    ExecROnce,
+    /// `exec-shutdown`, run when Hyprland exits
+    ExecShutdown,
+}
+
+impl ExecKind {
+    pub fn from_keyword(keyword: &str) -> Option<Self> {
+        match keyword {
+            "exec" => Some(Self::Exec),
+            "exec-once" => Some(Self::ExecOnce),
+            "execr" => Some(Self::ExecR),
+            "execr-once" => Some(Self::ExecROnce),
+            "exec-shutdown" => Some(Self::ExecShutdown),
+            _ => None,
+        }
+    }
+
+    pub fn keyword(&self) -> &'static str {
+        match self {
+            Self::Exec => "exec",
+            Self::ExecOnce => "exec-once",
+            Self::ExecR => "execr",
+            Self::ExecROnce => "execr-once",
+            Self::ExecShutdown => "exec-shutdown",
+        }
+    }
+
+    /// Whether a leading `[rules]` block is applied to the spawned window
+    pub fn takes_rules(&self) -> bool {
+        !matches!(self, Self::ExecR | Self::ExecROnce)
+    }
+}
+
+/// A command run through one of the `exec` keywords
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ExecCommand {
+    pub kind: ExecKind,
+    /// Window rules from a `[workspace 2 silent; float]` prefix
+    pub rules: Vec<WindowRuleEffect>,
+    pub command: String,
+}
+
+impl ExecCommand {
+    pub fn new(kind: ExecKind, command: &str) -> Self {
+        Self { kind, rules: Vec::new(), command: command.to_string() }
+    }
+}
+
+/// Parse the value of an `exec`-style line given its keyword
+pub fn parse_exec(keyword: &str, content: &str) -> Result<ExecCommand> {
+    let kind = ExecKind::from_keyword(keyword)
+        .ok_or_else(|| anyhow::anyhow!("Unknown exec keyword '{}'", keyword))?;
+    let content = content.trim();
+
+    if kind.takes_rules() {
+        if let Some(rest) = content.strip_prefix('[') {
+            let (rules, command) = rest.split_once(']')
+                .ok_or_else(|| anyhow::anyhow!("Unclosed rule block in '{}'", content))?;
+            return Ok(ExecCommand {
+                kind,
+                rules: rules
+                    .split(';')
+                    .map(str::trim)
+                    .filter(|rule| !rule.is_empty())
+                    .map(WindowRuleEffect::parse)
+                    .collect(),
+                command: command.trim().to_string(),
+            });
+        }
+    }
+
+    Ok(ExecCommand::new(kind, content))
+}
+
+/// Write an exec command to the provided writer
+pub fn write_exec<W: Write>(writer: &mut W, exec: &ExecCommand) -> io::Result<()> {
+    write!(writer, "{} = ", exec.kind.keyword())?;
+
+    if !exec.rules.is_empty() {
+        let rules: Vec<String> = exec.rules.iter().map(ToString::to_string).collect();
+        write!(writer, "[{}] ", rules.join("; "))?;
+    }
+
+    writeln!(writer, "{}", exec.command)?;
+    Ok(())
+}
+
+/// Implement ConfigSection for Vec<ExecCommand>
+impl ConfigSection for Vec<ExecCommand> {
+    fn write_section<W: Write>(&self, writer: &mut W, _comment_style: &CommentStyle) -> io::Result<()> {
+        for exec in self {
+            write_exec(writer, exec)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn written(exec: &ExecCommand) -> String {
+        let mut buffer = Vec::new();
+        write_exec(&mut buffer, exec).unwrap();
+        String::from_utf8(buffer).unwrap()
+    }
+
+    #[test]
+    fn rule_prefix_is_parsed_and_written_back() {
+        let exec = parse_exec("exec-once", "[workspace 2 silent; float] kitty").unwrap();
+        assert_eq!(exec.kind, ExecKind::ExecOnce);
+        assert_eq!(exec.rules.len(), 2);
+        assert_eq!(exec.command, "kitty");
+        assert_eq!(written(&exec), "exec-once = [workspace 2 silent; float] kitty\n");
+    }
+
+    #[test]
+    fn execr_keeps_brackets_in_the_command() {
+        let exec = parse_exec("execr", "[x] y").unwrap();
+        assert!(exec.rules.is_empty());
+        assert_eq!(exec.command, "[x] y");
+    }
+
+    #[test]
+    fn bad_input_is_rejected() {
+        assert!(parse_exec("exec-twice", "kitty").is_err());
+        assert!(parse_exec("exec", "[float kitty").is_err());
+    }
+
+    #[test]
+    fn keywords_round_trip() {
+        for keyword in ["exec", "exec-once", "execr", "execr-once", "exec-shutdown"] {
+            assert_eq!(ExecKind::from_keyword(keyword).unwrap().keyword(), keyword);
+        }
+    }
+}
@@ -1,13 +1,17 @@
 use std::io::{self, Write};
 use anyhow::Result;
+use indexmap::IndexMap;
 use log::debug;
 use serde::{Deserialize, Serialize};
-use crate::config::utils::{CommentStyle, parse_bool, write_boolean_option, write_section_header};
+use crate::config::utils::{CommentStyle, write_extra_options, parse_bool_value, write_boolean_option, write_section_header};
 use crate::config::models::core::ConfigSection;
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct ExperimentalSection {
     pub xx_color_management_v4: bool,
+    /// Options hyprconf has no model for, in file order
+    #[serde(default)]
+    pub extra: IndexMap<String, String>,
 }
 
 pub fn parse_experimental_section(section: &mut ExperimentalSection, content: &str) -> Result<()> {
@@ -22,8 +26,11 @@ pub fn parse_experimental_section(section: &mut ExperimentalSection, content: &s
             let value = value.trim();
             
             match key {
-                "xx_color_management_v4" => section.xx_color_management_v4 = parse_bool(value),
-                _ => debug!("Unknown experimental setting: {}", key),
+                "xx_color_management_v4" => section.xx_color_management_v4 = parse_bool_value(key, value)?,
+                _ => {
+                    debug!("Unknown experimental setting: {}", key);
+                    section.extra.insert(key.to_string(), value.to_string());
+                }
             }
         }
     }
@@ -43,6 +50,7 @@ pub fn write_experimental_section<W: Write>(
     write_boolean_option(writer, " xx_color_management_v4", experimental.xx_color_management_v4, None, comment_style)?;
     
     // Close the section
+    write_extra_options(writer, " ", &experimental.extra, comment_style)?;
     writeln!(writer, "}}")?;
     
     Ok(())
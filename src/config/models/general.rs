@@ -1,10 +1,11 @@
 use std::io::{self, Write};
 
 use anyhow::Result;
+use indexmap::IndexMap;
 use log::debug;
 use serde::{Deserialize, Serialize};
-use crate::config::utils::parse_bool;
-use crate::config::utils::{CommentStyle, write_boolean_option, write_option, write_section_header};
+use crate::config::utils::{Gradient, parse_bool_value, parse_value};
+use crate::config::utils::{CommentStyle, write_extra_options, write_boolean_option, write_option, write_section_header};
 
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -14,10 +15,10 @@ pub struct GeneralSection {
     pub gaps_in: String,
     pub gaps_out: String,
     pub gaps_workspaces: i32,
-    pub col_inactive_border: String,
-    pub col_active_border: String,
-    pub col_nogroup_border: String,
-    pub col_nogroup_border_active: String,
+    pub col_inactive_border: Gradient,
+    pub col_active_border: Gradient,
+    pub col_nogroup_border: Gradient,
+    pub col_nogroup_border_active: Gradient,
     pub layout: String,
     pub no_focus_fallback: bool,
     pub resize_on_border: bool,
@@ -26,6 +27,9 @@ pub struct GeneralSection {
     pub allow_tearing: bool,
     pub resize_corner: i32,
     pub snap: SnapSection,
+    /// Options hyprconf has no model for, in file order
+    #[serde(default)]
+    pub extra: IndexMap<String, String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -34,6 +38,9 @@ pub struct SnapSection {
     pub window_gap: i32,
     pub monitor_gap: i32,
     pub border_overlap: bool,
+    /// Options hyprconf has no model for, in file order
+    #[serde(default)]
+    pub extra: IndexMap<String, String>,
 }
 
 pub fn parse_general_section(section: &mut GeneralSection, content: &str) -> Result<()> {
@@ -49,23 +56,26 @@ pub fn parse_general_section(section: &mut GeneralSection, content: &str) -> Res
             let key = key.trim();
             let value = value.trim();
             match key {
-                "border_size" => section.border_size = value.parse().unwrap_or(1),
-                "no_border_on_floating" => section.no_border_on_floating = parse_bool(value),
+                "border_size" => section.border_size = parse_value(key, value)?,
+                "no_border_on_floating" => section.no_border_on_floating = parse_bool_value(key, value)?,
                 "gaps_in" => section.gaps_in = value.to_string(),
                 "gaps_out" => section.gaps_out = value.to_string(),
-                "gaps_workspaces" => section.gaps_workspaces = value.parse().unwrap_or(0),
-                "col.inactive_border" => section.col_inactive_border = value.to_string(),
-                "col.active_border" => section.col_active_border = value.to_string(),
-                "col.nogroup_border" => section.col_nogroup_border = value.to_string(),
-                "col.nogroup_border_active" => section.col_nogroup_border_active = value.to_string(),
+                "gaps_workspaces" => section.gaps_workspaces = parse_value(key, value)?,
+                "col.inactive_border" => section.col_inactive_border = parse_value(key, value)?,
+                "col.active_border" => section.col_active_border = parse_value(key, value)?,
+                "col.nogroup_border" => section.col_nogroup_border = parse_value(key, value)?,
+                "col.nogroup_border_active" => section.col_nogroup_border_active = parse_value(key, value)?,
                 "layout" => section.layout = value.to_string(),
-                "no_focus_fallback" => section.no_focus_fallback = parse_bool(value),
-                "resize_on_border" => section.resize_on_border = parse_bool(value),
-                "extend_border_grab_area" => section.extend_border_grab_area = value.parse().unwrap_or(15),
-                "hover_icon_on_border" => section.hover_icon_on_border = parse_bool(value),
-                "allow_tearing" => section.allow_tearing = parse_bool(value),
-                "resize_corner" => section.resize_corner = value.parse().unwrap_or(0),
-                _ => debug!("Unknown general setting: {}", key),
+                "no_focus_fallback" => section.no_focus_fallback = parse_bool_value(key, value)?,
+                "resize_on_border" => section.resize_on_border = parse_bool_value(key, value)?,
+                "extend_border_grab_area" => section.extend_border_grab_area = parse_value(key, value)?,
+                "hover_icon_on_border" => section.hover_icon_on_border = parse_bool_value(key, value)?,
+                "allow_tearing" => section.allow_tearing = parse_bool_value(key, value)?,
+                "resize_corner" => section.resize_corner = parse_value(key, value)?,
+                _ => {
+                    debug!("Unknown general setting: {}", key);
+                    section.extra.insert(key.to_string(), value.to_string());
+                }
             }
         }
     }
@@ -99,7 +109,9 @@ pub fn write_general_section<W: Write>(
     write_option(writer, "  window_gap", &general.snap.window_gap, None, comment_style)?;
     write_option(writer, "  monitor_gap", &general.snap.monitor_gap, None, comment_style)?;
     write_boolean_option(writer, "  border_overlap", general.snap.border_overlap, None, comment_style)?;
+    write_extra_options(writer, "  ", &general.snap.extra, comment_style)?;
     writeln!(writer, " }}")?;
+    write_extra_options(writer, " ", &general.extra, comment_style)?;
     writeln!(writer, "}}")?;
     Ok(())
 }
@@ -114,11 +126,14 @@ pub fn parse_snap_section(section: &mut SnapSection, content: &str) -> Result<()
             let key = key.trim();
             let value = value.trim();
             match key {
-                "enabled" => section.enabled = parse_bool(value),
-                "window_gap" => section.window_gap = value.parse().unwrap_or(10),
-                "monitor_gap" => section.monitor_gap = value.parse().unwrap_or(10),
-                "border_overlap" => section.border_overlap = parse_bool(value),
-                _ => debug!("Unknown snap setting: {}", key),
+                "enabled" => section.enabled = parse_bool_value(key, value)?,
+                "window_gap" => section.window_gap = parse_value(key, value)?,
+                "monitor_gap" => section.monitor_gap = parse_value(key, value)?,
+                "border_overlap" => section.border_overlap = parse_bool_value(key, value)?,
+                _ => {
+                    debug!("Unknown snap setting: {}", key);
+                    section.extra.insert(key.to_string(), value.to_string());
+                }
             }
         }
     }
@@ -134,6 +149,7 @@ pub fn write_snap_section<W: Write>(
     write_option(writer, " window_gap", &snap.window_gap, None, comment_style)?;
     write_option(writer, " monitor_gap", &snap.monitor_gap, None, comment_style)?;
     write_boolean_option(writer, " border_overlap", snap.border_overlap, None, comment_style)?;
+    write_extra_options(writer, " ", &snap.extra, comment_style)?;
     writeln!(writer, "}}")?;
     Ok(())
 }
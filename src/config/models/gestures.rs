@@ -1,9 +1,12 @@
+use std::fmt;
 use std::io::{self, Write};
-use anyhow::Result;
+use std::str::FromStr;
+use anyhow::{Result, anyhow};
+use indexmap::IndexMap;
 use log::debug;
 use serde::{Deserialize, Serialize};
-use crate::config::utils::parse_bool;
-use crate::config::utils::{CommentStyle, write_boolean_option, write_option, write_section_header};
+use crate::config::utils::{InvalidValue, parse_bool_value, parse_value};
+use crate::config::utils::{CommentStyle, write_extra_options, write_boolean_option, write_option, write_section_header};
 
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -22,6 +25,217 @@ pub struct GesturesSection {
     pub workspace_swipe_direction_lock_threshold: i32,
     pub workspace_swipe_forever: bool,
     pub workspace_swipe_use_r: bool,
+    /// `gesture = ...` lines, in file order
+    #[serde(default)]
+    pub gestures: Vec<Gesture>,
+    /// Options hyprconf has no model for, in file order
+    #[serde(default)]
+    pub extra: IndexMap<String, String>,
+}
+
+/// Fewest and most fingers a touchpad gesture can use
+const FINGERS: std::ops::RangeInclusive<u32> = 2..=5;
+
+/// A touchpad gesture binding, e.g. `gesture = 3, horizontal, workspace`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Gesture {
+    pub fingers: u32,
+    pub direction: GestureDirection,
+    /// Modifier keys that must be held, e.g. `SUPER`
+    pub modifiers: Option<String>,
+    /// Multiplier for how far the gesture has to move
+    pub scale: Option<f32>,
+    pub action: GestureAction,
+}
+
+/// Which movement triggers a gesture
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GestureDirection {
+    /// A swipe in any direction
+    Swipe,
+    Horizontal,
+    Vertical,
+    Left,
+    Right,
+    Up,
+    Down,
+    /// A pinch in either direction
+    Pinch,
+    PinchIn,
+    PinchOut,
+}
+
+const GESTURE_DIRECTIONS: &[(&str, GestureDirection)] = &[
+    ("swipe", GestureDirection::Swipe),
+    ("horizontal", GestureDirection::Horizontal),
+    ("vertical", GestureDirection::Vertical),
+    ("left", GestureDirection::Left),
+    ("right", GestureDirection::Right),
+    ("up", GestureDirection::Up),
+    ("down", GestureDirection::Down),
+    ("pinch", GestureDirection::Pinch),
+    ("pinchin", GestureDirection::PinchIn),
+    ("pinchout", GestureDirection::PinchOut),
+];
+
+impl FromStr for GestureDirection {
+    type Err = InvalidValue;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        GESTURE_DIRECTIONS.iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(s))
+            .map(|(_, direction)| *direction)
+            .ok_or_else(|| InvalidValue {
+                key: "gesture".to_string(),
+                value: s.to_string(),
+                expected: "a direction: swipe, horizontal, vertical, left, right, up, down, pinch, pinchin or pinchout",
+            })
+    }
+}
+
+impl fmt::Display for GestureDirection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = GESTURE_DIRECTIONS.iter().find(|(_, d)| d == self).map_or("", |(name, _)| name);
+        write!(f, "{}", name)
+    }
+}
+
+/// What a gesture does
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum GestureAction {
+    /// Swipe between workspaces
+    Workspace,
+    /// Move the active window
+    Move,
+    /// Resize the active window
+    Resize,
+    /// Close the active window
+    Close,
+    /// Toggle a special workspace
+    Special(String),
+    /// Toggle fullscreen, or `maximize`
+    Fullscreen(Option<String>),
+    /// Toggle floating, or force `float` or `tile`
+    Float(Option<String>),
+    /// Run a dispatcher once the gesture completes
+    Dispatcher { dispatcher: String, args: String },
+    /// Remove a gesture bound earlier
+    Unset,
+}
+
+impl GestureAction {
+    /// Build an action from its name and the fields following it
+    pub fn from_parts(name: &str, args: &[&str]) -> Result<Self> {
+        let joined = args.join(", ");
+        let optional = |allowed: &[&str]| -> Result<Option<String>> {
+            match args {
+                [] => Ok(None),
+                [arg] if allowed.contains(arg) => Ok(Some(arg.to_string())),
+                _ => Err(anyhow!("gesture action `{}` takes {}, got `{}`", name, allowed.join(" or "), joined)),
+            }
+        };
+        let none = || -> Result<()> {
+            match args {
+                [] => Ok(()),
+                _ => Err(anyhow!("gesture action `{}` takes no arguments, got `{}`", name, joined)),
+            }
+        };
+
+        Ok(match name {
+            "workspace" => none().map(|_| Self::Workspace)?,
+            "move" => none().map(|_| Self::Move)?,
+            "resize" => none().map(|_| Self::Resize)?,
+            "close" => none().map(|_| Self::Close)?,
+            "unset" => none().map(|_| Self::Unset)?,
+            "special" => match args {
+                [name] if !name.is_empty() => Self::Special(name.to_string()),
+                _ => return Err(anyhow!("gesture action `special` needs a workspace name")),
+            },
+            "fullscreen" => Self::Fullscreen(optional(&["maximize"])?),
+            "float" => Self::Float(optional(&["float", "tile"])?),
+            "dispatcher" => match args.split_first() {
+                Some((dispatcher, rest)) if !dispatcher.is_empty() && !dispatcher.contains(char::is_whitespace) => {
+                    Self::Dispatcher { dispatcher: dispatcher.to_string(), args: rest.join(", ") }
+                }
+                _ => return Err(anyhow!("gesture action `dispatcher` needs a dispatcher name, got `{}`", joined)),
+            },
+            "" => return Err(anyhow!("gesture has no action")),
+            other => return Err(anyhow!(
+                "unknown gesture action `{}`: expected workspace, move, resize, close, special, fullscreen, float, dispatcher or unset",
+                other
+            )),
+        })
+    }
+}
+
+impl fmt::Display for GestureAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Workspace => write!(f, "workspace"),
+            Self::Move => write!(f, "move"),
+            Self::Resize => write!(f, "resize"),
+            Self::Close => write!(f, "close"),
+            Self::Unset => write!(f, "unset"),
+            Self::Special(name) => write!(f, "special, {}", name),
+            Self::Fullscreen(None) => write!(f, "fullscreen"),
+            Self::Fullscreen(Some(mode)) => write!(f, "fullscreen, {}", mode),
+            Self::Float(None) => write!(f, "float"),
+            Self::Float(Some(mode)) => write!(f, "float, {}", mode),
+            Self::Dispatcher { dispatcher, args } if args.is_empty() => write!(f, "dispatcher, {}", dispatcher),
+            Self::Dispatcher { dispatcher, args } => write!(f, "dispatcher, {}, {}", dispatcher, args),
+        }
+    }
+}
+
+impl fmt::Display for Gesture {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}, {}", self.fingers, self.direction)?;
+        if let Some(modifiers) = &self.modifiers {
+            write!(f, ", mod: {}", modifiers)?;
+        }
+        if let Some(scale) = self.scale {
+            write!(f, ", scale: {}", scale)?;
+        }
+        write!(f, ", {}", self.action)
+    }
+}
+
+/// Parse the value of a `gesture = ` line, e.g. `3, up, mod: SUPER, scale: 1.5, fullscreen`
+pub fn parse_gesture(value: &str) -> Result<Gesture> {
+    let fields: Vec<&str> = value.split(',').map(str::trim).collect();
+    let [fingers, direction, rest @ ..] = fields.as_slice() else {
+        return Err(anyhow!("gesture needs a finger count, a direction and an action"));
+    };
+
+    let count: u32 = parse_value("gesture", fingers)?;
+    if !FINGERS.contains(&count) {
+        return Err(InvalidValue {
+            key: "gesture".to_string(),
+            value: fingers.to_string(),
+            expected: "a finger count from 2 to 5",
+        }.into());
+    }
+    let direction: GestureDirection = direction.parse()?;
+
+    let mut modifiers = None;
+    let mut scale = None;
+    let mut rest = rest;
+    while let Some((field, tail)) = rest.split_first() {
+        if let Some(mods) = field.strip_prefix("mod:") {
+            modifiers = Some(mods.trim().to_string());
+        } else if let Some(value) = field.strip_prefix("scale:") {
+            scale = Some(parse_value("gesture", value)?);
+        } else {
+            break;
+        }
+        rest = tail;
+    }
+
+    let (action, args) = rest.split_first()
+        .ok_or_else(|| anyhow!("gesture needs an action"))?;
+    let action = GestureAction::from_parts(action, args)?;
+    Ok(Gesture { fingers: count, direction, modifiers, scale, action })
 }
 
 pub fn parse_gestures_section(section: &mut GesturesSection, content: &str) -> Result<()> {
@@ -36,21 +250,24 @@ pub fn parse_gestures_section(section: &mut GesturesSection, content: &str) -> R
             let value = value.trim();
             
             match key {
-                "workspace_swipe" => section.workspace_swipe = parse_bool(value),
-                "workspace_swipe_fingers" => section.workspace_swipe_fingers = value.parse().unwrap_or(3),
-                "workspace_swipe_min_fingers" => section.workspace_swipe_min_fingers = parse_bool(value),
-                "workspace_swipe_distance" => section.workspace_swipe_distance = value.parse().unwrap_or(300),
-                "workspace_swipe_touch" => section.workspace_swipe_touch = parse_bool(value),
-                "workspace_swipe_invert" => section.workspace_swipe_invert = parse_bool(value),
-                "workspace_swipe_touch_invert" => section.workspace_swipe_touch_invert = parse_bool(value),
-                "workspace_swipe_min_speed_to_force" => section.workspace_swipe_min_speed_to_force = value.parse().unwrap_or(30),
-                "workspace_swipe_cancel_ratio" => section.workspace_swipe_cancel_ratio = value.parse().unwrap_or(0.5),
-                "workspace_swipe_create_new" => section.workspace_swipe_create_new = parse_bool(value),
-                "workspace_swipe_direction_lock" => section.workspace_swipe_direction_lock = parse_bool(value),
-                "workspace_swipe_direction_lock_threshold" => section.workspace_swipe_direction_lock_threshold = value.parse().unwrap_or(10),
-                "workspace_swipe_forever" => section.workspace_swipe_forever = parse_bool(value),
-                "workspace_swipe_use_r" => section.workspace_swipe_use_r = parse_bool(value),
-                _ => debug!("Unknown gestures setting: {}", key),
+                "workspace_swipe" => section.workspace_swipe = parse_bool_value(key, value)?,
+                "workspace_swipe_fingers" => section.workspace_swipe_fingers = parse_value(key, value)?,
+                "workspace_swipe_min_fingers" => section.workspace_swipe_min_fingers = parse_bool_value(key, value)?,
+                "workspace_swipe_distance" => section.workspace_swipe_distance = parse_value(key, value)?,
+                "workspace_swipe_touch" => section.workspace_swipe_touch = parse_bool_value(key, value)?,
+                "workspace_swipe_invert" => section.workspace_swipe_invert = parse_bool_value(key, value)?,
+                "workspace_swipe_touch_invert" => section.workspace_swipe_touch_invert = parse_bool_value(key, value)?,
+                "workspace_swipe_min_speed_to_force" => section.workspace_swipe_min_speed_to_force = parse_value(key, value)?,
+                "workspace_swipe_cancel_ratio" => section.workspace_swipe_cancel_ratio = parse_value(key, value)?,
+                "workspace_swipe_create_new" => section.workspace_swipe_create_new = parse_bool_value(key, value)?,
+                "workspace_swipe_direction_lock" => section.workspace_swipe_direction_lock = parse_bool_value(key, value)?,
+                "workspace_swipe_direction_lock_threshold" => section.workspace_swipe_direction_lock_threshold = parse_value(key, value)?,
+                "workspace_swipe_forever" => section.workspace_swipe_forever = parse_bool_value(key, value)?,
+                "workspace_swipe_use_r" => section.workspace_swipe_use_r = parse_bool_value(key, value)?,
+                _ => {
+                    debug!("Unknown gestures setting: {}", key);
+                    section.extra.insert(key.to_string(), value.to_string());
+                }
             }
         }
     }
@@ -65,9 +282,12 @@ pub fn write_section<W: Write>(
 ) -> io::Result<()> {
     write_section_header(writer, "gestures {", comment_style)?;
     
-    write_boolean_option(writer, " workspace_swipe", gestures.workspace_swipe, None, comment_style)?;
-    write_option(writer, " workspace_swipe_fingers", &gestures.workspace_swipe_fingers.to_string(), None, comment_style)?;
-    write_boolean_option(writer, " workspace_swipe_min_fingers", gestures.workspace_swipe_min_fingers, None, comment_style)?;
+    // Replaced by `gesture` lines in Hyprland 0.51, so only written while still in use
+    if gestures.workspace_swipe {
+        write_boolean_option(writer, " workspace_swipe", gestures.workspace_swipe, None, comment_style)?;
+        write_option(writer, " workspace_swipe_fingers", &gestures.workspace_swipe_fingers.to_string(), None, comment_style)?;
+        write_boolean_option(writer, " workspace_swipe_min_fingers", gestures.workspace_swipe_min_fingers, None, comment_style)?;
+    }
     write_option(writer, " workspace_swipe_distance", &gestures.workspace_swipe_distance.to_string(), None, comment_style)?;
     write_boolean_option(writer, " workspace_swipe_touch", gestures.workspace_swipe_touch, None, comment_style)?;
     write_boolean_option(writer, " workspace_swipe_invert", gestures.workspace_swipe_invert, None, comment_style)?;
@@ -80,6 +300,59 @@ pub fn write_section<W: Write>(
     write_boolean_option(writer, " workspace_swipe_forever", gestures.workspace_swipe_forever, None, comment_style)?;
     write_boolean_option(writer, " workspace_swipe_use_r", gestures.workspace_swipe_use_r, None, comment_style)?;
     
+    write_extra_options(writer, " ", &gestures.extra, comment_style)?;
     writeln!(writer, "}}")?;
+
+    for gesture in &gestures.gestures {
+        writeln!(writer, "gesture = {}", gesture)?;
+    }
     Ok(())
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::parser::ConfigParser;
+
+    #[test]
+    fn gestures_parse_and_write_back() {
+        for line in [
+            "3, horizontal, workspace",
+            "4, pinch, fullscreen, maximize",
+            "3, up, mod: SUPER, scale: 1.5, special, magic",
+            "3, down, dispatcher, exec, kitty",
+            "4, swipe, unset",
+        ] {
+            assert_eq!(parse_gesture(line).unwrap().to_string(), line);
+        }
+
+        let gesture = parse_gesture("3, UP, mod: SUPER, float, tile").unwrap();
+        assert_eq!(gesture.direction, GestureDirection::Up);
+        assert_eq!(gesture.modifiers.as_deref(), Some("SUPER"));
+        assert_eq!(gesture.action, GestureAction::Float(Some("tile".to_string())));
+    }
+
+    #[test]
+    fn invalid_gestures_are_rejected() {
+        for line in [
+            "1, horizontal, workspace",
+            "6, horizontal, workspace",
+            "3, sideways, workspace",
+            "3, horizontal, teleport",
+            "3, horizontal, special",
+            "3, horizontal, workspace, 2",
+            "3, pinch, fullscreen, minimize",
+            "3, horizontal, scale: fast, workspace",
+            "3, horizontal",
+        ] {
+            assert!(parse_gesture(line).is_err(), "{}", line);
+        }
+    }
+
+    #[test]
+    fn gesture_lines_are_read_into_the_section() {
+        let config = ConfigParser::parse_string("gesture = 3, horizontal, workspace\ngesture = 4, pinch, close\n").unwrap();
+        let actions: Vec<&GestureAction> = config.gestures.gestures.iter().map(|g| &g.action).collect();
+        assert_eq!(actions, [&GestureAction::Workspace, &GestureAction::Close]);
+    }
+}
@@ -1,10 +1,11 @@
 use std::io::{self, Write};
 
 use anyhow::Result;
+use indexmap::IndexMap;
 use log::debug;
 use serde::{Deserialize, Serialize};
-use crate::config::utils::parse_bool;
-use crate::config::utils::{CommentStyle, write_boolean_option, write_option, write_section_header};
+use crate::config::utils::{Color, Gradient, parse_bool_value, parse_value};
+use crate::config::utils::{CommentStyle, write_extra_options, write_boolean_option, write_option, write_section_header};
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct GroupSection {
@@ -16,11 +17,14 @@ pub struct GroupSection {
     pub merge_groups_on_groupbar: bool,
     pub merge_floated_into_tiled_on_groupbar: bool,
     pub group_on_movetoworkspace: bool,
-    pub col_border_active: String,
-    pub col_border_inactive: String,
-    pub col_border_locked_active: String,
-    pub col_border_locked_inactive: String,
+    pub col_border_active: Gradient,
+    pub col_border_inactive: Gradient,
+    pub col_border_locked_active: Gradient,
+    pub col_border_locked_inactive: Gradient,
     pub groupbar: GroupbarSection,
+    /// Options hyprconf has no model for, in file order
+    #[serde(default)]
+    pub extra: IndexMap<String, String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -40,14 +44,17 @@ pub struct GroupbarSection {
     pub gradient_rounding: i32,
     pub round_only_edges: bool,
     pub gradient_round_only_edges: bool,
-    pub text_color: String,
-    pub col_active: String,
-    pub col_inactive: String,
-    pub col_locked_active: String,
-    pub col_locked_inactive: String,
+    pub text_color: Color,
+    pub col_active: Gradient,
+    pub col_inactive: Gradient,
+    pub col_locked_active: Gradient,
+    pub col_locked_inactive: Gradient,
     pub gaps_in: i32,
     pub gaps_out: i32,
     pub keep_upper_gap: bool,
+    /// Options hyprconf has no model for, in file order
+    #[serde(default)]
+    pub extra: IndexMap<String, String>,
 }
 
 pub fn parse_group_section(section: &mut GroupSection, content: &str) -> Result<()> {
@@ -63,19 +70,22 @@ pub fn parse_group_section(section: &mut GroupSection, content: &str) -> Result<
             let key = key.trim();
             let value = value.trim();
             match key {
-                "auto_group" => section.auto_group = parse_bool(value),
-                "insert_after_current" => section.insert_after_current = parse_bool(value),
-                "focus_removed_window" => section.focus_removed_window = parse_bool(value),
-                "drag_into_group" => section.drag_into_group = value.parse().unwrap_or(1),
-                "merge_groups_on_drag" => section.merge_groups_on_drag = parse_bool(value),
-                "merge_groups_on_groupbar" => section.merge_groups_on_groupbar = parse_bool(value),
-                "merge_floated_into_tiled_on_groupbar" => section.merge_floated_into_tiled_on_groupbar = parse_bool(value),
-                "group_on_movetoworkspace" => section.group_on_movetoworkspace = parse_bool(value),
-                "col.border_active" => section.col_border_active = value.to_string(),
-                "col.border_inactive" => section.col_border_inactive = value.to_string(),
-                "col.border_locked_active" => section.col_border_locked_active = value.to_string(),
-                "col.border_locked_inactive" => section.col_border_locked_inactive = value.to_string(),
-                _ => debug!("Unknown group setting: {}", key),
+                "auto_group" => section.auto_group = parse_bool_value(key, value)?,
+                "insert_after_current" => section.insert_after_current = parse_bool_value(key, value)?,
+                "focus_removed_window" => section.focus_removed_window = parse_bool_value(key, value)?,
+                "drag_into_group" => section.drag_into_group = parse_value(key, value)?,
+                "merge_groups_on_drag" => section.merge_groups_on_drag = parse_bool_value(key, value)?,
+                "merge_groups_on_groupbar" => section.merge_groups_on_groupbar = parse_bool_value(key, value)?,
+                "merge_floated_into_tiled_on_groupbar" => section.merge_floated_into_tiled_on_groupbar = parse_bool_value(key, value)?,
+                "group_on_movetoworkspace" => section.group_on_movetoworkspace = parse_bool_value(key, value)?,
+                "col.border_active" => section.col_border_active = parse_value(key, value)?,
+                "col.border_inactive" => section.col_border_inactive = parse_value(key, value)?,
+                "col.border_locked_active" => section.col_border_locked_active = parse_value(key, value)?,
+                "col.border_locked_inactive" => section.col_border_locked_inactive = parse_value(key, value)?,
+                _ => {
+                    debug!("Unknown group setting: {}", key);
+                    section.extra.insert(key.to_string(), value.to_string());
+                }
             }
         }
     }
@@ -92,30 +102,33 @@ pub fn parse_groupbar_section(section: &mut GroupbarSection, content: &str) -> R
             let key = key.trim();
             let value = value.trim();
             match key {
-                "enabled" => section.enabled = parse_bool(value),
+                "enabled" => section.enabled = parse_bool_value(key, value)?,
                 "font_family" => section.font_family = value.to_string(),
-                "font_size" => section.font_size = value.parse().unwrap_or(8),
-                "gradients" => section.gradients = parse_bool(value),
-                "height" => section.height = value.parse().unwrap_or(14),
-                "indicator_height" => section.indicator_height = value.parse().unwrap_or(3),
-                "stacked" => section.stacked = parse_bool(value),
-                "priority" => section.priority = value.parse().unwrap_or(3),
-                "render_titles" => section.render_titles = parse_bool(value),
-                "text_offset" => section.text_offset = value.parse().unwrap_or(0),
-                "scrolling" => section.scrolling = parse_bool(value),
-                "rounding" => section.rounding = value.parse().unwrap_or(1),
-                "gradient_rounding" => section.gradient_rounding = value.parse().unwrap_or(2),
-                "round_only_edges" => section.round_only_edges = parse_bool(value),
-                "gradient_round_only_edges" => section.gradient_round_only_edges = parse_bool(value),
-                "text_color" => section.text_color = value.to_string(),
-                "col.active" => section.col_active = value.to_string(),
-                "col.inactive" => section.col_inactive = value.to_string(),
-                "col.locked_active" => section.col_locked_active = value.to_string(),
-                "col.locked_inactive" => section.col_locked_inactive = value.to_string(),
-                "gaps_in" => section.gaps_in = value.parse().unwrap_or(2),
-                "gaps_out" => section.gaps_out = value.parse().unwrap_or(2),
-                "keep_upper_gap" => section.keep_upper_gap = parse_bool(value),
-                _ => debug!("Unknown groupbar setting: {}", key),
+                "font_size" => section.font_size = parse_value(key, value)?,
+                "gradients" => section.gradients = parse_bool_value(key, value)?,
+                "height" => section.height = parse_value(key, value)?,
+                "indicator_height" => section.indicator_height = parse_value(key, value)?,
+                "stacked" => section.stacked = parse_bool_value(key, value)?,
+                "priority" => section.priority = parse_value(key, value)?,
+                "render_titles" => section.render_titles = parse_bool_value(key, value)?,
+                "text_offset" => section.text_offset = parse_value(key, value)?,
+                "scrolling" => section.scrolling = parse_bool_value(key, value)?,
+                "rounding" => section.rounding = parse_value(key, value)?,
+                "gradient_rounding" => section.gradient_rounding = parse_value(key, value)?,
+                "round_only_edges" => section.round_only_edges = parse_bool_value(key, value)?,
+                "gradient_round_only_edges" => section.gradient_round_only_edges = parse_bool_value(key, value)?,
+                "text_color" => section.text_color = parse_value(key, value)?,
+                "col.active" => section.col_active = parse_value(key, value)?,
+                "col.inactive" => section.col_inactive = parse_value(key, value)?,
+                "col.locked_active" => section.col_locked_active = parse_value(key, value)?,
+                "col.locked_inactive" => section.col_locked_inactive = parse_value(key, value)?,
+                "gaps_in" => section.gaps_in = parse_value(key, value)?,
+                "gaps_out" => section.gaps_out = parse_value(key, value)?,
+                "keep_upper_gap" => section.keep_upper_gap = parse_bool_value(key, value)?,
+                _ => {
+                    debug!("Unknown groupbar setting: {}", key);
+                    section.extra.insert(key.to_string(), value.to_string());
+                }
             }
         }
     }
@@ -164,7 +177,9 @@ pub fn write_section<W: Write>(
     write_option(writer, "  gaps_in", &group.groupbar.gaps_in, None, comment_style)?;
     write_option(writer, "  gaps_out", &group.groupbar.gaps_out, None, comment_style)?;
     write_boolean_option(writer, "  keep_upper_gap", group.groupbar.keep_upper_gap, None, comment_style)?;
+    write_extra_options(writer, "  ", &group.groupbar.extra, comment_style)?;
     writeln!(writer, " }}")?;
+    write_extra_options(writer, " ", &group.extra, comment_style)?;
     writeln!(writer, "}}")?;
     Ok(())
 }
@@ -1,9 +1,10 @@
 use std::io::{self, Write};
 use anyhow::Result;
+use indexmap::IndexMap;
 use log::debug;
 use serde::{Deserialize, Serialize};
-use crate::config::utils::parse_bool;
-use crate::config::utils::{CommentStyle, write_boolean_option, write_option, write_section_header};
+use crate::config::utils::{parse_bool_value, parse_value};
+use crate::config::utils::{CommentStyle, write_extra_options, write_boolean_option, write_option, write_section_header};
 
 
 /// Input section with touchpad, touchdevice, and tablet subcategories
@@ -41,6 +42,9 @@ pub struct InputSection {
     pub touchpad: TouchpadSection,
     pub touchdevice: TouchdeviceSection,
     pub tablet: TabletSection,
+    /// Options hyprconf has no model for, in file order
+    #[serde(default)]
+    pub extra: IndexMap<String, String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -56,6 +60,9 @@ pub struct TouchpadSection {
     pub tap_and_drag: bool,
     pub flip_x: bool,
     pub flip_y: bool,
+    /// Options hyprconf has no model for, in file order
+    #[serde(default)]
+    pub extra: IndexMap<String, String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -63,6 +70,9 @@ pub struct TouchdeviceSection {
     pub transform: i32,
     pub output: String,
     pub enabled: bool,
+    /// Options hyprconf has no model for, in file order
+    #[serde(default)]
+    pub extra: IndexMap<String, String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -76,6 +86,9 @@ pub struct TabletSection {
     pub left_handed: bool,
     pub active_area_size: (f32, f32),
     pub active_area_position: (f32, f32),
+    /// Options hyprconf has no model for, in file order
+    #[serde(default)]
+    pub extra: IndexMap<String, String>,
 }
 
 pub fn parse_input_section(section: &mut InputSection, content: &str) -> Result<()> {
@@ -101,30 +114,33 @@ pub fn parse_input_section(section: &mut InputSection, content: &str) -> Result<
                 "kb_options" => section.kb_options = value.to_string(),
                 "kb_rules" => section.kb_rules = value.to_string(),
                 "kb_file" => section.kb_file = value.to_string(),
-                "numlock_by_default" => section.numlock_by_default = parse_bool(value),
-                "resolve_binds_by_sym" => section.resolve_binds_by_sym = parse_bool(value),
-                "repeat_rate" => section.repeat_rate = value.parse().unwrap_or(25),
-                "repeat_delay" => section.repeat_delay = value.parse().unwrap_or(600),
-                "sensitivity" => section.sensitivity = value.parse().unwrap_or(0.0),
+                "numlock_by_default" => section.numlock_by_default = parse_bool_value(key, value)?,
+                "resolve_binds_by_sym" => section.resolve_binds_by_sym = parse_bool_value(key, value)?,
+                "repeat_rate" => section.repeat_rate = parse_value(key, value)?,
+                "repeat_delay" => section.repeat_delay = parse_value(key, value)?,
+                "sensitivity" => section.sensitivity = parse_value(key, value)?,
                 "accel_profile" => section.accel_profile = value.to_string(),
-                "force_no_accel" => section.force_no_accel = parse_bool(value),
-                "left_handed" => section.left_handed = parse_bool(value),
+                "force_no_accel" => section.force_no_accel = parse_bool_value(key, value)?,
+                "left_handed" => section.left_handed = parse_bool_value(key, value)?,
                 "scroll_points" => section.scroll_points = value.to_string(),
                 "scroll_method" => section.scroll_method = value.to_string(),
-                "scroll_button" => section.scroll_button = value.parse().unwrap_or(0),
-                "scroll_button_lock" => section.scroll_button_lock = parse_bool(value),
-                "scroll_factor" => section.scroll_factor = value.parse().unwrap_or(1.0),
-                "natural_scroll" => section.natural_scroll = parse_bool(value),
-                "follow_mouse" => section.follow_mouse = value.parse().unwrap_or(1),
-                "follow_mouse_threshold" => section.follow_mouse_threshold = value.parse().unwrap_or(0.0),
-                "focus_on_close" => section.focus_on_close = value.parse().unwrap_or(0),
-                "mouse_refocus" => section.mouse_refocus = parse_bool(value),
-                "float_switch_override_focus" => section.float_switch_override_focus = value.parse().unwrap_or(1),
-                "special_fallthrough" => section.special_fallthrough = parse_bool(value),
-                "off_window_axis_events" => section.off_window_axis_events = value.parse().unwrap_or(1),
-                "emulate_discrete_scroll" => section.emulate_discrete_scroll = value.parse().unwrap_or(1),
-                "drag_threshold" => section.drag_threshold = value.parse().unwrap_or(0),
-                _ => debug!("Unknown input setting: {}", key),
+                "scroll_button" => section.scroll_button = parse_value(key, value)?,
+                "scroll_button_lock" => section.scroll_button_lock = parse_bool_value(key, value)?,
+                "scroll_factor" => section.scroll_factor = parse_value(key, value)?,
+                "natural_scroll" => section.natural_scroll = parse_bool_value(key, value)?,
+                "follow_mouse" => section.follow_mouse = parse_value(key, value)?,
+                "follow_mouse_threshold" => section.follow_mouse_threshold = parse_value(key, value)?,
+                "focus_on_close" => section.focus_on_close = parse_value(key, value)?,
+                "mouse_refocus" => section.mouse_refocus = parse_bool_value(key, value)?,
+                "float_switch_override_focus" => section.float_switch_override_focus = parse_value(key, value)?,
+                "special_fallthrough" => section.special_fallthrough = parse_bool_value(key, value)?,
+                "off_window_axis_events" => section.off_window_axis_events = parse_value(key, value)?,
+                "emulate_discrete_scroll" => section.emulate_discrete_scroll = parse_value(key, value)?,
+                "drag_threshold" => section.drag_threshold = parse_value(key, value)?,
+                _ => {
+                    debug!("Unknown input setting: {}", key);
+                    section.extra.insert(key.to_string(), value.to_string());
+                }
             }
         }
     }
@@ -144,18 +160,21 @@ pub fn parse_touchpad_section(section: &mut TouchpadSection, content: &str) -> R
             let value = value.trim();
             
             match key {
-                "disable_while_typing" => section.disable_while_typing = parse_bool(value),
-                "natural_scroll" => section.natural_scroll = parse_bool(value),
-                "scroll_factor" => section.scroll_factor = value.parse().unwrap_or(1.0),
-                "middle_button_emulation" => section.middle_button_emulation = parse_bool(value),
+                "disable_while_typing" => section.disable_while_typing = parse_bool_value(key, value)?,
+                "natural_scroll" => section.natural_scroll = parse_bool_value(key, value)?,
+                "scroll_factor" => section.scroll_factor = parse_value(key, value)?,
+                "middle_button_emulation" => section.middle_button_emulation = parse_bool_value(key, value)?,
                 "tap_button_map" => section.tap_button_map = value.to_string(),
-                "clickfinger_behavior" => section.clickfinger_behavior = parse_bool(value),
-                "tap-to-click" | "tap_to_click" => section.tap_to_click = parse_bool(value),
-                "drag_lock" => section.drag_lock = parse_bool(value),
-                "tap-and-drag" | "tap_and_drag" => section.tap_and_drag = parse_bool(value),
-                "flip_x" => section.flip_x = parse_bool(value),
-                "flip_y" => section.flip_y = parse_bool(value),
-                _ => debug!("Unknown touchpad setting: {}", key),
+                "clickfinger_behavior" => section.clickfinger_behavior = parse_bool_value(key, value)?,
+                "tap-to-click" | "tap_to_click" => section.tap_to_click = parse_bool_value(key, value)?,
+                "drag_lock" => section.drag_lock = parse_bool_value(key, value)?,
+                "tap-and-drag" | "tap_and_drag" => section.tap_and_drag = parse_bool_value(key, value)?,
+                "flip_x" => section.flip_x = parse_bool_value(key, value)?,
+                "flip_y" => section.flip_y = parse_bool_value(key, value)?,
+                _ => {
+                    debug!("Unknown touchpad setting: {}", key);
+                    section.extra.insert(key.to_string(), value.to_string());
+                }
             }
         }
     }
@@ -175,10 +194,13 @@ pub fn parse_touchdevice_section(section: &mut TouchdeviceSection, content: &str
             let value = value.trim();
             
             match key {
-                "transform" => section.transform = value.parse().unwrap_or(-1),
+                "transform" => section.transform = parse_value(key, value)?,
                 "output" => section.output = value.to_string(),
-                "enabled" => section.enabled = parse_bool(value),
-                _ => debug!("Unknown touchdevice setting: {}", key),
+                "enabled" => section.enabled = parse_bool_value(key, value)?,
+                _ => {
+                    debug!("Unknown touchdevice setting: {}", key);
+                    section.extra.insert(key.to_string(), value.to_string());
+                }
             }
         }
     }
@@ -198,11 +220,11 @@ pub fn parse_tablet_section(section: &mut TabletSection, content: &str) -> Resul
             let value = value.trim();
             
             match key {
-                "transform" => section.transform = value.parse().unwrap_or(-1),
+                "transform" => section.transform = parse_value(key, value)?,
                 "output" => section.output = value.to_string(),
-                "relative_input" => section.relative_input = parse_bool(value),
-                "left_handed" => section.left_handed = parse_bool(value),
-                "absolute_region_position" => section.absolute_region_position = parse_bool(value),
+                "relative_input" => section.relative_input = parse_bool_value(key, value)?,
+                "left_handed" => section.left_handed = parse_bool_value(key, value)?,
+                "absolute_region_position" => section.absolute_region_position = parse_bool_value(key, value)?,
                 "region_position" => {
                     let parts: Vec<&str> = value.split_whitespace().collect();
                     if parts.len() == 2 {
@@ -235,7 +257,10 @@ pub fn parse_tablet_section(section: &mut TabletSection, content: &str) -> Resul
                         section.active_area_position = (x, y);
                     }
                 },
-                _ => debug!("Unknown tablet setting: {}", key),
+                _ => {
+                    debug!("Unknown tablet setting: {}", key);
+                    section.extra.insert(key.to_string(), value.to_string());
+                }
             }
         }
     }
@@ -288,11 +313,12 @@ pub fn write_section<W: Write>(
     write_boolean_option(writer, "  middle_button_emulation", input.touchpad.middle_button_emulation, None, comment_style)?;
     write_option(writer, "  tap_button_map", &input.touchpad.tap_button_map, None, comment_style)?;
     write_boolean_option(writer, "  clickfinger_behavior", input.touchpad.clickfinger_behavior, None, comment_style)?;
-    write_boolean_option(writer, "  tap_to_click", input.touchpad.tap_to_click, None, comment_style)?;
+    write_boolean_option(writer, "  tap-to-click", input.touchpad.tap_to_click, None, comment_style)?;
     write_boolean_option(writer, "  drag_lock", input.touchpad.drag_lock, None, comment_style)?;
-    write_boolean_option(writer, "  tap_and_drag", input.touchpad.tap_and_drag, None, comment_style)?;
+    write_boolean_option(writer, "  tap-and-drag", input.touchpad.tap_and_drag, None, comment_style)?;
     write_boolean_option(writer, "  flip_x", input.touchpad.flip_x, None, comment_style)?;
     write_boolean_option(writer, "  flip_y", input.touchpad.flip_y, None, comment_style)?;
+    write_extra_options(writer, "  ", &input.touchpad.extra, comment_style)?;
     writeln!(writer, " }}")?;
     
     // Write touchdevice subsection
@@ -300,6 +326,7 @@ pub fn write_section<W: Write>(
     write_option(writer, "  transform", &input.touchdevice.transform.to_string(), None, comment_style)?;
     write_option(writer, "  output", &input.touchdevice.output, None, comment_style)?;
     write_boolean_option(writer, "  enabled", input.touchdevice.enabled, None, comment_style)?;
+    write_extra_options(writer, "  ", &input.touchdevice.extra, comment_style)?;
     writeln!(writer, " }}")?;
     
     // Write tablet subsection
@@ -313,8 +340,10 @@ pub fn write_section<W: Write>(
     write_boolean_option(writer, "  left_handed", input.tablet.left_handed, None, comment_style)?;
     write_option(writer, "  active_area_size", &format!("{} {}", input.tablet.active_area_size.0, input.tablet.active_area_size.1), None, comment_style)?;
     write_option(writer, "  active_area_position", &format!("{} {}", input.tablet.active_area_position.0, input.tablet.active_area_position.1), None, comment_style)?;
+    write_extra_options(writer, "  ", &input.tablet.extra, comment_style)?;
     writeln!(writer, " }}")?;
     
+    write_extra_options(writer, " ", &input.extra, comment_style)?;
     writeln!(writer, "}}")?;
     Ok(())
 }
\ No newline at end of file
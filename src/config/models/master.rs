@@ -1,9 +1,10 @@
 use std::io::{self, Write};
 use anyhow::Result;
+use indexmap::IndexMap;
 use log::debug;
 use serde::{Deserialize, Serialize};
-use crate::config::utils::parse_bool;
-use crate::config::utils::{CommentStyle, write_boolean_option, write_option, write_section_header};
+use crate::config::utils::{parse_bool_value, parse_value};
+use crate::config::utils::{CommentStyle, write_extra_options, write_boolean_option, write_option, write_section_header};
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct MasterSection {
@@ -20,6 +21,9 @@ pub struct MasterSection {
     pub smart_resizing: bool,
     pub drop_at_cursor: bool,
     pub always_keep_position: bool,
+    /// Options hyprconf has no model for, in file order
+    #[serde(default)]
+    pub extra: IndexMap<String, String>,
 }
 
 pub fn parse_master_section(section: &mut MasterSection, content: &str) -> Result<()> {
@@ -32,20 +36,23 @@ pub fn parse_master_section(section: &mut MasterSection, content: &str) -> Resul
             let key = key.trim();
             let value = value.trim();
             match key {
-                "allow_small_split" => section.allow_small_split = parse_bool(value),
-                "special_scale_factor" => section.special_scale_factor = value.parse().unwrap_or(0.8),
-                "mfact" => section.mfact = value.parse().unwrap_or(0.55),
+                "allow_small_split" => section.allow_small_split = parse_bool_value(key, value)?,
+                "special_scale_factor" => section.special_scale_factor = parse_value(key, value)?,
+                "mfact" => section.mfact = parse_value(key, value)?,
                 "new_status" => section.new_status = value.to_string(),
-                "new_on_top" => section.new_on_top = parse_bool(value),
+                "new_on_top" => section.new_on_top = parse_bool_value(key, value)?,
                 "new_on_active" => section.new_on_active = value.to_string(),
                 "orientation" => section.orientation = value.to_string(),
-                "inherit_fullscreen" => section.inherit_fullscreen = parse_bool(value),
-                "slave_count_for_center_master" => section.slave_count_for_center_master = value.parse().unwrap_or(2),
-                "center_master_slaves_on_right" => section.center_master_slaves_on_right = parse_bool(value),
-                "smart_resizing" => section.smart_resizing = parse_bool(value),
-                "drop_at_cursor" => section.drop_at_cursor = parse_bool(value),
-                "always_keep_position" => section.always_keep_position = parse_bool(value),
-                _ => debug!("Unknown master setting: {}", key),
+                "inherit_fullscreen" => section.inherit_fullscreen = parse_bool_value(key, value)?,
+                "slave_count_for_center_master" => section.slave_count_for_center_master = parse_value(key, value)?,
+                "center_master_slaves_on_right" => section.center_master_slaves_on_right = parse_bool_value(key, value)?,
+                "smart_resizing" => section.smart_resizing = parse_bool_value(key, value)?,
+                "drop_at_cursor" => section.drop_at_cursor = parse_bool_value(key, value)?,
+                "always_keep_position" => section.always_keep_position = parse_bool_value(key, value)?,
+                _ => {
+                    debug!("Unknown master setting: {}", key);
+                    section.extra.insert(key.to_string(), value.to_string());
+                }
             }
         }
     }
@@ -71,6 +78,7 @@ pub fn write_section<W: Write>(
     write_boolean_option(writer, " smart_resizing", master.smart_resizing, None, comment_style)?;
     write_boolean_option(writer, " drop_at_cursor", master.drop_at_cursor, None, comment_style)?;
     write_boolean_option(writer, " always_keep_position", master.always_keep_position, None, comment_style)?;
+    write_extra_options(writer, " ", &master.extra, comment_style)?;
     writeln!(writer, "}}")?;
     Ok(())
 }
@@ -1,16 +1,17 @@
 use std::io::{self, Write};
 use anyhow::Result;
+use indexmap::IndexMap;
 use log::debug;
 use serde::{Deserialize, Serialize};
-use crate::config::utils::parse_bool;
-use crate::config::utils::{CommentStyle, write_boolean_option, write_option, write_section_header};
+use crate::config::utils::{Color, parse_bool_value, parse_value};
+use crate::config::utils::{CommentStyle, write_extra_options, write_boolean_option, write_option, write_section_header};
 
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct MiscSection {
     pub disable_hyprland_logo: bool,
     pub disable_splash_rendering: bool,
-    pub col_splash: String,
+    pub col_splash: Color,
     pub font_family: String,
     pub splash_font_family: String,
     pub force_default_wallpaper: i32,
@@ -31,7 +32,7 @@ pub struct MiscSection {
     pub render_ahead_of_time: bool,
     pub render_ahead_safezone: i32,
     pub allow_session_lock_restore: bool,
-    pub background_color: String,
+    pub background_color: Color,
     pub close_special_on_empty: bool,
     pub new_window_takes_over_fullscreen: i32,
     pub exit_window_retains_fullscreen: bool,
@@ -43,6 +44,9 @@ pub struct MiscSection {
     pub lockdead_screen_delay: i32,
     pub enable_anr_dialog: bool,
     pub anr_missed_pings: i32,
+    /// Options hyprconf has no model for, in file order
+    #[serde(default)]
+    pub extra: IndexMap<String, String>,
 }
 
 pub fn parse_misc_section(section: &mut MiscSection, content: &str) -> Result<()> {
@@ -55,42 +59,45 @@ pub fn parse_misc_section(section: &mut MiscSection, content: &str) -> Result<()
             let key = key.trim();
             let value = value.trim();
             match key {
-                "disable_hyprland_logo" => section.disable_hyprland_logo = parse_bool(value),
-                "disable_splash_rendering" => section.disable_splash_rendering = parse_bool(value),
-                "col.splash" => section.col_splash = value.to_string(),
+                "disable_hyprland_logo" => section.disable_hyprland_logo = parse_bool_value(key, value)?,
+                "disable_splash_rendering" => section.disable_splash_rendering = parse_bool_value(key, value)?,
+                "col.splash" => section.col_splash = parse_value(key, value)?,
                 "font_family" => section.font_family = value.to_string(),
                 "splash_font_family" => section.splash_font_family = value.to_string(),
-                "force_default_wallpaper" => section.force_default_wallpaper = value.parse().unwrap_or(-1),
-                "vfr" => section.vfr = parse_bool(value),
-                "vrr" => section.vrr = value.parse().unwrap_or(0),
-                "mouse_move_enables_dpms" => section.mouse_move_enables_dpms = parse_bool(value),
-                "key_press_enables_dpms" => section.key_press_enables_dpms = parse_bool(value),
-                "always_follow_on_dnd" => section.always_follow_on_dnd = parse_bool(value),
-                "layers_hog_keyboard_focus" => section.layers_hog_keyboard_focus = parse_bool(value),
-                "animate_manual_resizes" => section.animate_manual_resizes = parse_bool(value),
-                "animate_mouse_windowdragging" => section.animate_mouse_windowdragging = parse_bool(value),
-                "disable_autoreload" => section.disable_autoreload = parse_bool(value),
-                "enable_swallow" => section.enable_swallow = parse_bool(value),
+                "force_default_wallpaper" => section.force_default_wallpaper = parse_value(key, value)?,
+                "vfr" => section.vfr = parse_bool_value(key, value)?,
+                "vrr" => section.vrr = parse_value(key, value)?,
+                "mouse_move_enables_dpms" => section.mouse_move_enables_dpms = parse_bool_value(key, value)?,
+                "key_press_enables_dpms" => section.key_press_enables_dpms = parse_bool_value(key, value)?,
+                "always_follow_on_dnd" => section.always_follow_on_dnd = parse_bool_value(key, value)?,
+                "layers_hog_keyboard_focus" => section.layers_hog_keyboard_focus = parse_bool_value(key, value)?,
+                "animate_manual_resizes" => section.animate_manual_resizes = parse_bool_value(key, value)?,
+                "animate_mouse_windowdragging" => section.animate_mouse_windowdragging = parse_bool_value(key, value)?,
+                "disable_autoreload" => section.disable_autoreload = parse_bool_value(key, value)?,
+                "enable_swallow" => section.enable_swallow = parse_bool_value(key, value)?,
                 "swallow_regex" => section.swallow_regex = value.to_string(),
                 "swallow_exception_regex" => section.swallow_exception_regex = value.to_string(),
-                "focus_on_activate" => section.focus_on_activate = parse_bool(value),
-                "mouse_move_focuses_monitor" => section.mouse_move_focuses_monitor = parse_bool(value),
-                "render_ahead_of_time" => section.render_ahead_of_time = parse_bool(value),
-                "render_ahead_safezone" => section.render_ahead_safezone = value.parse().unwrap_or(1),
-                "allow_session_lock_restore" => section.allow_session_lock_restore = parse_bool(value),
-                "background_color" => section.background_color = value.to_string(),
-                "close_special_on_empty" => section.close_special_on_empty = parse_bool(value),
-                "new_window_takes_over_fullscreen" => section.new_window_takes_over_fullscreen = value.parse().unwrap_or(0),
-                "exit_window_retains_fullscreen" => section.exit_window_retains_fullscreen = parse_bool(value),
-                "initial_workspace_tracking" => section.initial_workspace_tracking = value.parse().unwrap_or(1),
-                "middle_click_paste" => section.middle_click_paste = parse_bool(value),
-                "render_unfocused_fps" => section.render_unfocused_fps = value.parse().unwrap_or(15),
-                "disable_xdg_env_checks" => section.disable_xdg_env_checks = parse_bool(value),
-                "disable_hyprland_qtutils_check" => section.disable_hyprland_qtutils_check = parse_bool(value),
-                "lockdead_screen_delay" => section.lockdead_screen_delay = value.parse().unwrap_or(1000),
-                "enable_anr_dialog" => section.enable_anr_dialog = parse_bool(value),
-                "anr_missed_pings" => section.anr_missed_pings = value.parse().unwrap_or(1),
-                _ => debug!("Unknown misc setting: {}", key),
+                "focus_on_activate" => section.focus_on_activate = parse_bool_value(key, value)?,
+                "mouse_move_focuses_monitor" => section.mouse_move_focuses_monitor = parse_bool_value(key, value)?,
+                "render_ahead_of_time" => section.render_ahead_of_time = parse_bool_value(key, value)?,
+                "render_ahead_safezone" => section.render_ahead_safezone = parse_value(key, value)?,
+                "allow_session_lock_restore" => section.allow_session_lock_restore = parse_bool_value(key, value)?,
+                "background_color" => section.background_color = parse_value(key, value)?,
+                "close_special_on_empty" => section.close_special_on_empty = parse_bool_value(key, value)?,
+                "new_window_takes_over_fullscreen" => section.new_window_takes_over_fullscreen = parse_value(key, value)?,
+                "exit_window_retains_fullscreen" => section.exit_window_retains_fullscreen = parse_bool_value(key, value)?,
+                "initial_workspace_tracking" => section.initial_workspace_tracking = parse_value(key, value)?,
+                "middle_click_paste" => section.middle_click_paste = parse_bool_value(key, value)?,
+                "render_unfocused_fps" => section.render_unfocused_fps = parse_value(key, value)?,
+                "disable_xdg_env_checks" => section.disable_xdg_env_checks = parse_bool_value(key, value)?,
+                "disable_hyprland_qtutils_check" => section.disable_hyprland_qtutils_check = parse_bool_value(key, value)?,
+                "lockdead_screen_delay" => section.lockdead_screen_delay = parse_value(key, value)?,
+                "enable_anr_dialog" => section.enable_anr_dialog = parse_bool_value(key, value)?,
+                "anr_missed_pings" => section.anr_missed_pings = parse_value(key, value)?,
+                _ => {
+                    debug!("Unknown misc setting: {}", key);
+                    section.extra.insert(key.to_string(), value.to_string());
+                }
             }
         }
     }
@@ -138,6 +145,7 @@ pub fn write_section<W: Write>(
     write_option(writer, " lockdead_screen_delay", &misc.lockdead_screen_delay, None, comment_style)?;
     write_boolean_option(writer, " enable_anr_dialog", misc.enable_anr_dialog, None, comment_style)?;
     write_option(writer, " anr_missed_pings", &misc.anr_missed_pings, None, comment_style)?;
+    write_extra_options(writer, " ", &misc.extra, comment_style)?;
     writeln!(writer, "}}")?;
     Ok(())
 }
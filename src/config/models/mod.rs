@@ -7,6 +7,7 @@ pub mod decoration;
 pub mod devices;
 pub mod dwindle;
 pub mod ecosystem;
+pub mod exec;
 pub mod experimental;
 pub mod general;
 pub mod gestures;
@@ -17,6 +18,7 @@ pub mod misc;
 pub mod monitors;
 pub mod opengl;
 pub mod permissions;
+pub mod plugins;
 pub mod render;
 pub mod rules;
 pub mod xwayland;
@@ -1,7 +1,7 @@
 use std::io::{self, Write};
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
-use crate::config::utils::CommentStyle;
+use crate::config::utils::{CommentStyle, parse_value};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MonitorConfig {
@@ -53,30 +53,30 @@ pub fn parse_monitor_config(content: &str) -> Result<MonitorConfig> {
     for part in &parts[3..] {
         let part = part.trim();
         if let Some(value) = part.strip_prefix("transform:") {
-            monitor.transform = Some(value.trim().parse().unwrap_or(0));
+            monitor.transform = Some(parse_value("transform", value)?);
         } else if let Some(value) = part.strip_prefix("scale:") {
-            monitor.scale = value.trim().parse().unwrap_or(1.0);
+            monitor.scale = parse_value("scale", value)?;
         } else if let Some(value) = part.strip_prefix("mirror:") {
             monitor.mirror = Some(value.trim().to_string());
         } else if let Some(value) = part.strip_prefix("bitdepth:") {
-            monitor.bitdepth = Some(value.trim().parse().unwrap_or(8));
+            monitor.bitdepth = Some(parse_value("bitdepth", value)?);
         } else if let Some(value) = part.strip_prefix("color_management:") {
             monitor.color_management = Some(value.trim().to_string());
         } else if let Some(value) = part.strip_prefix("sdr_brightness:") {
-            monitor.sdr_brightness = Some(value.trim().parse().unwrap_or(1.0));
+            monitor.sdr_brightness = Some(parse_value("sdr_brightness", value)?);
         } else if let Some(value) = part.strip_prefix("sdr_saturation:") {
-            monitor.sdr_saturation = Some(value.trim().parse().unwrap_or(1.0));
+            monitor.sdr_saturation = Some(parse_value("sdr_saturation", value)?);
         } else if let Some(value) = part.strip_prefix("vrr:") {
-            monitor.vrr = Some(value.trim().parse().unwrap_or(0));
+            monitor.vrr = Some(parse_value("vrr", value)?);
         } else if part == "disable" {
             monitor.disable = true;
         } else if let Some(value) = part.strip_prefix("reserved_area:") {
             let area_parts: Vec<&str> = value.trim().split_whitespace().collect();
             if area_parts.len() == 4 {
-                let top = area_parts[0].parse().unwrap_or(0);
-                let bottom = area_parts[1].parse().unwrap_or(0);
-                let left = area_parts[2].parse().unwrap_or(0);
-                let right = area_parts[3].parse().unwrap_or(0);
+                let top = parse_value("reserved_area", area_parts[0])?;
+                let bottom = parse_value("reserved_area", area_parts[1])?;
+                let left = parse_value("reserved_area", area_parts[2])?;
+                let right = parse_value("reserved_area", area_parts[3])?;
                 monitor.reserved_area = Some((top, bottom, left, right));
             }
         }
@@ -1,13 +1,17 @@
 use std::io::{self, Write};
 use anyhow::Result;
+use indexmap::IndexMap;
 use log::debug;
 use serde::{Deserialize, Serialize};
-use crate::config::utils::parse_bool;
-use crate::config::utils::{CommentStyle, write_boolean_option, write_section_header};
+use crate::config::utils::{parse_bool_value};
+use crate::config::utils::{CommentStyle, write_extra_options, write_boolean_option, write_section_header};
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct OpenGLSection {
     pub nvidia_anti_flicker: bool,
+    /// Options hyprconf has no model for, in file order
+    #[serde(default)]
+    pub extra: IndexMap<String, String>,
 }
 
 pub fn parse_opengl_section(section: &mut OpenGLSection, content: &str) -> Result<()> {
@@ -22,8 +26,11 @@ pub fn parse_opengl_section(section: &mut OpenGLSection, content: &str) -> Resul
             let value = value.trim();
             
             match key {
-                "nvidia_anti_flicker" => section.nvidia_anti_flicker = parse_bool(value),
-                _ => debug!("Unknown opengl setting: {}", key),
+                "nvidia_anti_flicker" => section.nvidia_anti_flicker = parse_bool_value(key, value)?,
+                _ => {
+                    debug!("Unknown opengl setting: {}", key);
+                    section.extra.insert(key.to_string(), value.to_string());
+                }
             }
         }
     }
@@ -38,6 +45,7 @@ pub fn write_section<W: Write>(
 ) -> io::Result<()> {
     write_section_header(writer, "opengl {", comment_style)?;
     write_boolean_option(writer, " nvidia_anti_flicker", opengl.nvidia_anti_flicker, None, comment_style)?;
+    write_extra_options(writer, " ", &opengl.extra, comment_style)?;
     writeln!(writer, "}}")?;
     Ok(())
 }
\ No newline at end of file
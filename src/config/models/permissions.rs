@@ -1,19 +1,56 @@
 use std::fmt::{self, Display, Formatter};
 use std::io::{self, Write};
 use std::str::FromStr;
-use anyhow::{Context, Result};
+use anyhow::{Result, anyhow};
 use log::debug;
+use regex::Regex;
 use serde::{Deserialize, Serialize};
 use crate::config::utils::CommentStyle;
 use crate::config::models::core::ConfigSection;
 
+/// A `permission = regex, type, mode` rule
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Permission {
+    /// Regex matched against the binary path, or the device name for `keyboard`
     pub path_regex: String,
-    pub permission_type: String,
+    pub permission_type: PermissionType,
     pub mode: PermissionMode,
 }
 
+/// What a permission rule grants or denies
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum PermissionType {
+    /// Capturing the screen
+    ScreenCopy,
+    /// Loading plugins
+    Plugin,
+    /// Using a keyboard device
+    Keyboard,
+}
+
+impl Display for PermissionType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            PermissionType::ScreenCopy => write!(f, "screencopy"),
+            PermissionType::Plugin => write!(f, "plugin"),
+            PermissionType::Keyboard => write!(f, "keyboard"),
+        }
+    }
+}
+
+impl FromStr for PermissionType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "screencopy" => Ok(PermissionType::ScreenCopy),
+            "plugin" => Ok(PermissionType::Plugin),
+            "keyboard" | "keeb" => Ok(PermissionType::Keyboard),
+            _ => Err(format!("invalid permission type `{}`", s.trim())),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum PermissionMode {
     Allow,
@@ -39,72 +76,97 @@ impl FromStr for PermissionMode {
             "allow" => Ok(PermissionMode::Allow),
             "ask" => Ok(PermissionMode::Ask),
             "deny" => Ok(PermissionMode::Deny),
-            _ => Err(format!("Invalid permission mode: {}", s)),
+            _ => Err(format!("invalid permission mode `{}`", s)),
         }
     }
 }
 
-/// Parse a permission block (TOML-style) into a Permission struct
-pub fn parse_permission_section(content: &str) -> Result<Permission> {
-    let mut path_regex = None;
-    let mut permission_type = None;
-    let mut mode = None;
-    
-    for line in content.lines() {
-        let line = line.trim();
-        if line.is_empty() || line.starts_with('#') {
-            continue;
-        }
-        
-        if let Some((key, val)) = line.split_once('=') {
-            let key = key.trim();
-            let val = val.trim().trim_matches('"');
-            
-            match key {
-                "path_regex" => path_regex = Some(val.to_string()),
-                "permission_type" => permission_type = Some(val.to_string()),
-                "mode" => {
-                    let parsed = PermissionMode::from_str(val)
-                        .map_err(|e| anyhow::anyhow!("Invalid permission mode '{}': {}", val, e))?;
-                    mode = Some(parsed);
-                }
-                other => debug!("Unknown permission key: {}", other),
-            }
-        }
+/// Parse the value of a `permission = ` line, e.g. `/usr/bin/grim, screencopy, allow`
+pub fn parse_permission(value: &str) -> Result<Permission> {
+    // The regex comes first and may itself contain commas
+    let mut fields = value.rsplitn(3, ',').map(str::trim);
+    let (Some(mode), Some(permission_type), Some(path_regex)) = (fields.next(), fields.next(), fields.next()) else {
+        return Err(anyhow!("permission needs a regex, a type and a mode"));
+    };
+
+    if path_regex.is_empty() {
+        return Err(anyhow!("permission has an empty regex"));
     }
-    
-    let path_regex = path_regex.context("Missing 'path_regex' in permission block")?;
-    let permission_type = permission_type.context("Missing 'permission_type' in permission block")?;
-    let mode = mode.context("Missing or invalid 'mode' in permission block")?;
-    
+    Regex::new(path_regex).map_err(|_| anyhow!("invalid permission regex `{}`", path_regex))?;
+    let permission_type = PermissionType::from_str(permission_type)
+        .map_err(|_| anyhow!("invalid permission type `{}`: expected screencopy, plugin or keyboard", permission_type))?;
+    let mode = PermissionMode::from_str(mode)
+        .map_err(|_| anyhow!("invalid permission mode `{}`: expected allow, ask or deny", mode))?;
+
+    debug!("Permission: {} {} {}", path_regex, permission_type, mode);
     Ok(Permission {
-        path_regex,
+        path_regex: path_regex.to_string(),
         permission_type,
         mode,
     })
 }
 
 /// Write a permission to the provided writer in Hyprland's config format
-pub fn write_permission<W: Write>(
-    writer: &mut W,
-    permission: &Permission,
-    comment_style: &CommentStyle,
-) -> io::Result<()> {
-    writeln!(writer, "permission {{")?;
-    writeln!(writer, "    path_regex = \"{}\"", permission.path_regex)?;
-    writeln!(writer, "    permission_type = \"{}\"", permission.permission_type)?;
-    writeln!(writer, "    mode = {}", permission.mode)?;
-    writeln!(writer, "}}")?;
-    
-    Ok(())
+pub fn write_permission<W: Write>(writer: &mut W, permission: &Permission) -> io::Result<()> {
+    writeln!(
+        writer,
+        "permission = {}, {}, {}",
+        permission.path_regex, permission.permission_type, permission.mode
+    )
 }
 
 /// Implement ConfigSection for Vec<Permission>
 impl ConfigSection for Vec<Permission> {
-    fn write_section<W: Write>(&self, writer: &mut W, comment_style: &CommentStyle) -> io::Result<()> {
+    fn write_section<W: Write>(&self, writer: &mut W, _comment_style: &CommentStyle) -> io::Result<()> {
         for permission in self {
-            write_permission(writer, permission, comment_style)?;
+            write_permission(writer, permission)?;
         }
         Ok(())
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::parser::ConfigParser;
+    use crate::config::parser::source::SourceTree;
+
+    #[test]
+    fn permissions_parse_and_write_back() {
+        let permission = parse_permission("/usr/bin/(grim|slurp), screencopy, allow").unwrap();
+        assert_eq!(permission.path_regex, "/usr/bin/(grim|slurp)");
+        assert_eq!(permission.permission_type, PermissionType::ScreenCopy);
+
+        let mut buffer = Vec::new();
+        write_permission(&mut buffer, &permission).unwrap();
+        assert_eq!(String::from_utf8(buffer).unwrap(), "permission = /usr/bin/(grim|slurp), screencopy, allow\n");
+    }
+
+    #[test]
+    fn regex_may_contain_commas() {
+        let permission = parse_permission("^(a{1,3})$, keeb, deny").unwrap();
+        assert_eq!(permission.path_regex, "^(a{1,3})$");
+        assert_eq!(permission.permission_type, PermissionType::Keyboard);
+        assert_eq!(permission.mode, PermissionMode::Deny);
+    }
+
+    #[test]
+    fn permissions_without_enforcement_are_reported() {
+        let warned = |content: &str| ConfigParser::validate(&SourceTree::from_string(content))
+            .iter()
+            .any(|d| d.message.contains("enforce_permissions"));
+        assert!(warned("permission = /usr/bin/grim, screencopy, allow\n"));
+        assert!(!warned("ecosystem {\n    enforce_permissions = true\n}\npermission = /usr/bin/grim, screencopy, allow\n"));
+    }
+
+    #[test]
+    fn invalid_permissions_are_rejected() {
+        for value in ["/usr/bin/grim, screencopy", ", plugin, allow", "(, plugin, allow", "/bin/x, camera, allow", "/bin/x, plugin, maybe"] {
+            assert!(parse_permission(value).is_err(), "{}", value);
+        }
+        assert_eq!(
+            parse_permission("/bin/x, camera, allow").unwrap_err().to_string(),
+            "invalid permission type `camera`: expected screencopy, plugin or keyboard"
+        );
+    }
+}
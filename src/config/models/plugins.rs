@@ -0,0 +1,136 @@
+use std::io::{self, Write};
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::utils::{CommentStyle, write_option, write_section_header};
+use crate::config::models::core::ConfigSection;
+
+/// A block hyprconf has no typed model for, such as `plugin { hyprexpo { ... } }`,
+/// kept as a generic tree so it can be written back unchanged
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+pub struct PluginSection {
+    pub name: String,
+    /// Options and nested blocks in file order
+    pub entries: Vec<PluginEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum PluginEntry {
+    Option { key: String, value: String },
+    Section(PluginSection),
+}
+
+impl PluginSection {
+    pub fn new(name: &str) -> Self {
+        Self { name: name.to_string(), entries: Vec::new() }
+    }
+
+    /// Set an option given its path below this block, creating blocks as needed
+    pub fn set(&mut self, path: &str, value: &str) {
+        let Some((name, rest)) = path.split_once(':') else {
+            let existing = self.entries.iter_mut().rev().find_map(|entry| match entry {
+                PluginEntry::Option { key, value } if key == path => Some(value),
+                _ => None,
+            });
+            match existing {
+                Some(existing) => *existing = value.to_string(),
+                None => self.entries.push(PluginEntry::Option { key: path.to_string(), value: value.to_string() }),
+            }
+            return;
+        };
+
+        let position = self.entries.iter().rposition(|entry| {
+            matches!(entry, PluginEntry::Section(section) if section.name == name)
+        });
+        let index = match position {
+            Some(index) => index,
+            None => {
+                self.entries.push(PluginEntry::Section(PluginSection::new(name)));
+                self.entries.len() - 1
+            }
+        };
+        if let PluginEntry::Section(section) = &mut self.entries[index] {
+            section.set(rest, value);
+        }
+    }
+}
+
+/// Write a plugin or unknown block, with everything nested in it
+pub fn write_plugin_section<W: Write>(
+    writer: &mut W,
+    section: &PluginSection,
+    comment_style: &CommentStyle,
+) -> io::Result<()> {
+    write_section_header(writer, &format!("{} {{", section.name), comment_style)?;
+    write_entries(writer, &section.entries, 1, comment_style)?;
+    writeln!(writer, "}}")?;
+    Ok(())
+}
+
+fn write_entries<W: Write>(
+    writer: &mut W,
+    entries: &[PluginEntry],
+    depth: usize,
+    comment_style: &CommentStyle,
+) -> io::Result<()> {
+    let indent = " ".repeat(depth);
+    for entry in entries {
+        match entry {
+            PluginEntry::Option { key, value } => {
+                write_option(writer, &format!("{}{}", indent, key), value, None, comment_style)?;
+            }
+            PluginEntry::Section(section) => {
+                writeln!(writer, "{}{} {{", indent, section.name)?;
+                write_entries(writer, &section.entries, depth + 1, comment_style)?;
+                writeln!(writer, "{}}}", indent)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Implement ConfigSection for Vec<PluginSection>
+impl ConfigSection for Vec<PluginSection> {
+    fn write_section<W: Write>(&self, writer: &mut W, comment_style: &CommentStyle) -> io::Result<()> {
+        for section in self {
+            write_plugin_section(writer, section, comment_style)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hyprbars() -> PluginSection {
+        let mut plugin = PluginSection::new("plugin");
+        plugin.set("hyprbars:bar_height", "20");
+        plugin.set("hyprbars:buttons:size", "10");
+        plugin.set("hyprexpo:columns", "3");
+        plugin
+    }
+
+    #[test]
+    fn set_replaces_an_existing_option() {
+        let mut plugin = hyprbars();
+        plugin.set("hyprbars:buttons:size", "12");
+
+        let mut expected = PluginSection::new("plugin");
+        expected.set("hyprbars:bar_height", "20");
+        expected.set("hyprbars:buttons:size", "12");
+        expected.set("hyprexpo:columns", "3");
+        assert_eq!(plugin, expected);
+        assert_eq!(plugin.entries.len(), 2);
+    }
+
+    #[test]
+    fn nested_blocks_are_written_in_order() {
+        let mut buffer = Vec::new();
+        write_plugin_section(&mut buffer, &hyprbars(), &CommentStyle::Hash).unwrap();
+        assert_eq!(
+            String::from_utf8(buffer).unwrap(),
+            "\nplugin {\n hyprbars {\n  bar_height = 20\n  buttons {\n   size = 10\n  }\n }\n hyprexpo {\n  columns = 3\n }\n}\n"
+        );
+    }
+}
@@ -1,9 +1,10 @@
 use std::io::{self, Write};
 use anyhow::Result;
+use indexmap::IndexMap;
 use log::debug;
 use serde::{Deserialize, Serialize};
-use crate::config::utils::parse_bool;
-use crate::config::utils::{CommentStyle, write_boolean_option, write_option, write_section_header};
+use crate::config::utils::{parse_bool_value, parse_value};
+use crate::config::utils::{CommentStyle, write_extra_options, write_boolean_option, write_option, write_section_header};
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct RenderSection {
@@ -15,6 +16,9 @@ pub struct RenderSection {
     pub ctm_animation: i32,
     pub cm_fs_passthrough: i32,
     pub cm_enabled: bool,
+    /// Options hyprconf has no model for, in file order
+    #[serde(default)]
+    pub extra: IndexMap<String, String>,
 }
 
 pub fn parse_render_section(section: &mut RenderSection, content: &str) -> Result<()> {
@@ -29,15 +33,18 @@ pub fn parse_render_section(section: &mut RenderSection, content: &str) -> Resul
             let value = value.trim();
             
             match key {
-                "explicit_sync" => section.explicit_sync = value.parse().unwrap_or(2),
-                "explicit_sync_kms" => section.explicit_sync_kms = value.parse().unwrap_or(2),
-                "direct_scanout" => section.direct_scanout = value.parse().unwrap_or(0),
-                "expand_undersized_textures" => section.expand_undersized_textures = parse_bool(value),
-                "xp_mode" => section.xp_mode = parse_bool(value),
-                "ctm_animation" => section.ctm_animation = value.parse().unwrap_or(2),
-                "cm_fs_passthrough" => section.cm_fs_passthrough = value.parse().unwrap_or(2),
-                "cm_enabled" => section.cm_enabled = parse_bool(value),
-                _ => debug!("Unknown render setting: {}", key),
+                "explicit_sync" => section.explicit_sync = parse_value(key, value)?,
+                "explicit_sync_kms" => section.explicit_sync_kms = parse_value(key, value)?,
+                "direct_scanout" => section.direct_scanout = parse_value(key, value)?,
+                "expand_undersized_textures" => section.expand_undersized_textures = parse_bool_value(key, value)?,
+                "xp_mode" => section.xp_mode = parse_bool_value(key, value)?,
+                "ctm_animation" => section.ctm_animation = parse_value(key, value)?,
+                "cm_fs_passthrough" => section.cm_fs_passthrough = parse_value(key, value)?,
+                "cm_enabled" => section.cm_enabled = parse_bool_value(key, value)?,
+                _ => {
+                    debug!("Unknown render setting: {}", key);
+                    section.extra.insert(key.to_string(), value.to_string());
+                }
             }
         }
     }
@@ -61,6 +68,7 @@ pub fn write_section<W: Write>(
     write_option(writer, " cm_fs_passthrough", &render.cm_fs_passthrough.to_string(), None, comment_style)?;
     write_boolean_option(writer, " cm_enabled", render.cm_enabled, None, comment_style)?;
     
+    write_extra_options(writer, " ", &render.extra, comment_style)?;
     writeln!(writer, "}}")?;
     Ok(())
 }
\ No newline at end of file
@@ -1,56 +1,494 @@
-use std::collections::HashMap;
 use anyhow::Result;
+use indexmap::IndexMap;
+use log::debug;
+use regex::Regex;
 use serde::{Deserialize, Serialize};
+use std::fmt;
 use std::io::{self, Write};
-use crate::config::utils::{CommentStyle};
+use crate::config::utils::{parse_bool, write_option, write_section_header, CommentStyle};
+
+/// Which of Hyprland's window rule syntaxes a rule was written in
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub enum WindowRuleSyntax {
+    /// `windowrule = effect, matchers...`
+    #[default]
+    V1,
+    /// `windowrulev2 = effect, matchers...`
+    V2,
+    /// `windowrule { name = ...; match:class = ...; effect = ... }`
+    Block,
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct WindowRule {
-    pub rule: String,
-    pub value: String,
-    pub parameters: Vec<String>,
+    pub syntax: WindowRuleSyntax,
+    /// Name of a block-style rule
+    pub name: Option<String>,
+    pub effects: Vec<WindowRuleEffect>,
+    pub matchers: Vec<WindowRuleMatcher>,
+}
+
+/// Something a window rule does to the windows it matches
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum WindowRuleEffect {
+    Float,
+    Tile,
+    Fullscreen,
+    Maximize,
+    Pin,
+    Center,
+    Pseudo,
+    NoInitialFocus,
+    NoFocus,
+    StayFocused,
+    NoBlur,
+    NoBorder,
+    NoShadow,
+    NoDim,
+    NoAnim,
+    DimAround,
+    KeepAspectRatio,
+    Immediate,
+    Size(String),
+    Move(String),
+    MinSize(String),
+    MaxSize(String),
+    Opacity(String),
+    Workspace(String),
+    Monitor(String),
+    IdleInhibit(String),
+    Animation(String),
+    BorderColor(String),
+    BorderSize(String),
+    Rounding(String),
+    SuppressEvent(String),
+    Tag(String),
+    Group(String),
+    /// Any other effect, kept as written
+    Other { name: String, args: String },
+}
+
+/// Effects that take no arguments
+const WINDOW_RULE_FLAGS: &[(&str, WindowRuleEffect)] = &[
+    ("float", WindowRuleEffect::Float),
+    ("tile", WindowRuleEffect::Tile),
+    ("fullscreen", WindowRuleEffect::Fullscreen),
+    ("maximize", WindowRuleEffect::Maximize),
+    ("pin", WindowRuleEffect::Pin),
+    ("center", WindowRuleEffect::Center),
+    ("pseudo", WindowRuleEffect::Pseudo),
+    ("noinitialfocus", WindowRuleEffect::NoInitialFocus),
+    ("nofocus", WindowRuleEffect::NoFocus),
+    ("stayfocused", WindowRuleEffect::StayFocused),
+    ("noblur", WindowRuleEffect::NoBlur),
+    ("noborder", WindowRuleEffect::NoBorder),
+    ("noshadow", WindowRuleEffect::NoShadow),
+    ("nodim", WindowRuleEffect::NoDim),
+    ("noanim", WindowRuleEffect::NoAnim),
+    ("dimaround", WindowRuleEffect::DimAround),
+    ("keepaspectratio", WindowRuleEffect::KeepAspectRatio),
+    ("immediate", WindowRuleEffect::Immediate),
+];
+
+impl WindowRuleEffect {
+    /// Build an effect from its name and the arguments following it
+    pub fn from_parts(name: &str, args: &str) -> Self {
+        let args = args.trim();
+        if args.is_empty() {
+            if let Some((_, flag)) = WINDOW_RULE_FLAGS.iter().find(|(n, _)| *n == name) {
+                return flag.clone();
+            }
+        }
+
+        let value = args.to_string();
+        match name {
+            "size" => Self::Size(value),
+            "move" => Self::Move(value),
+            "minsize" => Self::MinSize(value),
+            "maxsize" => Self::MaxSize(value),
+            "opacity" => Self::Opacity(value),
+            "workspace" => Self::Workspace(value),
+            "monitor" => Self::Monitor(value),
+            "idleinhibit" => Self::IdleInhibit(value),
+            "animation" => Self::Animation(value),
+            "bordercolor" => Self::BorderColor(value),
+            "bordersize" => Self::BorderSize(value),
+            "rounding" => Self::Rounding(value),
+            "suppressevent" => Self::SuppressEvent(value),
+            "tag" => Self::Tag(value),
+            "group" => Self::Group(value),
+            _ => Self::Other { name: name.to_string(), args: value },
+        }
+    }
+
+    /// Parse an effect as written in a rule line, e.g. `size 800 600`
+    pub fn parse(content: &str) -> Self {
+        let content = content.trim();
+        let (name, args) = content.split_once(char::is_whitespace).unwrap_or((content, ""));
+        Self::from_parts(name, args)
+    }
+
+    /// Name of the effect, e.g. `float` or `opacity`
+    pub fn name(&self) -> &str {
+        if let Some((name, _)) = WINDOW_RULE_FLAGS.iter().find(|(_, flag)| flag == self) {
+            return name;
+        }
+        match self {
+            Self::Size(_) => "size",
+            Self::Move(_) => "move",
+            Self::MinSize(_) => "minsize",
+            Self::MaxSize(_) => "maxsize",
+            Self::Opacity(_) => "opacity",
+            Self::Workspace(_) => "workspace",
+            Self::Monitor(_) => "monitor",
+            Self::IdleInhibit(_) => "idleinhibit",
+            Self::Animation(_) => "animation",
+            Self::BorderColor(_) => "bordercolor",
+            Self::BorderSize(_) => "bordersize",
+            Self::Rounding(_) => "rounding",
+            Self::SuppressEvent(_) => "suppressevent",
+            Self::Tag(_) => "tag",
+            Self::Group(_) => "group",
+            Self::Other { name, .. } => name,
+            _ => "",
+        }
+    }
+
+    /// Arguments of the effect, `None` for effects that take none
+    pub fn args(&self) -> Option<&str> {
+        match self {
+            Self::Size(v) | Self::Move(v) | Self::MinSize(v) | Self::MaxSize(v)
+            | Self::Opacity(v) | Self::Workspace(v) | Self::Monitor(v) | Self::IdleInhibit(v)
+            | Self::Animation(v) | Self::BorderColor(v) | Self::BorderSize(v) | Self::Rounding(v)
+            | Self::SuppressEvent(v) | Self::Tag(v) | Self::Group(v) => Some(v),
+            Self::Other { args, .. } if !args.is_empty() => Some(args),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for WindowRuleEffect {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.args() {
+            Some(args) => write!(f, "{} {}", self.name(), args),
+            None => write!(f, "{}", self.name()),
+        }
+    }
+}
+
+/// A condition a window has to meet for a rule to apply
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum WindowRuleMatcher {
+    Class(String),
+    Title(String),
+    InitialClass(String),
+    InitialTitle(String),
+    Tag(String),
+    Xwayland(bool),
+    Floating(bool),
+    Fullscreen(bool),
+    Pinned(bool),
+    Focus(bool),
+    Group(bool),
+    FullscreenState(String),
+    Workspace(String),
+    OnWorkspace(String),
+    Content(String),
+    XdgTag(String),
+    /// Bare regex of a legacy `windowrule = RULE, WINDOW` line, matched against the class
+    Window(String),
+    /// Any other matcher, kept as written
+    Other { key: String, value: String },
+}
+
+impl WindowRuleMatcher {
+    /// Build a matcher from its key, in either the line (`initialClass`) or
+    /// block (`initial_class`) spelling, returning `None` for unknown keys
+    pub fn from_parts(key: &str, value: &str) -> Option<Self> {
+        let value = value.trim();
+        let text = value.to_string();
+        let flag = parse_bool(value);
+        let matcher = match key.trim().to_lowercase().replace('_', "").as_str() {
+            "class" => Self::Class(text),
+            "title" => Self::Title(text),
+            "initialclass" => Self::InitialClass(text),
+            "initialtitle" => Self::InitialTitle(text),
+            "tag" => Self::Tag(text),
+            "xwayland" => Self::Xwayland(flag),
+            "floating" | "float" => Self::Floating(flag),
+            "fullscreen" => Self::Fullscreen(flag),
+            "pinned" | "pin" => Self::Pinned(flag),
+            "focus" => Self::Focus(flag),
+            "group" => Self::Group(flag),
+            "fullscreenstate" => Self::FullscreenState(text),
+            "workspace" => Self::Workspace(text),
+            "onworkspace" => Self::OnWorkspace(text),
+            "content" => Self::Content(text),
+            "xdgtag" => Self::XdgTag(text),
+            _ => return None,
+        };
+        Some(matcher)
+    }
+
+    /// Key of the matcher in the given syntax, without the `match:` prefix of blocks
+    pub fn key(&self, syntax: WindowRuleSyntax) -> &str {
+        let block = syntax == WindowRuleSyntax::Block;
+        match self {
+            Self::Class(_) | Self::Window(_) => "class",
+            Self::Title(_) => "title",
+            Self::InitialClass(_) if block => "initial_class",
+            Self::InitialClass(_) => "initialClass",
+            Self::InitialTitle(_) if block => "initial_title",
+            Self::InitialTitle(_) => "initialTitle",
+            Self::Tag(_) => "tag",
+            Self::Xwayland(_) => "xwayland",
+            Self::Floating(_) if block => "float",
+            Self::Floating(_) => "floating",
+            Self::Fullscreen(_) => "fullscreen",
+            Self::Pinned(_) if block => "pin",
+            Self::Pinned(_) => "pinned",
+            Self::Focus(_) => "focus",
+            Self::Group(_) => "group",
+            Self::FullscreenState(_) if block => "fullscreen_state",
+            Self::FullscreenState(_) => "fullscreenstate",
+            Self::Workspace(_) => "workspace",
+            Self::OnWorkspace(_) if block => "on_workspace",
+            Self::OnWorkspace(_) => "onworkspace",
+            Self::Content(_) => "content",
+            Self::XdgTag(_) if block => "xdg_tag",
+            Self::XdgTag(_) => "xdgTag",
+            Self::Other { key, .. } => key,
+        }
+    }
+
+    /// Value of the matcher in the given syntax
+    pub fn value(&self, syntax: WindowRuleSyntax) -> String {
+        let block = syntax == WindowRuleSyntax::Block;
+        match self {
+            Self::Xwayland(b) | Self::Floating(b) | Self::Fullscreen(b)
+            | Self::Pinned(b) | Self::Focus(b) | Self::Group(b) => match (block, b) {
+                (true, true) => "true".to_string(),
+                (true, false) => "false".to_string(),
+                (false, true) => "1".to_string(),
+                (false, false) => "0".to_string(),
+            },
+            Self::Class(v) | Self::Title(v) | Self::InitialClass(v) | Self::InitialTitle(v)
+            | Self::Tag(v) | Self::FullscreenState(v) | Self::Workspace(v) | Self::OnWorkspace(v)
+            | Self::Content(v) | Self::XdgTag(v) | Self::Window(v) => v.clone(),
+            Self::Other { value, .. } => value.clone(),
+        }
+    }
+
+    /// The matcher as written in a `windowrule`/`windowrulev2` line
+    fn to_field(&self, syntax: WindowRuleSyntax) -> String {
+        match self {
+            Self::Window(regex) => regex.clone(),
+            _ => format!("{}:{}", self.key(syntax), self.value(syntax)),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct WorkspaceRule {
     pub workspace: String,
-    pub rules: HashMap<String, String>,
+    pub rules: IndexMap<String, String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LayerRule {
-    pub rule: String,
-    pub target: String,
-    pub value: Option<String>,
+    pub effect: LayerRuleEffect,
+    /// Regex matched against the layer namespace, or `address:0x...`
+    pub namespace: String,
 }
 
-pub fn parse_layer_rule(content: &str) -> Result<LayerRule> {
-    let parts: Vec<&str> = content.split(',').collect();
-    let mut rule = LayerRule::default();
-    if parts.len() >= 2 {
-        rule.rule = parts[0].trim().to_string();
-        rule.target = parts[1].trim().to_string();
-        if parts.len() > 2 {
-            rule.value = Some(parts[2].trim().to_string());
+impl LayerRule {
+    /// Namespace regex compiled, `None` if the rule targets an address instead
+    pub fn namespace_regex(&self) -> Option<Result<Regex, regex::Error>> {
+        if self.namespace.starts_with("address:") {
+            return None;
+        }
+        Some(Regex::new(&self.namespace))
+    }
+}
+
+/// Something a layer rule does to the layer surfaces it matches
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum LayerRuleEffect {
+    Unset,
+    NoAnim,
+    Blur,
+    BlurPopups,
+    IgnoreZero,
+    DimAround,
+    NoScreenShare,
+    /// Ignore pixels at or below this alpha when blurring
+    IgnoreAlpha(f32),
+    XRay(bool),
+    /// Animation style, e.g. `slide top` or `popin 80%`
+    Animation(String),
+    Order(i32),
+    /// Render above the lock screen, optionally taking input there
+    AboveLock { interactable: bool },
+    /// Any other effect, kept as written
+    Other { name: String, args: String },
+}
+
+impl LayerRuleEffect {
+    /// Parse an effect as written in a rule line, e.g. `ignorealpha 0.3`
+    pub fn parse(content: &str) -> Self {
+        let content = content.trim();
+        let (name, args) = content.split_once(char::is_whitespace).unwrap_or((content, ""));
+        let args = args.trim();
+
+        let effect = match (name, args) {
+            ("unset", "") => Some(Self::Unset),
+            ("noanim", "") => Some(Self::NoAnim),
+            ("blur", "") => Some(Self::Blur),
+            ("blurpopups", "") => Some(Self::BlurPopups),
+            ("ignorezero", "") => Some(Self::IgnoreZero),
+            ("dimaround", "") => Some(Self::DimAround),
+            ("noscreenshare", "") => Some(Self::NoScreenShare),
+            ("ignorealpha", a) => a.parse().ok().map(Self::IgnoreAlpha),
+            ("xray", a) if !a.is_empty() => Some(Self::XRay(parse_bool(a))),
+            ("animation", a) if !a.is_empty() => Some(Self::Animation(a.to_string())),
+            ("order", a) => a.parse().ok().map(Self::Order),
+            ("abovelock", "") => Some(Self::AboveLock { interactable: false }),
+            ("abovelock", a) => Some(Self::AboveLock { interactable: parse_bool(a) }),
+            _ => None,
+        };
+        effect.unwrap_or_else(|| Self::Other { name: name.to_string(), args: args.to_string() })
+    }
+}
+
+impl fmt::Display for LayerRuleEffect {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unset => write!(f, "unset"),
+            Self::NoAnim => write!(f, "noanim"),
+            Self::Blur => write!(f, "blur"),
+            Self::BlurPopups => write!(f, "blurpopups"),
+            Self::IgnoreZero => write!(f, "ignorezero"),
+            Self::DimAround => write!(f, "dimaround"),
+            Self::NoScreenShare => write!(f, "noscreenshare"),
+            Self::IgnoreAlpha(alpha) => write!(f, "ignorealpha {}", alpha),
+            Self::XRay(on) => write!(f, "xray {}", if *on { 1 } else { 0 }),
+            Self::Animation(style) => write!(f, "animation {}", style),
+            Self::Order(order) => write!(f, "order {}", order),
+            Self::AboveLock { interactable: false } => write!(f, "abovelock"),
+            Self::AboveLock { interactable: true } => write!(f, "abovelock true"),
+            Self::Other { name, args } if args.is_empty() => write!(f, "{}", name),
+            Self::Other { name, args } => write!(f, "{} {}", name, args),
         }
     }
-    Ok(rule)
 }
 
+/// Parse the value of a `layerrule = ` line, e.g. `ignorealpha 0.3, rofi`
+pub fn parse_layer_rule(content: &str) -> Result<LayerRule> {
+    let (effect, namespace) = content.split_once(',')
+        .ok_or_else(|| anyhow::anyhow!("Layer rule needs an effect and a namespace"))?;
+    let namespace = namespace.trim();
+    if effect.trim().is_empty() || namespace.is_empty() {
+        return Err(anyhow::anyhow!("Layer rule needs an effect and a namespace"));
+    }
+    
+    Ok(LayerRule {
+        effect: LayerRuleEffect::parse(effect),
+        namespace: namespace.to_string(),
+    })
+}
+
+/// Parse the value of a `windowrule = ` line.
+///
+/// Accepts both the legacy `RULE, WINDOW` form, where `WINDOW` is a bare class
+/// regex, and the `key:value` matchers used by `windowrulev2`.
 pub fn parse_window_rule(content: &str) -> Result<WindowRule> {
-    let parts: Vec<&str> = content.splitn(3, ',').collect();
-    let mut rule = WindowRule::default();
-    if parts.len() >= 2 {
-        rule.rule = parts[0].trim().to_string();
-        rule.value = parts[1].trim().to_string();
-        if parts.len() > 2 {
-            rule.parameters = parts[2]
-                .trim()
-                .split(',')
-                .map(|s| s.trim().to_string())
-                .collect();
+    parse_window_rule_line(WindowRuleSyntax::V1, content)
+}
+
+/// Parse the value of a `windowrulev2 = ` line
+pub fn parse_window_rule_v2(content: &str) -> Result<WindowRule> {
+    parse_window_rule_line(WindowRuleSyntax::V2, content)
+}
+
+fn parse_window_rule_line(syntax: WindowRuleSyntax, content: &str) -> Result<WindowRule> {
+    let (effect, fields) = content.split_once(',')
+        .ok_or_else(|| anyhow::anyhow!("Window rule needs an effect and at least one matcher"))?;
+    let effect = effect.trim();
+    if effect.is_empty() {
+        return Err(anyhow::anyhow!("Window rule has no effect"));
+    }
+
+    let mut matchers: Vec<WindowRuleMatcher> = Vec::new();
+    for field in fields.split(',') {
+        let typed = field.split_once(':').and_then(|(k, v)| WindowRuleMatcher::from_parts(k, v));
+        match (typed, matchers.last_mut()) {
+            (Some(matcher), _) => matchers.push(matcher),
+            (None, _) if syntax == WindowRuleSyntax::V1 => {
+                matchers.push(WindowRuleMatcher::Window(field.trim().to_string()))
+            }
+            // A comma inside a regex: the field continues the previous matcher
+            (None, Some(WindowRuleMatcher::Class(v) | WindowRuleMatcher::Title(v)
+                | WindowRuleMatcher::InitialClass(v) | WindowRuleMatcher::InitialTitle(v)
+                | WindowRuleMatcher::Tag(v) | WindowRuleMatcher::Content(v)
+                | WindowRuleMatcher::XdgTag(v))) if !field.contains(':') => {
+                v.push(',');
+                v.push_str(field.trim_end());
+            }
+            (None, _) => {
+                let (key, value) = field.split_once(':').unwrap_or((field, ""));
+                debug!("Unknown window rule matcher: {}", key.trim());
+                matchers.push(WindowRuleMatcher::Other {
+                    key: key.trim().to_string(),
+                    value: value.trim().to_string(),
+                });
+            }
         }
     }
+
+    Ok(WindowRule {
+        syntax,
+        name: None,
+        effects: vec![WindowRuleEffect::parse(effect)],
+        matchers,
+    })
+}
+
+/// Parse the body of a `windowrule { ... }` block
+pub fn parse_window_rule_block(content: &str) -> Result<WindowRule> {
+    let mut rule = WindowRule { syntax: WindowRuleSyntax::Block, ..Default::default() };
+
+    for line in content.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let Some((key, value)) = line.split_once('=') else { continue };
+        let key = key.trim();
+        let value = value.trim();
+
+        if key == "name" {
+            rule.name = Some(value.to_string());
+        } else if let Some(key) = key.strip_prefix("match:") {
+            let matcher = WindowRuleMatcher::from_parts(key, value).unwrap_or_else(|| {
+                debug!("Unknown window rule matcher: {}", key);
+                WindowRuleMatcher::Other { key: key.to_string(), value: value.to_string() }
+            });
+            rule.matchers.push(matcher);
+        } else {
+            // Effects without arguments are switched on with a boolean
+            let is_flag = WINDOW_RULE_FLAGS.iter().any(|(name, _)| *name == key);
+            let effect = if is_flag && parse_bool(value) {
+                WindowRuleEffect::from_parts(key, "")
+            } else {
+                WindowRuleEffect::from_parts(key, value)
+            };
+            rule.effects.push(effect);
+        }
+    }
+
+    if rule.effects.is_empty() {
+        debug!("Window rule {} has no effects", rule.name.as_deref().unwrap_or("<unnamed>"));
+    }
     Ok(rule)
 }
 
@@ -68,36 +506,62 @@ pub fn parse_workspace_rule(content: &str) -> Result<WorkspaceRule> {
     Ok(rule)
 }
 
-/// Write a window rule to the provided writer
+/// Write a window rule to the provided writer, in the syntax it was read in
 pub fn write_window_rule<W: Write>(
     writer: &mut W,
     rule: &WindowRule,
     comment_style: &CommentStyle,
 ) -> io::Result<()> {
-    let mut rule_str = format!("windowrule = {},{}", rule.rule, rule.value);
+    let keyword = match rule.syntax {
+        WindowRuleSyntax::V1 => "windowrule",
+        WindowRuleSyntax::V2 => "windowrulev2",
+        WindowRuleSyntax::Block => return write_window_rule_block(writer, rule, comment_style),
+    };
+
+    let matchers: Vec<String> = rule.matchers.iter().map(|m| m.to_field(rule.syntax)).collect();
     
-    // Add any additional parameters
-    for param in &rule.parameters {
-        rule_str.push_str(&format!(",{}", param));
+    // Line rules carry a single effect, so each effect gets its own line
+    for effect in &rule.effects {
+        write!(writer, "{} = {}", keyword, effect)?;
+        for matcher in &matchers {
+            write!(writer, ", {}", matcher)?;
+        }
+        writeln!(writer)?;
     }
     
-    // Simply write the rule without comment handling
-    writeln!(writer, "{}", rule_str)?;
-    
     Ok(())
 }
 
-/// Write a workspace rule to the provided writer
-pub fn write_workspace_rule<W: Write>(
+fn write_window_rule_block<W: Write>(
     writer: &mut W,
-    rule: &WorkspaceRule,
+    rule: &WindowRule,
     comment_style: &CommentStyle,
 ) -> io::Result<()> {
+    write_section_header(writer, "windowrule {", comment_style)?;
+    
+    if let Some(name) = &rule.name {
+        write_option(writer, " name", name, None, comment_style)?;
+    }
+    for matcher in &rule.matchers {
+        let key = format!(" match:{}", matcher.key(WindowRuleSyntax::Block));
+        write_option(writer, &key, &matcher.value(WindowRuleSyntax::Block), None, comment_style)?;
+    }
+    for effect in &rule.effects {
+        let key = format!(" {}", effect.name());
+        write_option(writer, &key, &effect.args().unwrap_or("on"), None, comment_style)?;
+    }
+    
+    writeln!(writer, "}}")?;
+    Ok(())
+}
+
+/// Write a workspace rule to the provided writer
+pub fn write_workspace_rule<W: Write>(writer: &mut W, rule: &WorkspaceRule) -> io::Result<()> {
     let mut workspace_str = format!("workspace = {}", rule.workspace);
     
     // Add rules
     for (key, value) in &rule.rules {
-        workspace_str.push_str(&format!(",{}:{}", key, value));
+        workspace_str.push_str(&format!(", {}:{}", key, value));
     }
     
     // Simply write the rule without comment handling
@@ -107,21 +571,8 @@ pub fn write_workspace_rule<W: Write>(
 }
 
 /// Write a layer rule to the provided writer
-pub fn write_layer_rule<W: Write>(
-    writer: &mut W,
-    rule: &LayerRule,
-    comment_style: &CommentStyle,
-) -> io::Result<()> {
-    // Handle the Option<String> value correctly with the correct field names
-    let value_str = match &rule.value {
-        Some(v) => format!(",{}", v),
-        None => String::new(),
-    };
-    
-    let layer_str = format!("layerrule = {},{}{}", rule.rule, rule.target, value_str);
-    
-    // Simply write the rule without comment handling
-    writeln!(writer, "{}", layer_str)?;
+pub fn write_layer_rule<W: Write>(writer: &mut W, rule: &LayerRule) -> io::Result<()> {
+    writeln!(writer, "layerrule = {}, {}", rule.effect, rule.namespace)?;
     
     Ok(())
 }
@@ -138,9 +589,9 @@ impl crate::config::models::core::ConfigSection for Vec<WindowRule> {
 
 /// Implement ConfigSection for Vec<WorkspaceRule>
 impl crate::config::models::core::ConfigSection for Vec<WorkspaceRule> {
-    fn write_section<W: Write>(&self, writer: &mut W, comment_style: &CommentStyle) -> io::Result<()> {
+    fn write_section<W: Write>(&self, writer: &mut W, _comment_style: &CommentStyle) -> io::Result<()> {
         for rule in self {
-            write_workspace_rule(writer, rule, comment_style)?;
+            write_workspace_rule(writer, rule)?;
         }
         Ok(())
     }
@@ -148,10 +599,90 @@ impl crate::config::models::core::ConfigSection for Vec<WorkspaceRule> {
 
 /// Implement ConfigSection for Vec<LayerRule>
 impl crate::config::models::core::ConfigSection for Vec<LayerRule> {
-    fn write_section<W: Write>(&self, writer: &mut W, comment_style: &CommentStyle) -> io::Result<()> {
+    fn write_section<W: Write>(&self, writer: &mut W, _comment_style: &CommentStyle) -> io::Result<()> {
         for rule in self {
-            write_layer_rule(writer, rule, comment_style)?;
+            write_layer_rule(writer, rule)?;
         }
         Ok(())
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn written(rule: &WindowRule) -> String {
+        let mut buffer = Vec::new();
+        write_window_rule(&mut buffer, rule, &CommentStyle::Hash).unwrap();
+        String::from_utf8(buffer).unwrap()
+    }
+
+    #[test]
+    fn legacy_rule_matches_a_bare_class() {
+        let rule = parse_window_rule("float, ^(kitty)$").unwrap();
+        assert_eq!(rule.effects, [WindowRuleEffect::Float]);
+        assert_eq!(rule.matchers, [WindowRuleMatcher::Window("^(kitty)$".to_string())]);
+        assert_eq!(written(&rule), "windowrule = float, ^(kitty)$\n");
+    }
+
+    #[test]
+    fn v2_rule_has_typed_matchers() {
+        let rule = parse_window_rule_v2("opacity 1.0 0.85, class:^(a, b)$, xwayland:1, initialClass:foo, weird:x").unwrap();
+        assert_eq!(rule.effects, [WindowRuleEffect::Opacity("1.0 0.85".to_string())]);
+        assert_eq!(rule.matchers, [
+            WindowRuleMatcher::Class("^(a, b)$".to_string()),
+            WindowRuleMatcher::Xwayland(true),
+            WindowRuleMatcher::InitialClass("foo".to_string()),
+            WindowRuleMatcher::Other { key: "weird".to_string(), value: "x".to_string() },
+        ]);
+        assert_eq!(written(&rule), "windowrulev2 = opacity 1.0 0.85, class:^(a, b)$, xwayland:1, initialClass:foo, weird:x\n");
+    }
+
+    #[test]
+    fn block_rule_uses_block_spellings() {
+        let rule = parse_window_rule_block("name = kitty-float\nmatch:class = kitty\nmatch:initial_title = x\nfloat = true\nsize = 800 600\n").unwrap();
+        assert_eq!(rule.name.as_deref(), Some("kitty-float"));
+        assert_eq!(rule.matchers[1], WindowRuleMatcher::InitialTitle("x".to_string()));
+        assert_eq!(rule.effects, [WindowRuleEffect::Float, WindowRuleEffect::Size("800 600".to_string())]);
+
+        let reparsed = written(&rule);
+        let body = reparsed.trim_start_matches("windowrule {\n").trim_end_matches("}\n");
+        let again = parse_window_rule_block(body).unwrap();
+        assert_eq!((again.name, again.matchers, again.effects), (rule.name, rule.matchers, rule.effects));
+    }
+
+    #[test]
+    fn line_rule_with_several_effects_writes_one_line_each() {
+        let mut rule = parse_window_rule_v2("float, class:kitty").unwrap();
+        rule.effects.push(WindowRuleEffect::Center);
+        assert_eq!(written(&rule), "windowrulev2 = float, class:kitty\nwindowrulev2 = center, class:kitty\n");
+    }
+
+    #[test]
+    fn layer_rules_parse_typed_effects() {
+        let cases = [
+            ("blur, waybar", LayerRuleEffect::Blur),
+            ("ignorealpha 0.3, ^(notifications)$", LayerRuleEffect::IgnoreAlpha(0.3)),
+            ("animation slide top, wofi", LayerRuleEffect::Animation("slide top".to_string())),
+            ("order -1, bar", LayerRuleEffect::Order(-1)),
+            ("abovelock true, lockwidget", LayerRuleEffect::AboveLock { interactable: true }),
+            ("frob 3, x", LayerRuleEffect::Other { name: "frob".to_string(), args: "3".to_string() }),
+        ];
+        for (line, effect) in cases {
+            let rule = parse_layer_rule(line).unwrap();
+            assert_eq!(rule.effect, effect, "{}", line);
+
+            let mut buffer = Vec::new();
+            write_layer_rule(&mut buffer, &rule).unwrap();
+            assert_eq!(String::from_utf8(buffer).unwrap(), format!("layerrule = {}\n", line));
+        }
+        assert!(parse_layer_rule("blur").is_err());
+    }
+
+    #[test]
+    fn layer_rule_namespace_regex() {
+        let rule = |namespace: &str| LayerRule { effect: LayerRuleEffect::Blur, namespace: namespace.to_string() };
+        assert!(rule("^(waybar)$").namespace_regex().unwrap().unwrap().is_match("waybar"));
+        assert!(rule("(waybar").namespace_regex().unwrap().is_err());
+        assert!(rule("address:0x1234").namespace_regex().is_none());
+    }
+}
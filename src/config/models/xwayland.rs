@@ -1,9 +1,10 @@
 use std::io::{self, Write};
 use anyhow::Result;
+use indexmap::IndexMap;
 use log::debug;
 use serde::{Deserialize, Serialize};
-use crate::config::utils::parse_bool;
-use crate::config::utils::{CommentStyle, write_boolean_option, write_section_header};
+use crate::config::utils::{parse_bool_value};
+use crate::config::utils::{CommentStyle, write_extra_options, write_boolean_option, write_section_header};
 
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -12,6 +13,9 @@ pub struct XWaylandSection {
     pub use_nearest_neighbor: bool,
     pub force_zero_scaling: bool,
     pub create_abstract_socket: bool,
+    /// Options hyprconf has no model for, in file order
+    #[serde(default)]
+    pub extra: IndexMap<String, String>,
 }
 
 pub fn parse_xwayland_section(section: &mut XWaylandSection, content: &str) -> Result<()> {
@@ -26,11 +30,14 @@ pub fn parse_xwayland_section(section: &mut XWaylandSection, content: &str) -> R
             let value = value.trim();
             
             match key {
-                "enabled" => section.enabled = parse_bool(value),
-                "use_nearest_neighbor" => section.use_nearest_neighbor = parse_bool(value),
-                "force_zero_scaling" => section.force_zero_scaling = parse_bool(value),
-                "create_abstract_socket" => section.create_abstract_socket = parse_bool(value),
-                _ => debug!("Unknown xwayland setting: {}", key),
+                "enabled" => section.enabled = parse_bool_value(key, value)?,
+                "use_nearest_neighbor" => section.use_nearest_neighbor = parse_bool_value(key, value)?,
+                "force_zero_scaling" => section.force_zero_scaling = parse_bool_value(key, value)?,
+                "create_abstract_socket" => section.create_abstract_socket = parse_bool_value(key, value)?,
+                _ => {
+                    debug!("Unknown xwayland setting: {}", key);
+                    section.extra.insert(key.to_string(), value.to_string());
+                }
             }
         }
     }
@@ -50,6 +57,7 @@ pub fn write_section<W: Write>(
     write_boolean_option(writer, " force_zero_scaling", xwayland.force_zero_scaling, None, comment_style)?;
     write_boolean_option(writer, " create_abstract_socket", xwayland.create_abstract_socket, None, comment_style)?;
     
+    write_extra_options(writer, " ", &xwayland.extra, comment_style)?;
     writeln!(writer, "}}")?;
     Ok(())
 }
\ No newline at end of file
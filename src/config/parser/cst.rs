@@ -0,0 +1,784 @@
+use std::fmt;
+use std::ops::Range;
+
+use indexmap::IndexMap;
+
+use crate::config::models::binds::is_bind_keyword;
+
+/// Indentation used for new lines when the document gives no hint
+const DEFAULT_INDENT: &str = "    ";
+
+/// Leading path component of binds that live in a submap
+const SUBMAP_PREFIX: &str = "submap";
+
+/// Blocks that each describe one record rather than a category of options.
+///
+/// They may repeat, are told apart by their `name` when they have one, e.g.
+/// `device[mouse]:sensitivity`, and always stay blocks when flattening.
+const RECORD_BLOCKS: &[&str] = &["device", "windowrule"];
+
+/// Prefixes that belong to a key inside a block rather than naming a nested block
+const KEY_PREFIXES: &[&str] = &["match"];
+
+/// A `key = value` line, with the value located inside the raw text
+#[derive(Debug, Clone, PartialEq)]
+pub struct Assignment {
+    pub key: String,
+    /// Value with `##` escapes resolved
+    pub value: String,
+    /// Byte range of the value inside the raw line
+    pub value_span: Range<usize>,
+    pub comment: Option<String>,
+}
+
+/// What a single physical line contains
+#[derive(Debug, Clone, PartialEq)]
+pub enum LineKind {
+    Blank,
+    Comment(String),
+    Assignment(Assignment),
+    BlockOpen(String),
+    BlockClose,
+    /// Anything that is not valid config syntax, kept verbatim
+    Unknown,
+}
+
+/// One physical line of a config file
+#[derive(Debug, Clone, PartialEq)]
+pub struct Line {
+    /// 1-based line number
+    pub number: usize,
+    /// The line exactly as written, without the line terminator
+    pub raw: String,
+    pub kind: LineKind,
+    /// Names of the blocks enclosing this line, outermost first
+    pub section: Vec<String>,
+    /// Submap whose `submap = name` ... `submap = reset` region holds this line
+    pub submap: Option<String>,
+}
+
+impl Line {
+    /// Leading whitespace of the line
+    pub fn indent(&self) -> &str {
+        let trimmed = self.raw.trim_start();
+        &self.raw[..self.raw.len() - trimmed.len()]
+    }
+
+    /// Full colon-separated option path for assignments, e.g. `decoration:blur:size`.
+    ///
+    /// Binds inside a submap region are scoped to it, e.g. `submap:resize:binde`.
+    pub fn path(&self) -> Option<String> {
+        match &self.kind {
+            LineKind::Assignment(a) => Some(match &self.submap {
+                Some(submap) if self.section.is_empty() && is_bind_keyword(&a.key) => {
+                    format!("{}:{}:{}", SUBMAP_PREFIX, submap, a.key)
+                }
+                _ => join_path(&self.section, &a.key),
+            }),
+            _ => None,
+        }
+    }
+
+    /// Comment text on this line, whether it is a full-line or an inline comment
+    pub fn comment(&self) -> Option<&str> {
+        match &self.kind {
+            LineKind::Comment(c) => Some(c),
+            LineKind::Assignment(a) => a.comment.as_deref(),
+            _ => None,
+        }
+    }
+}
+
+/// Lossless concrete syntax tree of a Hyprland config file.
+///
+/// Every line, comment and blank line is kept, so writing the document back
+/// out reproduces the input byte for byte unless something was edited.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Document {
+    lines: Vec<Line>,
+    trailing_newline: bool,
+}
+
+impl Document {
+    /// Parse config text into a document
+    pub fn parse(content: &str) -> Self {
+        let mut raw_lines: Vec<&str> = content.split('\n').collect();
+        let trailing_newline = raw_lines.len() > 1 && raw_lines.last() == Some(&"");
+        if trailing_newline {
+            raw_lines.pop();
+        }
+
+        let kinds: Vec<LineKind> = raw_lines.iter().map(|raw| classify_line(raw)).collect();
+        let mut stack: Vec<String> = Vec::new();
+        let mut submap: Option<String> = None;
+        let mut lines = Vec::with_capacity(raw_lines.len());
+        for (i, (raw, kind)) in raw_lines.into_iter().zip(kinds.iter().cloned()).enumerate() {
+            let section = stack.clone();
+            let line_submap = submap.clone();
+            match &kind {
+                LineKind::BlockOpen(name) if RECORD_BLOCKS.contains(&name.as_str()) => {
+                    stack.push(match block_name(&kinds[i + 1..]) {
+                        Some(key) => format!("{}[{}]", name, key),
+                        None => name.clone(),
+                    });
+                }
+                LineKind::BlockOpen(name) => stack.push(name.clone()),
+                LineKind::BlockClose => {
+                    stack.pop();
+                }
+                LineKind::Assignment(a) if stack.is_empty() && a.key == "submap" => {
+                    submap = Some(a.value.clone()).filter(|name| name != "reset");
+                }
+                _ => {}
+            }
+            lines.push(Line {
+                number: i + 1,
+                raw: raw.to_string(),
+                kind,
+                section,
+                submap: line_submap,
+            });
+        }
+
+        Self { lines, trailing_newline }
+    }
+
+    /// All lines of the document in order
+    pub fn lines(&self) -> &[Line] {
+        &self.lines
+    }
+
+    /// Iterate over assignments as (line index, full path, assignment)
+    pub fn assignments(&self) -> impl Iterator<Item = (usize, String, &Assignment)> {
+        self.lines.iter().enumerate().filter_map(|(i, line)| match (&line.kind, line.path()) {
+            (LineKind::Assignment(a), Some(path)) => Some((i, path, a)),
+            _ => None,
+        })
+    }
+
+    /// Index of the last assignment to `path`, which is the one Hyprland applies
+    pub fn find(&self, path: &str) -> Option<usize> {
+        self.find_all(path).pop()
+    }
+
+    /// Indices of every assignment to `path`, in file order
+    pub fn find_all(&self, path: &str) -> Vec<usize> {
+        self.assignments()
+            .filter(|(_, p, _)| p == path)
+            .map(|(i, _, _)| i)
+            .collect()
+    }
+
+    /// Value of the assignment at `index`
+    pub fn value_at(&self, index: usize) -> Option<&str> {
+        match &self.lines.get(index)?.kind {
+            LineKind::Assignment(a) => Some(a.value.as_str()),
+            _ => None,
+        }
+    }
+
+    /// All values assigned to each path, in order of first appearance
+    pub fn values_by_path(&self) -> IndexMap<String, Vec<String>> {
+        let mut map: IndexMap<String, Vec<String>> = IndexMap::new();
+        for (_, path, a) in self.assignments() {
+            map.entry(path).or_default().push(a.value.clone());
+        }
+        map
+    }
+
+    /// Set `path` to `value`, rewriting only the line that holds it.
+    ///
+    /// If the option is not present yet it is added to the matching block,
+    /// creating the block at the end of the file when needed.
+    pub fn set(&mut self, path: &str, value: &str) {
+        match self.find(path) {
+            Some(index) => self.set_value(index, value),
+            None => self.insert(path, value),
+        }
+    }
+
+    /// Make `path` hold exactly `values`, for options that may repeat (binds, monitors, ...).
+    ///
+    /// Existing values are matched up with the new ones, so only lines whose value
+    /// was added, removed or changed are touched; the others keep their text.
+    pub fn set_all(&mut self, path: &str, values: &[String]) {
+        let existing = self.find_all(path);
+        if existing.is_empty() {
+            for value in values {
+                self.insert(path, value);
+            }
+            return;
+        }
+
+        let old: Vec<String> = existing.iter().filter_map(|&i| self.value_at(i).map(str::to_string)).collect();
+        for edit in diff_values(&old, values).into_iter().rev() {
+            match edit {
+                ValueEdit::Keep(_) => {}
+                ValueEdit::Replace { old, new } => self.set_value(existing[old], &values[new]),
+                ValueEdit::Remove(old) => self.remove_line(existing[old]),
+                ValueEdit::InsertAfter { after, new } => {
+                    self.insert_value(existing[after] + 1, existing[after], &values[new])
+                }
+                ValueEdit::InsertBefore { before, new } => {
+                    self.insert_value(existing[before], existing[before], &values[new])
+                }
+            }
+        }
+    }
+
+    /// Insert a new assignment of `value` before `index`, with the key and indentation of line `like`
+    pub fn insert_value(&mut self, index: usize, like: usize, value: &str) {
+        let line = &self.lines[like];
+        let LineKind::Assignment(a) = &line.kind else { return };
+        let raw = format!("{}{} = {}", line.indent(), a.key, escape_value(value));
+        self.insert_line(index, raw);
+    }
+
+    /// Insert a raw line before `index`
+    pub fn insert_line(&mut self, index: usize, raw: String) {
+        let index = index.min(self.lines.len());
+        self.lines.insert(index, placeholder(raw));
+        self.reindex();
+    }
+
+    /// Remove the line at `index`, along with any block that leaves empty
+    pub fn remove_line(&mut self, index: usize) {
+        if index >= self.lines.len() {
+            return;
+        }
+        self.lines.remove(index);
+
+        let mut at = index;
+        while at > 0 && at < self.lines.len()
+            && matches!(self.lines[at - 1].kind, LineKind::BlockOpen(_))
+            && self.lines[at].kind == LineKind::BlockClose
+        {
+            self.lines.drain(at - 1..=at);
+            at -= 1;
+            // Don't leave the blank line that separated the block behind
+            let before_is_blank = at == 0 || self.lines[at - 1].kind == LineKind::Blank;
+            let after_is_blank = self.lines.get(at).is_none_or(|l| l.kind == LineKind::Blank);
+            if before_is_blank && after_is_blank {
+                if at > 0 {
+                    self.lines.remove(at - 1);
+                    at -= 1;
+                } else if !self.lines.is_empty() {
+                    self.lines.remove(at);
+                }
+            }
+        }
+        self.reindex();
+    }
+
+    /// Replace the value of the assignment at `index`, keeping the rest of the line
+    pub fn set_value(&mut self, index: usize, value: &str) {
+        let line = &mut self.lines[index];
+        if let LineKind::Assignment(a) = &mut line.kind {
+            let mut escaped = escape_value(value);
+            if a.value_span.is_empty() && a.comment.is_some() {
+                escaped.push(' ');
+            }
+            line.raw.replace_range(a.value_span.clone(), &escaped);
+            a.value_span = a.value_span.start..a.value_span.start + escaped.trim_end().len();
+            a.value = value.to_string();
+        }
+    }
+
+    /// Add a new option, placing it inside the deepest existing block on its path
+    fn insert(&mut self, path: &str, value: &str) {
+        if let Some((submap, key)) = path
+            .strip_prefix(SUBMAP_PREFIX)
+            .and_then(|p| p.strip_prefix(':'))
+            .and_then(|p| p.rsplit_once(':'))
+        {
+            self.insert_submap_bind(submap, key, value);
+            return;
+        }
+
+        let (section, key) = split_path(path);
+        let unit = self.indent_unit();
+
+        // Variables only apply to lines after them, so keep new ones with the others
+        if section.is_empty() && key.starts_with('$') {
+            let last_variable = self.lines.iter().rposition(|l| {
+                l.section.is_empty() && matches!(&l.kind, LineKind::Assignment(a) if a.key.starts_with('$'))
+            });
+            if let Some(index) = last_variable {
+                self.insert_line(index + 1, format!("{} = {}", key, escape_value(value)));
+                return;
+            }
+        }
+
+        for depth in (1..=section.len()).rev() {
+            let Some(close) = self.find_block_close(&section[..depth]) else { continue };
+            let mut new_lines = Vec::new();
+            push_nested(&mut new_lines, &section[depth..], key, value, depth, &unit);
+            for (offset, raw) in new_lines.into_iter().enumerate() {
+                self.lines.insert(close + offset, placeholder(raw));
+            }
+            self.reindex();
+            return;
+        }
+
+        if !section.is_empty() && self.lines.last().is_some_and(|l| l.kind != LineKind::Blank) {
+            self.lines.push(placeholder(String::new()));
+        }
+        let mut new_lines = Vec::new();
+        push_nested(&mut new_lines, &section, key, value, 0, &unit);
+        self.lines.extend(new_lines.into_iter().map(placeholder));
+        if !self.trailing_newline && self.lines.len() > 1 {
+            self.trailing_newline = true;
+        }
+        self.reindex();
+    }
+
+    /// Add a bind to the end of the last region of `submap`, creating the region if needed
+    fn insert_submap_bind(&mut self, submap: &str, key: &str, value: &str) {
+        let raw = format!("{} = {}", key, escape_value(value));
+        let marker = |l: &Line| match &l.kind {
+            LineKind::Assignment(a) if l.section.is_empty() && a.key == "submap" => Some(a.value.clone()),
+            _ => None,
+        };
+
+        if let Some(start) = self.lines.iter().rposition(|l| marker(l).as_deref() == Some(submap)) {
+            let end = self.lines[start + 1..]
+                .iter()
+                .position(|l| l.submap.as_deref() != Some(submap) || marker(l).is_some())
+                .map_or(self.lines.len(), |offset| start + 1 + offset);
+            self.lines.insert(end, placeholder(raw));
+        } else {
+            if self.lines.last().is_some_and(|l| l.kind != LineKind::Blank) {
+                self.lines.push(placeholder(String::new()));
+            }
+            self.lines.push(placeholder(format!("submap = {}", submap)));
+            self.lines.push(placeholder(raw));
+            self.lines.push(placeholder("submap = reset".to_string()));
+        }
+        self.reindex();
+    }
+
+    /// Index of the closing brace of the last block at exactly `section`
+    fn find_block_close(&self, section: &[String]) -> Option<usize> {
+        self.lines
+            .iter()
+            .rposition(|l| l.kind == LineKind::BlockClose && l.section.as_slice() == section)
+    }
+
+    /// Indentation of one nesting level, taken from the first indented option
+    fn indent_unit(&self) -> String {
+        self.lines
+            .iter()
+            .find(|l| l.section.len() == 1 && matches!(l.kind, LineKind::Assignment(_)))
+            .map(|l| l.indent().to_string())
+            .filter(|indent| !indent.is_empty())
+            .unwrap_or_else(|| DEFAULT_INDENT.to_string())
+    }
+
+    /// Rewrite options inside category blocks as top-level `section:key = value` lines.
+    ///
+    /// Record blocks such as `device` and `windowrule` are kept as blocks.
+    pub fn flatten(&self) -> Document {
+        let mut raw_lines = Vec::with_capacity(self.lines.len());
+        for line in &self.lines {
+            let in_record = line.section.first().is_some_and(|s| is_record_block(s));
+            match &line.kind {
+                _ if in_record => raw_lines.push(line.raw.clone()),
+                LineKind::BlockOpen(name) if line.section.is_empty() && is_record_block(name) => {
+                    raw_lines.push(line.raw.clone())
+                }
+                LineKind::BlockOpen(_) | LineKind::BlockClose => {}
+                LineKind::Assignment(a) if !line.section.is_empty() => {
+                    let mut raw = format!("{} = {}", join_path(&line.section, &a.key), escape_value(&a.value));
+                    if let Some(comment) = &a.comment {
+                        raw.push_str(&format!(" # {}", comment));
+                    }
+                    raw_lines.push(raw);
+                }
+                _ => raw_lines.push(line.raw.trim_start().to_string()),
+            }
+        }
+
+        let mut content = raw_lines.join("\n");
+        if self.trailing_newline {
+            content.push('\n');
+        }
+        Document::parse(&content)
+    }
+
+    /// Rebuild line numbers and section paths after a structural edit
+    fn reindex(&mut self) {
+        let trailing_newline = self.trailing_newline;
+        let content = self.lines.iter().map(|l| l.raw.as_str()).collect::<Vec<_>>().join("\n");
+        *self = Document::parse(&content);
+        if self.lines.len() == 1 && self.lines[0].raw.is_empty() && content.is_empty() {
+            self.lines.clear();
+        }
+        self.trailing_newline = trailing_newline;
+    }
+}
+
+impl fmt::Display for Document {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, line) in self.lines.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{}", line.raw)?;
+        }
+        if self.trailing_newline && !self.lines.is_empty() {
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+/// One step in turning a list of existing values into a new one
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValueEdit {
+    /// Existing value `old` stays as written
+    Keep(usize),
+    /// Existing value `old` becomes new value `new`
+    Replace { old: usize, new: usize },
+    Remove(usize),
+    /// New value `new` goes right after existing value `after`
+    InsertAfter { after: usize, new: usize },
+    /// New value `new` goes right before existing value `before`
+    InsertBefore { before: usize, new: usize },
+}
+
+/// Match `old` values to `new` ones along their longest common subsequence.
+///
+/// Edits come in list order and positions refer to the original lists, so they
+/// must be applied last first. A removal next to an addition becomes a
+/// `Replace`, which keeps the rest of the line such as its comment. When `old`
+/// is empty there is nothing to place new values next to and no inserts are
+/// returned; callers add those values themselves.
+pub fn diff_values(old: &[String], new: &[String]) -> Vec<ValueEdit> {
+    let (n, m) = (old.len(), new.len());
+    let mut common = vec![vec![0usize; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            common[i][j] = if old[i] == new[j] {
+                common[i + 1][j + 1] + 1
+            } else {
+                common[i + 1][j].max(common[i][j + 1])
+            };
+        }
+    }
+
+    let mut edits = Vec::new();
+    let (mut removed, mut added) = (Vec::new(), Vec::new());
+    let mut kept = None;
+    let (mut i, mut j) = (0, 0);
+    while i < n || j < m {
+        if i < n && j < m && old[i] == new[j] {
+            flush_changes(&mut edits, &mut removed, &mut added, kept, Some(i));
+            edits.push(ValueEdit::Keep(i));
+            kept = Some(i);
+            i += 1;
+            j += 1;
+        } else if j < m && (i == n || common[i][j + 1] >= common[i + 1][j]) {
+            added.push(j);
+            j += 1;
+        } else {
+            removed.push(i);
+            i += 1;
+        }
+    }
+    flush_changes(&mut edits, &mut removed, &mut added, kept, None);
+    edits
+}
+
+/// Turn a run of removed and added values between two kept ones into edits
+fn flush_changes(
+    edits: &mut Vec<ValueEdit>,
+    removed: &mut Vec<usize>,
+    added: &mut Vec<usize>,
+    kept: Option<usize>,
+    next: Option<usize>,
+) {
+    let paired = removed.len().min(added.len());
+    edits.extend(removed.iter().zip(added.iter()).map(|(&old, &new)| ValueEdit::Replace { old, new }));
+    edits.extend(removed[paired..].iter().map(|&old| ValueEdit::Remove(old)));
+
+    let after = removed[..paired].last().copied().or(kept);
+    for &new in &added[paired..] {
+        match (after, next) {
+            (Some(after), _) => edits.push(ValueEdit::InsertAfter { after, new }),
+            (None, Some(before)) => edits.push(ValueEdit::InsertBefore { before, new }),
+            (None, None) => {}
+        }
+    }
+    removed.clear();
+    added.clear();
+}
+
+/// Split a raw line into its code and the comment text after `#`.
+///
+/// Hyprland treats `##` as an escaped literal `#`, so those do not start a comment.
+pub fn split_comment(raw: &str) -> (&str, Option<&str>) {
+    let bytes = raw.as_bytes();
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'#' {
+            if bytes.get(i + 1) == Some(&b'#') {
+                i += 2;
+                continue;
+            }
+            return (&raw[..i], Some(&raw[i + 1..]));
+        }
+        i += 1;
+    }
+    (raw, None)
+}
+
+/// Join a section path and key into a colon-separated option path
+pub fn join_path(section: &[String], key: &str) -> String {
+    if section.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}:{}", section.join(":"), key)
+    }
+}
+
+/// Split an option path into its section names and the final key
+pub fn split_path(path: &str) -> (Vec<String>, &str) {
+    let Some((section, key)) = path.rsplit_once(':') else {
+        return (Vec::new(), path);
+    };
+    let mut section: Vec<String> = section.split(':').map(str::to_string).collect();
+    // Keys like `match:class` in window rule blocks keep their prefix
+    if section.len() > 1 && section.last().is_some_and(|s| KEY_PREFIXES.contains(&s.as_str())) {
+        let prefix = section.pop().unwrap_or_default();
+        return (section, &path[path.len() - key.len() - prefix.len() - 1..]);
+    }
+    (section, key)
+}
+
+fn classify_line(raw: &str) -> LineKind {
+    // `##` only escapes a `#` inside a value; a line starting with `#` is always a comment
+    if let Some(comment) = raw.trim_start().strip_prefix('#') {
+        return LineKind::Comment(comment.trim().to_string());
+    }
+    let (code, comment) = split_comment(raw);
+    let trimmed = code.trim();
+    let comment = comment.map(|c| c.trim().to_string());
+
+    if trimmed.is_empty() {
+        return match comment {
+            Some(c) => LineKind::Comment(c),
+            None => LineKind::Blank,
+        };
+    }
+    if trimmed == "}" {
+        return LineKind::BlockClose;
+    }
+    if let Some(name) = trimmed.strip_suffix('{') {
+        return LineKind::BlockOpen(name.trim().to_string());
+    }
+    if let Some(eq) = code.find('=') {
+        let key = code[..eq].trim().to_string();
+        let after = &code[eq + 1..];
+        let start = eq + 1 + (after.len() - after.trim_start().len());
+        let end = (eq + 1 + after.trim_end().len()).max(start);
+        return LineKind::Assignment(Assignment {
+            key,
+            value: unescape_value(&code[start..end]),
+            value_span: start..end,
+            comment,
+        });
+    }
+    LineKind::Unknown
+}
+
+fn unescape_value(value: &str) -> String {
+    value.replace("##", "#")
+}
+
+fn escape_value(value: &str) -> String {
+    value.replace('#', "##")
+}
+
+fn placeholder(raw: String) -> Line {
+    Line { number: 0, raw, kind: LineKind::Blank, section: Vec::new(), submap: None }
+}
+
+/// Value of the `name = ` option directly inside the block starting before `lines`
+fn block_name(lines: &[LineKind]) -> Option<String> {
+    let mut depth = 0;
+    for kind in lines {
+        match kind {
+            LineKind::BlockOpen(_) => depth += 1,
+            LineKind::BlockClose if depth == 0 => return None,
+            LineKind::BlockClose => depth -= 1,
+            LineKind::Assignment(a) if depth == 0 && a.key == "name" => return Some(a.value.clone()),
+            _ => {}
+        }
+    }
+    None
+}
+
+pub fn is_record_block(section: &str) -> bool {
+    let block = split_keyed(section).map_or(section, |(block, _)| block);
+    RECORD_BLOCKS.contains(&block)
+}
+
+/// Split a keyed section like `device[mouse]` into its block and name
+pub fn split_keyed(section: &str) -> Option<(&str, &str)> {
+    let (block, rest) = section.split_once('[')?;
+    Some((block, rest.strip_suffix(']')?))
+}
+
+fn push_nested(out: &mut Vec<String>, sections: &[String], key: &str, value: &str, depth: usize, unit: &str) {
+    let mut named = false;
+    for (i, section) in sections.iter().enumerate() {
+        match split_keyed(section) {
+            Some((block, name)) => {
+                out.push(format!("{}{} {{", unit.repeat(depth + i), block));
+                out.push(format!("{}name = {}", unit.repeat(depth + i + 1), escape_value(name)));
+                named = i + 1 == sections.len();
+            }
+            None => out.push(format!("{}{} {{", unit.repeat(depth + i), section)),
+        }
+    }
+    // A keyed block already got its `name` line when it was opened
+    if !(named && key == "name") {
+        out.push(format!("{}{} = {}", unit.repeat(depth + sections.len()), key, escape_value(value)));
+    }
+    for i in (0..sections.len()).rev() {
+        out.push(format!("{}}}", unit.repeat(depth + i)));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BINDS: &str = "\
+$mainMod = SUPER
+bind = $mainMod, Q, exec, kitty # terminal
+bind = $mainMod, E, exec, thunar # files
+bind = $mainMod SHIFT, M, exit, # quit
+";
+
+    fn values(values: &[&str]) -> Vec<String> {
+        values.iter().map(|v| v.to_string()).collect()
+    }
+
+    #[test]
+    fn display_reproduces_input() {
+        let inputs = [
+            include_str!("../../../docs/hyprland.conf"),
+            include_str!("../../../docs/hyprland_mine.conf"),
+            include_str!("../../../docs/default.conf"),
+            "",
+            "\n",
+            "general {\n\tgaps_in = 5 # inner\n}",
+            "col = rgba(00000000) ## not a comment\r\nbroken line\n\n",
+        ];
+        for input in inputs {
+            assert_eq!(Document::parse(input).to_string(), input);
+        }
+    }
+
+    #[test]
+    fn paths_follow_blocks_and_submaps() {
+        let document = Document::parse("decoration {\n  blur {\n    size = 3\n  }\n}\nsubmap = resize\nbind = , h, resizeactive, -10 0\nsubmap = reset\n");
+        let paths: Vec<String> = document.assignments().map(|(_, path, _)| path).collect();
+        assert_eq!(paths, ["decoration:blur:size", "submap", "submap:resize:bind", "submap"]);
+    }
+
+    #[test]
+    fn set_rewrites_only_the_value() {
+        let mut document = Document::parse("general {\n    gaps_in = 5   # inner\n}\n");
+        document.set("general:gaps_in", "10");
+        document.set("general:gaps_out", "20");
+        assert_eq!(document.to_string(), "general {\n    gaps_in = 10   # inner\n    gaps_out = 20\n}\n");
+    }
+
+    #[test]
+    fn set_all_removes_only_the_deleted_line() {
+        let mut document = Document::parse(BINDS);
+        document.set_all("bind", &values(&["$mainMod, E, exec, thunar", "$mainMod SHIFT, M, exit,"]));
+        assert_eq!(document.to_string(), BINDS.replace("bind = $mainMod, Q, exec, kitty # terminal\n", ""));
+    }
+
+    #[test]
+    fn removing_the_last_option_drops_its_blocks() {
+        let mut document = Document::parse("$a = 1\n\ndecoration {\n    blur {\n        size = 3\n    }\n}\n\nmisc {\n}\n");
+        document.remove_line(4);
+        assert_eq!(document.to_string(), "$a = 1\n\nmisc {\n}\n");
+    }
+
+    #[test]
+    fn set_all_inserts_next_to_its_neighbour() {
+        let mut document = Document::parse(BINDS);
+        document.set_all("bind", &values(&[
+            "$mainMod, Q, exec, kitty",
+            "$mainMod, B, exec, firefox",
+            "$mainMod, E, exec, thunar",
+            "$mainMod SHIFT, M, exit,",
+        ]));
+        let expected = BINDS.replace("# terminal\n", "# terminal\nbind = $mainMod, B, exec, firefox\n");
+        assert_eq!(document.to_string(), expected);
+    }
+
+    #[test]
+    fn set_all_keeps_the_comment_of_a_changed_line() {
+        let mut document = Document::parse(BINDS);
+        document.set_all("bind", &values(&["$mainMod, Q, exec, kitty", "$mainMod, E, exec, nautilus", "$mainMod SHIFT, M, exit,"]));
+        assert_eq!(document.to_string(), BINDS.replace("thunar # files", "nautilus # files"));
+    }
+
+    #[test]
+    fn diff_values_pairs_changes_and_keeps_the_rest() {
+        let old = values(&["a", "b", "c", "d"]);
+        let new = values(&["a", "x", "c", "d", "e"]);
+        assert_eq!(diff_values(&old, &new), [
+            ValueEdit::Keep(0),
+            ValueEdit::Replace { old: 1, new: 1 },
+            ValueEdit::Keep(2),
+            ValueEdit::Keep(3),
+            ValueEdit::InsertAfter { after: 3, new: 4 },
+        ]);
+        assert_eq!(diff_values(&old, &values(&["z", "c"])), [
+            ValueEdit::Replace { old: 0, new: 0 },
+            ValueEdit::Remove(1),
+            ValueEdit::Keep(2),
+            ValueEdit::Remove(3),
+        ]);
+        assert_eq!(diff_values(&values(&["b"]), &values(&["a", "b"])), [
+            ValueEdit::InsertBefore { before: 0, new: 0 },
+            ValueEdit::Keep(0),
+        ]);
+    }
+
+    #[test]
+    fn submap_binds_go_into_their_region() {
+        let mut document = Document::parse("submap = resize\nbinde = , l, resizeactive, 10 0\nsubmap = reset\nbind = SUPER, Q, exec, kitty\n");
+        document.set_all("submap:resize:binde", &values(&[", l, resizeactive, 10 0", ", h, resizeactive, -10 0"]));
+        document.set_all("submap:move:bind", &values(&[", escape, submap, reset"]));
+        assert_eq!(
+            document.to_string(),
+            "submap = resize\nbinde = , l, resizeactive, 10 0\nbinde = , h, resizeactive, -10 0\nsubmap = reset\nbind = SUPER, Q, exec, kitty\n\nsubmap = move\nbind = , escape, submap, reset\nsubmap = reset\n"
+        );
+    }
+
+    #[test]
+    fn match_keys_stay_inside_rule_blocks() {
+        assert_eq!(split_path("windowrule[kitty]:match:class"), (vec!["windowrule[kitty]".to_string()], "match:class"));
+
+        let mut document = Document::parse("windowrule {\n    name = kitty\n    float = true\n}\n");
+        document.set("windowrule[kitty]:match:class", "kitty");
+        assert_eq!(document.to_string(), "windowrule {\n    name = kitty\n    float = true\n    match:class = kitty\n}\n");
+    }
+
+    #[test]
+    fn flatten_moves_block_options_to_colon_paths() {
+        let document = Document::parse("general {\n    gaps_in = 5 # inner\n}\ndevice {\n    name = mouse\n    sensitivity = 1\n}\n");
+        assert_eq!(
+            document.flatten().to_string(),
+            "general:gaps_in = 5 # inner\ndevice {\n    name = mouse\n    sensitivity = 1\n}\n"
+        );
+    }
+}
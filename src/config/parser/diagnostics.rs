@@ -0,0 +1,232 @@
+use std::fmt;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+use crate::config::utils::InvalidValue;
+use super::cst::{Line, LineKind};
+
+/// How serious a diagnostic is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Severity::Warning => write!(f, "warning"),
+            Severity::Error => write!(f, "error"),
+        }
+    }
+}
+
+/// A problem found while reading a config, pointing at the text it is about
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub file: PathBuf,
+    /// 1-based line number
+    pub line: usize,
+    /// 1-based column of the first character pointed at
+    pub column: usize,
+    /// Byte range inside the line that is pointed at
+    pub span: Range<usize>,
+    pub message: String,
+    /// How the problem might be fixed
+    pub suggestion: Option<String>,
+    /// ID of the lint that raised it, e.g. `duplicate-bind`
+    pub code: Option<&'static str>,
+}
+
+impl Diagnostic {
+    pub fn new(severity: Severity, file: &Path, line: &Line, span: Range<usize>, message: impl Into<String>) -> Self {
+        let start = span.start.min(line.raw.len());
+        Self {
+            severity,
+            file: file.to_path_buf(),
+            line: line.number,
+            column: line.raw[..start].chars().count() + 1,
+            span,
+            message: message.into(),
+            suggestion: None,
+            code: None,
+        }
+    }
+
+    pub fn error(file: &Path, line: &Line, span: Range<usize>, message: impl Into<String>) -> Self {
+        Self::new(Severity::Error, file, line, span, message)
+    }
+
+    pub fn warning(file: &Path, line: &Line, span: Range<usize>, message: impl Into<String>) -> Self {
+        Self::new(Severity::Warning, file, line, span, message)
+    }
+
+    pub fn with_suggestion(mut self, suggestion: impl Into<String>) -> Self {
+        self.suggestion = Some(suggestion.into());
+        self
+    }
+
+    pub fn with_code(mut self, code: &'static str) -> Self {
+        self.code = Some(code);
+        self
+    }
+
+    /// An error for a value a parser rejected, pointing at the offending part of the value
+    pub fn invalid_value(file: &Path, line: &Line, error: &anyhow::Error) -> Self {
+        let Some(invalid) = error.downcast_ref::<InvalidValue>() else {
+            return Self::error(file, line, value_span(line), format!("{:#}", error));
+        };
+
+        let whole = value_span(line);
+        let span = line.raw[whole.clone()]
+            .find(&invalid.value)
+            .filter(|_| !invalid.value.is_empty())
+            .map(|at| whole.start + at..whole.start + at + invalid.value.len())
+            .unwrap_or(whole);
+        let diagnostic = Self::error(file, line, span, invalid.to_string());
+        match suggest_value(invalid) {
+            Some(suggestion) => diagnostic.with_suggestion(suggestion),
+            None => diagnostic,
+        }
+    }
+
+    pub fn is_error(&self) -> bool {
+        self.severity == Severity::Error
+    }
+
+    /// `warning`, or `warning[code]` for a lint
+    fn heading(&self) -> String {
+        match self.code {
+            Some(code) => format!("{}[{}]", self.severity, code),
+            None => self.severity.to_string(),
+        }
+    }
+
+    /// Render the diagnostic the way rustc does, with `source` as the text of its line
+    pub fn render(&self, source: &str) -> String {
+        let number = self.line.to_string();
+        let gutter = " ".repeat(number.len());
+        let start = self.span.start.min(source.len());
+        let end = self.span.end.clamp(start, source.len());
+        let text = source.replace('\t', "    ");
+        let offset = source[..start].replace('\t', "    ").chars().count();
+        let width = source[start..end].replace('\t', "    ").chars().count().max(1);
+
+        let mut out = format!("{}: {}\n", self.heading(), self.message);
+        out.push_str(&format!("{}--> {}:{}:{}\n", gutter, self.file.display(), self.line, self.column));
+        out.push_str(&format!("{} |\n", gutter));
+        out.push_str(&format!("{} | {}\n", number, text));
+        out.push_str(&format!("{} | {}{}\n", gutter, " ".repeat(offset), "^".repeat(width)));
+        if let Some(suggestion) = &self.suggestion {
+            out.push_str(&format!("{} = help: {}\n", gutter, suggestion));
+        }
+        out
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}:{}: {}: {}", self.file.display(), self.line, self.column, self.heading(), self.message)
+    }
+}
+
+/// Span of an assignment's value, or of the whole line for anything else
+pub fn value_span(line: &Line) -> Range<usize> {
+    match &line.kind {
+        LineKind::Assignment(a) if !a.value_span.is_empty() => a.value_span.clone(),
+        _ => content_span(line),
+    }
+}
+
+/// Span of an assignment's key, or of the whole line for anything else
+pub fn key_span(line: &Line) -> Range<usize> {
+    match &line.kind {
+        LineKind::Assignment(a) => match line.raw.find(a.key.as_str()) {
+            Some(at) => at..at + a.key.len(),
+            None => content_span(line),
+        },
+        _ => content_span(line),
+    }
+}
+
+/// Span of the line without surrounding whitespace
+pub fn content_span(line: &Line) -> Range<usize> {
+    let start = line.indent().len();
+    start..line.raw.trim_end().len().max(start)
+}
+
+/// Guess what was meant by a value that failed to parse
+fn suggest_value(invalid: &InvalidValue) -> Option<String> {
+    if invalid.expected == "a boolean" {
+        return Some("use `true` or `false`".to_string());
+    }
+
+    // Letters typed in place of the digits they look like, e.g. `1O`
+    let fixed: String = invalid.value.chars().map(|c| match c {
+        'O' | 'o' => '0',
+        'l' | 'I' => '1',
+        c => c,
+    }).collect();
+    let parses = match invalid.expected {
+        "an integer" => fixed.parse::<i64>().is_ok(),
+        _ => fixed.parse::<f64>().is_ok(),
+    };
+    (parses && fixed != invalid.value).then(|| format!("did you mean `{}`?", fixed))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::parser::ConfigParser;
+    use crate::config::parser::cst::Document;
+    use crate::config::parser::source::SourceTree;
+
+    fn validate(content: &str) -> Vec<Diagnostic> {
+        ConfigParser::validate(&SourceTree::from_string(content))
+    }
+
+    #[test]
+    fn mistyped_number_points_at_the_value_with_a_suggestion() {
+        let content = "decoration {\n    rounding = 1O\n}\n";
+        let diagnostics = validate(content);
+        let diagnostic = diagnostics.iter().find(|d| d.is_error()).unwrap();
+
+        assert_eq!((diagnostic.line, diagnostic.column), (2, 16));
+        assert_eq!(&content.lines().nth(1).unwrap()[diagnostic.span.clone()], "1O");
+        assert_eq!(diagnostic.suggestion.as_deref(), Some("did you mean `10`?"));
+    }
+
+    #[test]
+    fn bad_boolean_suggests_true_or_false() {
+        let diagnostics = validate("misc {\n    vfr = maybe\n}\n");
+        let diagnostic = diagnostics.iter().find(|d| d.is_error()).unwrap();
+        assert_eq!(diagnostic.suggestion.as_deref(), Some("use `true` or `false`"));
+    }
+
+    #[test]
+    fn valid_config_has_no_errors() {
+        let diagnostics = validate(include_str!("../../../docs/hyprland.conf"));
+        assert!(!diagnostics.iter().any(Diagnostic::is_error), "{:?}", diagnostics);
+    }
+
+    #[test]
+    fn render_underlines_the_span() {
+        let document = Document::parse("decoration {\n    rounding = 1O\n}\n");
+        let line = &document.lines()[1];
+        let diagnostic = Diagnostic::error(Path::new("hyprland.conf"), line, 15..17, "bad value")
+            .with_suggestion("did you mean `10`?")
+            .with_code("some-lint");
+
+        assert_eq!(
+            diagnostic.render(&line.raw),
+            "error[some-lint]: bad value\n \
+             --> hyprland.conf:2:16\n  \
+             |\n\
+             2 |     rounding = 1O\n  \
+             |                ^^\n  \
+             = help: did you mean `10`?\n"
+        );
+        assert_eq!(diagnostic.to_string(), "hyprland.conf:2:16: error[some-lint]: bad value");
+    }
+}
@@ -0,0 +1,696 @@
+pub mod cst;
+pub mod diagnostics;
+pub mod source;
+pub mod variables;
+
+use std::path::{Path, PathBuf};
+use anyhow::Result;
+use indexmap::IndexMap;
+use log::debug;
+
+use crate::config::models::{
+    core::HyprlandConfig,
+    animations,
+    binds,
+    cursor,
+    debug,
+    decoration,
+    devices,
+    dwindle,
+    ecosystem,
+    exec,
+    experimental,
+    general,
+    gestures,
+    group,
+    input,
+    master,
+    misc,
+    monitors::MonitorConfig,
+    opengl,
+    permissions,
+    plugins::{PluginEntry, PluginSection},
+    render,
+    rules,
+    xwayland,
+};
+use crate::config::schema;
+use crate::config::utils::{InvalidValue, parse_value};
+use cst::{Document, Line, LineKind};
+use diagnostics::{Diagnostic, content_span, key_span, value_span};
+use source::SourceTree;
+use variables::{Variables, expand_with};
+
+pub struct ConfigParser;
+
+/// An option hyprconf has no model for, located in the config files
+#[derive(Debug, Clone)]
+pub struct UnknownOption {
+    pub file: PathBuf,
+    /// 1-based line number
+    pub line: usize,
+    /// Full colon path of the option, e.g. `general:some_new_option`
+    pub path: String,
+}
+
+impl ConfigParser {
+    /// Parse a config file, following `source =` includes
+    pub fn parse_file<P: AsRef<Path>>(path: P) -> Result<HyprlandConfig> {
+        let sources = SourceTree::load(path)?;
+        Self::parse_sources(&sources)
+    }
+
+    /// Parse config text on its own; `source =` lines are not followed
+    pub fn parse_string(content: &str) -> Result<HyprlandConfig> {
+        let document = Document::parse(content);
+        let lines = document.lines().iter().map(|line| (Path::new(""), line));
+        Ok(Self::log_diagnostics(Self::parse_lines(lines)))
+    }
+
+    /// Parse an already loaded tree of config files
+    pub fn parse_sources(sources: &SourceTree) -> Result<HyprlandConfig> {
+        Ok(Self::log_diagnostics(Self::parse_with_diagnostics(sources)))
+    }
+
+    /// Parse an already loaded tree of config files, collecting every problem found.
+    ///
+    /// Values that fail to parse are reported and leave the option unset.
+    pub fn parse_with_diagnostics(sources: &SourceTree) -> (HyprlandConfig, Vec<Diagnostic>) {
+        Self::parse_lines(sources.lines().into_iter())
+    }
+
+    /// Check a loaded config: parse errors, unknown options and variable problems
+    pub fn validate(sources: &SourceTree) -> Vec<Diagnostic> {
+        let (config, mut diagnostics) = Self::parse_with_diagnostics(sources);
+        let lines = sources.lines();
+        let line_at = |file: &Path, number: usize| {
+            lines.iter().find(|(f, l)| *f == file && l.number == number).map(|(_, l)| *l)
+        };
+
+        for unknown in Self::unknown_options(sources, &config) {
+            if let Some(line) = line_at(&unknown.file, unknown.line) {
+                let message = format!("unknown option `{}`", unknown.path);
+                diagnostics.push(Diagnostic::warning(&unknown.file, line, key_span(line), message));
+            }
+        }
+
+        // Permission rules do nothing unless Hyprland is told to enforce them
+        if !config.permissions.is_empty() && !config.ecosystem.enforce_permissions {
+            let first = lines.iter().find(|(_, line)| {
+                matches!(&line.kind, LineKind::Assignment(a) if a.key == "permission" && line.section.is_empty())
+            });
+            if let Some((file, line)) = first {
+                let message = "permission rules are ignored while `ecosystem:enforce_permissions` is off";
+                diagnostics.push(Diagnostic::warning(file, line, key_span(line), message)
+                    .with_suggestion("set `ecosystem:enforce_permissions = true`"));
+            }
+        }
+
+        let variables = Variables::from_sources(sources);
+        for (file, line) in &lines {
+            let Some(path) = line.path() else { continue };
+            let Some(option) = schema::lookup(&path) else { continue };
+            let LineKind::Assignment(a) = &line.kind else { continue };
+            if let Some(version) = option.removed {
+                let message = format!("`{}` was removed in Hyprland {}", path, version);
+                diagnostics.push(Diagnostic::warning(file, line, key_span(line), message));
+                continue;
+            }
+            let reported = diagnostics.iter().any(|d| d.is_error() && d.file == *file && d.line == line.number);
+            if reported {
+                continue;
+            }
+            let value = variables.resolve(&a.value);
+            if value.contains('$') {
+                continue;
+            }
+            if let Err(message) = option.check(&value) {
+                diagnostics.push(Diagnostic::error(file, line, value_span(line), message));
+            }
+        }
+        for site in variables.undefined() {
+            let Some(line) = line_at(&site.file, site.line) else { continue };
+            let reference = format!("${}", site.name);
+            let span = line.raw.find(&reference)
+                .map(|at| at..at + reference.len())
+                .unwrap_or_else(|| value_span(line));
+            let message = format!("undefined variable `{}`", reference);
+            diagnostics.push(Diagnostic::warning(&site.file, line, span, message)
+                .with_suggestion(format!("define `{} = ...` before this line", reference)));
+        }
+        for site in variables.unused() {
+            let Some(line) = line_at(&site.file, site.line) else { continue };
+            let message = format!("unused variable `${}`", site.name);
+            diagnostics.push(Diagnostic::warning(&site.file, line, key_span(line), message));
+        }
+
+        // Report in the order the files were loaded
+        let file_order = |file: &Path| sources.files().iter().position(|f| f.path == file);
+        diagnostics.sort_by_key(|d| (file_order(&d.file), d.line));
+        diagnostics
+    }
+
+    /// A config holding the default of every option in the schema
+    pub fn defaults() -> HyprlandConfig {
+        let mut config = HyprlandConfig::default();
+        for option in schema::options() {
+            if let Err(e) = Self::process_option_path(&mut config, option.path, option.default) {
+                debug!("Schema default for {} was rejected: {:#}", option.path, e);
+            }
+            // Removed options the model no longer has would only be kept as unknown
+            if option.is_removed() {
+                if let Some(extra) = config.extra_options_mut(option.section()) {
+                    extra.shift_remove(option.key());
+                }
+            }
+        }
+        config
+    }
+
+    fn log_diagnostics((config, diagnostics): (HyprlandConfig, Vec<Diagnostic>)) -> HyprlandConfig {
+        for diagnostic in diagnostics {
+            debug!("{}", diagnostic);
+        }
+        config
+    }
+
+    /// Locate every option that `config` kept in an `extra` map because it is not modelled
+    pub fn unknown_options(sources: &SourceTree, config: &HyprlandConfig) -> Vec<UnknownOption> {
+        let mut unknown = Vec::new();
+        for (file, line) in sources.lines() {
+            let Some(path) = line.path() else { continue };
+            let (section, key) = cst::split_path(&path);
+            if section.is_empty() {
+                continue;
+            }
+            let is_extra = config
+                .extra_options(&section.join(":"))
+                .is_some_and(|extra| extra.contains_key(key));
+            if is_extra {
+                unknown.push(UnknownOption { file: file.to_path_buf(), line: line.number, path: path.clone() });
+            }
+        }
+        unknown
+    }
+
+    fn parse_lines<'a>(mut lines: impl Iterator<Item = (&'a Path, &'a Line)>) -> (HyprlandConfig, Vec<Diagnostic>) {
+        debug!("Parsing Hyprland configuration");
+
+        let mut config = Self::defaults();
+        let mut diagnostics = Vec::new();
+        let mut variables = IndexMap::new();
+        let mut env_vars = IndexMap::new();
+        let mut autostart = Vec::new();
+        let mut current_submap: Option<String> = None;
+
+        while let Some((file, line)) = lines.next() {
+            let assignment = match &line.kind {
+                LineKind::Blank | LineKind::Comment(_) => continue,
+                LineKind::Unknown => {
+                    diagnostics.push(Diagnostic::error(file, line, content_span(line), "expected `key = value`, `name {` or `}`"));
+                    continue;
+                }
+                LineKind::BlockClose => {
+                    diagnostics.push(Diagnostic::error(file, line, content_span(line), "unmatched closing brace"));
+                    continue;
+                }
+                LineKind::BlockOpen(name) => {
+                    debug!("Entering section: {}", name);
+                    let block = Self::parse_block(file, line, name, &mut lines, &mut diagnostics);
+                    Self::process_block(&mut config, block, &variables, &mut diagnostics);
+                    continue;
+                }
+                LineKind::Assignment(a) => a,
+            };
+
+            let key = assignment.key.as_str();
+            let value = assignment.value.as_str();
+
+            if let Some(name) = key.strip_prefix('$') {
+                variables.insert(name.to_string(), value.to_string());
+                continue;
+            }
+
+            let parsed = match key {
+                "env" => {
+                    if let Some((n,v)) = value.split_once(',') {
+                        env_vars.insert(n.trim().to_string(), v.trim().to_string());
+                    }
+                    Ok(())
+                }
+                k if exec::ExecKind::from_keyword(k).is_some() => {
+                    exec::parse_exec(key, value).map(|command| autostart.push(command))
+                }
+                "source" => Ok(()),
+                "submap" => {
+                    if value == "reset" {
+                        current_submap = None;
+                    } else {
+                        config.submap_definitions.entry(value.to_string()).or_default();
+                        current_submap = Some(value.to_string());
+                    }
+                    Ok(())
+                }
+                "monitor" => Self::with_variables(&variables, value, Self::parse_monitor)
+                    .map(|monitor| config.monitors.extend(monitor)),
+                "windowrule" | "windowrulev2" => {
+                    let rule = if key == "windowrule" {
+                        rules::parse_window_rule(value)
+                    } else {
+                        rules::parse_window_rule_v2(value)
+                    };
+                    rule.map(|rule| config.window_rules.push(rule))
+                }
+                "permission" => permissions::parse_permission(value).map(|p| config.permissions.push(p)),
+                "gesture" => gestures::parse_gesture(value).map(|gesture| config.gestures.gestures.push(gesture)),
+                "layerrule" => rules::parse_layer_rule(value).map(|rule| config.layer_rules.push(rule)),
+                "workspace" => rules::parse_workspace_rule(value).map(|rule| config.workspace_rules.push(rule)),
+                k if k.contains(':') => Self::with_variables(&variables, value, |value| {
+                    Self::process_option_path(&mut config, k, value)
+                }),
+                k if binds::is_bind_keyword(k) => binds::parse_bind(k, value).map(|bind| match &current_submap {
+                    Some(name) => config.submap_definitions.entry(name.clone()).or_default().push(bind),
+                    None => config.binds.keybinds.push(bind),
+                }),
+                _ => {
+                    debug!("Top-level setting: {} = {}", key, value);
+                    Ok(())
+                }
+            };
+            if let Err(e) = parsed {
+                diagnostics.push(Diagnostic::invalid_value(file, line, &e));
+            }
+        }
+
+        config.variables = variables;
+        config.environment_variables = env_vars;
+        config.autostart_programs = autostart;
+        (config, diagnostics)
+    }
+
+    /// Parse the value of a `monitor = name,resolution,position,scale,...` line
+    fn parse_monitor(value: &str) -> Result<Option<MonitorConfig>> {
+        let parts: Vec<&str> = value.split(',').collect();
+        if parts.len() < 4 {
+            return Ok(None);
+        }
+        let mut m = MonitorConfig {
+            name: parts[0].trim().to_string(),
+            resolution: parts[1].trim().to_string(),
+            position: parts[2].trim().to_string(),
+            ..Default::default()
+        };
+        if parts[3].trim() != "auto" {
+            m.scale = parse_value("scale", parts[3])?;
+        }
+        if let Some(t)  = parts.get(4)  { m.transform = Some(parse_value("transform", t)?); }
+        if let Some(mi) = parts.get(5)  { m.mirror = Some(mi.trim().to_string()); }
+        if let Some(bd) = parts.get(6)  { m.bitdepth = Some(parse_value("bitdepth", bd)?); }
+        if let Some(cm) = parts.get(7)  { m.color_management = Some(cm.trim().to_string()); }
+        if let Some(sb) = parts.get(8)  { m.sdr_brightness = Some(parse_value("sdr_brightness", sb)?); }
+        if let Some(ss) = parts.get(9)  { m.sdr_saturation = Some(parse_value("sdr_saturation", ss)?); }
+        if let Some(v)  = parts.get(10) { m.vrr = Some(parse_value("vrr", v)?); }
+        if let Some(d)  = parts.get(11) { m.disable = parse_value::<i32>("disable", d)? != 0; }
+        if parts.len() >= 16 {
+            m.reserved_area = Some((
+                parse_value("reserved_area", parts[12])?,
+                parse_value("reserved_area", parts[13])?,
+                parse_value("reserved_area", parts[14])?,
+                parse_value("reserved_area", parts[15])?,
+            ));
+        }
+        Ok(Some(m))
+    }
+
+    /// Read the body of a block whose opening line was just consumed, up to its closing brace
+    fn parse_block<'a>(
+        file: &'a Path,
+        open: &'a Line,
+        name: &str,
+        lines: &mut impl Iterator<Item = (&'a Path, &'a Line)>,
+        diagnostics: &mut Vec<Diagnostic>,
+    ) -> Block<'a> {
+        let mut block = Block { name: name.to_string(), file, open, entries: Vec::new() };
+        while let Some((_, line)) = lines.next() {
+            match &line.kind {
+                LineKind::Assignment(_) => block.entries.push(BlockEntry::Option(line)),
+                LineKind::BlockOpen(child) => {
+                    let child = Self::parse_block(file, line, child, lines, diagnostics);
+                    block.entries.push(BlockEntry::Block(child));
+                }
+                LineKind::BlockClose => return block,
+                LineKind::Unknown => {
+                    diagnostics.push(Diagnostic::error(file, line, content_span(line), "expected `key = value`, `name {` or `}`"));
+                }
+                LineKind::Blank | LineKind::Comment(_) => {}
+            }
+        }
+        diagnostics.push(
+            Diagnostic::error(file, open, content_span(open), format!("section `{}` is never closed", name))
+                .with_suggestion("add a closing `}`"),
+        );
+        block
+    }
+
+    /// Hand a block to the typed section parsers, keeping whatever they have no model for
+    fn process_block(
+        config: &mut HyprlandConfig,
+        block: Block,
+        variables: &IndexMap<String, String>,
+        diagnostics: &mut Vec<Diagnostic>,
+    ) {
+        let known = if Self::is_whole_block(&block.name) {
+            // Records only make sense as a whole, so problems are traced back to their line
+            if let Err(e) = Self::process_section_block(config, &block.name, &block.options_content()) {
+                diagnostics.push(block.locate(&e));
+            }
+            true
+        } else {
+            // An empty body tells whether the section is known without changing anything
+            let known = matches!(Self::process_section_block(config, &block.name, ""), Ok(true));
+            for (line, key, value) in block.options().filter(|_| known).filter_map(assignment) {
+                let parsed = Self::with_variables(variables, value, |value| {
+                    Self::process_section_block(config, &block.name, &format!("{} = {}\n", key, value))
+                });
+                if let Err(e) = parsed {
+                    diagnostics.push(Diagnostic::invalid_value(block.file, line, &e));
+                }
+            }
+            known
+        };
+        if !known {
+            config.plugins.push(block.to_plugin_section());
+            return;
+        }
+
+        let mut unknown = PluginSection::new(&block.name);
+        for child in block.blocks() {
+            let known = matches!(Self::process_nested_section_block(config, &block.name, &child.name, ""), Ok(true));
+            if !known {
+                unknown.entries.push(PluginEntry::Section(child.to_plugin_section()));
+                continue;
+            }
+            for (line, key, value) in child.options().filter_map(assignment) {
+                let parsed = Self::with_variables(variables, value, |value| {
+                    let content = format!("{} = {}\n", key, value);
+                    Self::process_nested_section_block(config, &block.name, &child.name, &content)
+                });
+                if let Err(e) = parsed {
+                    diagnostics.push(Diagnostic::invalid_value(child.file, line, &e));
+                }
+            }
+            if child.blocks().next().is_some() {
+                let mut deeper = PluginSection::new(&child.name);
+                deeper.entries = child.blocks().map(|b| PluginEntry::Section(b.to_plugin_section())).collect();
+                unknown.entries.push(PluginEntry::Section(deeper));
+            }
+        }
+        if !unknown.entries.is_empty() {
+            config.plugins.push(unknown);
+        }
+    }
+
+    /// Parse `value` as written, or with its `$variables` expanded if that fails,
+    /// since Hyprland substitutes variables before reading a value
+    fn with_variables<T>(
+        variables: &IndexMap<String, String>,
+        value: &str,
+        mut parse: impl FnMut(&str) -> Result<T>,
+    ) -> Result<T> {
+        match parse(value) {
+            Err(_) if value.contains('$') => parse(&expand_with(variables, value).0),
+            parsed => parsed,
+        }
+    }
+
+    /// Blocks whose options are parsed together rather than one line at a time
+    fn is_whole_block(section: &str) -> bool {
+        cst::is_record_block(section) || section == "bezier_curves"
+    }
+
+    /// Set an option of an already parsed config by its colon path, expanding `$variables`
+    pub(crate) fn apply_option(config: &mut HyprlandConfig, path: &str, value: &str) -> Result<()> {
+        let variables = config.variables.clone();
+        Self::with_variables(&variables, value, |value| Self::process_option_path(config, path, value))
+    }
+
+    /// Route a top-level `section:sub:key = value` line through the section parsers
+    fn process_option_path(config: &mut HyprlandConfig, path: &str, value: &str) -> Result<()> {
+        let (section, key) = cst::split_path(path);
+        let content = format!("{} = {}\n", key, value);
+        let known = match section.as_slice() {
+            [block] => match cst::split_keyed(block) {
+                Some((block, name)) => {
+                    let content = format!("name = {}\n{}", name, content);
+                    Self::process_section_block(config, block, &content)?
+                }
+                None => Self::process_section_block(config, block, &content)?,
+            },
+            [block, sub] => Self::process_nested_section_block(config, block, sub, &content)?,
+            _ => false,
+        };
+
+        if !known {
+            let Some((name, rest)) = path.split_once(':') else { return Ok(()) };
+            match config.plugins.iter_mut().rev().find(|p| p.name == name) {
+                Some(plugin) => plugin.set(rest, value),
+                None => {
+                    let mut plugin = PluginSection::new(name);
+                    plugin.set(rest, value);
+                    config.plugins.push(plugin);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Parse the options of a top-level block, returning whether the section is known
+    fn process_section_block(
+        config: &mut HyprlandConfig,
+        section: &str,
+        content: &str
+    ) -> Result<bool> {
+        match section {
+            "general"    => general::parse_general_section(&mut config.general, content)?,
+            "decoration" => decoration::parse_decoration_section(&mut config.decoration, content)?,
+            "animations" => animations::parse_animations_section(&mut config.animations, content)?,
+            "input"      => input::parse_input_section(&mut config.input, content)?,
+            "gestures"   => gestures::parse_gestures_section(&mut config.gestures, content)?,
+            "group"      => group::parse_group_section(&mut config.group, content)?,
+            "misc"       => misc::parse_misc_section(&mut config.misc, content)?,
+            "binds"      => binds::parse_binds_section(&mut config.binds, content)?,
+            "xwayland"   => xwayland::parse_xwayland_section(&mut config.xwayland, content)?,
+            "opengl"     => opengl::parse_opengl_section(&mut config.opengl, content)?,
+            "render"     => render::parse_render_section(&mut config.render, content)?,
+            "cursor"     => cursor::parse_cursor_section(&mut config.cursor, content)?,
+            "dwindle"    => dwindle::parse_dwindle_section(&mut config.dwindle, content)?,
+            "master"     => master::parse_master_section(&mut config.master, content)?,
+            "debug"      => debug::parse_debug_section(&mut config.debug, content)?,
+            "ecosystem"  => ecosystem::parse_ecosystem_section(&mut config.ecosystem, content)?,
+            "experimental"=> experimental::parse_experimental_section(&mut config.experimental, content)?,
+            "bezier_curves" => {
+                config.bezier_curves = content.lines()
+                    .filter_map(|l| l.trim().split_once('=').map(|(k,v)| (k.trim().to_string(), v.trim().to_string())))
+                    .collect();
+            }
+            "device" => {
+                let device = devices::parse_device_section(content)?;
+                match config.devices.iter_mut().find(|d| d.name == device.name) {
+                    Some(existing) => existing.merge(device),
+                    None => config.devices.push(device),
+                }
+            }
+            "windowrule" => config.window_rules.push(rules::parse_window_rule_block(content)?),
+            _ => {
+                debug!("Keeping unknown section: {}", section);
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    /// Parse the options of a block nested in a known section, returning whether it is known
+    fn process_nested_section_block(
+        config: &mut HyprlandConfig,
+        section: &str,
+        subsection: &str,
+        content: &str
+    ) -> Result<bool> {
+        match (section, subsection) {
+            ("general", "snap") => general::parse_snap_section(&mut config.general.snap, content)?,
+            ("decoration", "blur") => decoration::parse_blur_section(&mut config.decoration.blur, content)?,
+            ("decoration", "shadow") => decoration::parse_shadow_section(&mut config.decoration.shadow, content)?,
+            ("input", "touchpad") => input::parse_touchpad_section(&mut config.input.touchpad, content)?,
+            ("input", "touchdevice") => input::parse_touchdevice_section(&mut config.input.touchdevice, content)?,
+            ("input", "tablet") => input::parse_tablet_section(&mut config.input.tablet, content)?,
+            ("group", "groupbar") => group::parse_groupbar_section(&mut config.group.groupbar, content)?,
+            _ => {
+                debug!("Keeping unknown nested section: {}:{}", section, subsection);
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+}
+
+/// A block as read from the files, with the lines its options came from
+struct Block<'a> {
+    name: String,
+    file: &'a Path,
+    /// The `name {` line
+    open: &'a Line,
+    entries: Vec<BlockEntry<'a>>,
+}
+
+enum BlockEntry<'a> {
+    Option(&'a Line),
+    Block(Block<'a>),
+}
+
+impl<'a> Block<'a> {
+    /// Option lines directly inside this block, in order
+    fn options(&self) -> impl Iterator<Item = &'a Line> + '_ {
+        self.entries.iter().filter_map(|entry| match entry {
+            BlockEntry::Option(line) => Some(*line),
+            BlockEntry::Block(_) => None,
+        })
+    }
+
+    /// Blocks directly inside this block, in order
+    fn blocks(&self) -> impl Iterator<Item = &Block<'a>> {
+        self.entries.iter().filter_map(|entry| match entry {
+            BlockEntry::Block(block) => Some(block),
+            BlockEntry::Option(_) => None,
+        })
+    }
+
+    /// Direct options rendered as `key = value` lines, the form section parsers take
+    fn options_content(&self) -> String {
+        self.options().map(option_content).collect()
+    }
+
+    /// The block as a generic tree, for sections hyprconf has no model for
+    fn to_plugin_section(&self) -> PluginSection {
+        let mut section = PluginSection::new(&self.name);
+        for entry in &self.entries {
+            section.entries.push(match entry {
+                BlockEntry::Option(line) => {
+                    let LineKind::Assignment(a) = &line.kind else { continue };
+                    PluginEntry::Option { key: a.key.clone(), value: a.value.clone() }
+                }
+                BlockEntry::Block(block) => PluginEntry::Section(block.to_plugin_section()),
+            });
+        }
+        section
+    }
+
+    /// Point an error from parsing the whole block at the option it is about
+    fn locate(&self, error: &anyhow::Error) -> Diagnostic {
+        let key = error.downcast_ref::<InvalidValue>().map(|invalid| invalid.key.as_str());
+        let line = self.options().filter(|line| {
+            matches!(&line.kind, LineKind::Assignment(a) if Some(a.key.as_str()) == key)
+        }).last();
+        match line {
+            Some(line) => Diagnostic::invalid_value(self.file, line, error),
+            None => Diagnostic::error(self.file, self.open, content_span(self.open), format!("{:#}", error)),
+        }
+    }
+}
+
+/// An option line with its key and value
+fn assignment(line: &Line) -> Option<(&Line, &str, &str)> {
+    match &line.kind {
+        LineKind::Assignment(a) => Some((line, a.key.as_str(), a.value.as_str())),
+        _ => None,
+    }
+}
+
+/// An option line rendered as `key = value`, the form section parsers take
+fn option_content(line: &Line) -> String {
+    match &line.kind {
+        LineKind::Assignment(a) => format!("{} = {}\n", a.key, a.value),
+        _ => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::utils::{CommentStyle, OptionStyle, WriteMode};
+
+    const SUBMAPS: &str = "\
+bind = SUPER, R, submap, resize
+submap = resize
+binde = , right, resizeactive, 10 0
+bind = , escape, submap, reset
+submap = reset
+bind = SUPER, Q, exec, kitty
+";
+
+    #[test]
+    fn submap_regions_hold_their_binds() {
+        let config = ConfigParser::parse_string(SUBMAPS).unwrap();
+        let global: Vec<String> = config.binds.keybinds.iter().map(|b| b.key.to_string()).collect();
+        assert_eq!(global, ["R", "Q"]);
+
+        let resize = &config.submap_definitions["resize"];
+        assert_eq!(resize.len(), 2);
+        assert_eq!(resize[0].keyword(), "binde");
+        assert_eq!(resize[1].arg, "reset");
+    }
+
+    #[test]
+    fn colon_paths_set_the_same_options_as_blocks() {
+        let config = ConfigParser::parse_string("general:gaps_in = 7\ndecoration:blur:size = 9\ndevice[mouse]:sensitivity = 0.4\n").unwrap();
+        assert_eq!(config.general.gaps_in, "7");
+        assert_eq!(config.decoration.blur.size, 9);
+        assert_eq!(config.devices[0].name, "mouse");
+        assert_eq!(config.devices[0].sensitivity, Some(0.4));
+    }
+
+    #[test]
+    fn colon_path_style_parses_back_to_the_same_config() {
+        let mut config = ConfigParser::parse_string(include_str!("../../../docs/hyprland.conf")).unwrap();
+        config.decoration.blur.size = 4;
+        let mut buffer = Vec::new();
+        config.write_styled(&mut buffer, &CommentStyle::Hash, OptionStyle::ColonPath, WriteMode::Full).unwrap();
+        let written = String::from_utf8(buffer).unwrap();
+        assert!(written.contains("\ndecoration:blur:size = 4\n"));
+        assert!(!written.contains("decoration {"));
+
+        let again = ConfigParser::parse_string(&written).unwrap();
+        assert_eq!(again.decoration.blur.size, 4);
+        assert_eq!(again.general.gaps_in, config.general.gaps_in);
+        assert_eq!(again.devices.len(), config.devices.len());
+    }
+
+    #[test]
+    fn unmodelled_blocks_are_kept_as_trees() {
+        let config = ConfigParser::parse_string("plugin {\n    hyprexpo {\n        columns = 3\n    }\n}\nplugin:hyprexpo:gap_size = 5\ndecoration {\n    blur {\n        deeper {\n            x = 1\n        }\n    }\n}\n").unwrap();
+        let mut plugin = PluginSection::new("plugin");
+        plugin.set("hyprexpo:columns", "3");
+        plugin.set("hyprexpo:gap_size", "5");
+        let mut decoration = PluginSection::new("decoration");
+        decoration.set("blur:deeper:x", "1");
+        assert_eq!(config.plugins, [plugin, decoration]);
+    }
+
+    fn messages(content: &str) -> Vec<String> {
+        ConfigParser::validate(&SourceTree::from_string(content)).iter().map(|d| d.message.clone()).collect()
+    }
+
+    #[test]
+    fn unmodelled_options_are_kept_and_reported() {
+        let content = "general {\n    gaps_in = 5\n    some_new_option = 1\n}\n";
+        let config = ConfigParser::parse_string(content).unwrap();
+        assert_eq!(config.general.extra["some_new_option"], "1");
+
+        let unknown = ConfigParser::unknown_options(&SourceTree::from_string(content), &config);
+        assert_eq!(unknown.iter().map(|u| (u.line, u.path.as_str())).collect::<Vec<_>>(), [(3, "general:some_new_option")]);
+        assert!(messages(content).iter().any(|m| m.contains("general:some_new_option")));
+
+        let mut buffer = Vec::new();
+        config.write_to(&mut buffer, &CommentStyle::Hash).unwrap();
+        assert!(String::from_utf8(buffer).unwrap().contains(" some_new_option = 1\n"));
+    }
+}
@@ -0,0 +1,430 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use log::{debug, warn};
+use regex::Regex;
+
+use super::cst::{diff_values, Document, Line, LineKind, ValueEdit};
+use super::ConfigParser;
+
+/// A single file of a (possibly split) configuration
+#[derive(Debug, Clone)]
+pub struct SourceFile {
+    pub path: PathBuf,
+    pub document: Document,
+    /// (position of the `source =` line among the file's source lines, included file)
+    includes: Vec<(usize, usize)>,
+    /// Text as read from disk, to tell which files need writing back
+    loaded: String,
+}
+
+impl SourceFile {
+    /// Whether the document was edited since it was loaded
+    pub fn is_modified(&self) -> bool {
+        self.document.to_string() != self.loaded
+    }
+}
+
+/// A root config file together with every file it pulls in through `source =`
+#[derive(Debug, Clone)]
+pub struct SourceTree {
+    files: Vec<SourceFile>,
+}
+
+impl SourceTree {
+    /// Load `path` and, recursively, every file it sources
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let mut tree = Self { files: Vec::new() };
+        let mut stack = Vec::new();
+        tree.load_file(path.as_ref(), &mut stack)?
+            .context("Failed to read Hyprland config file")?;
+        Ok(tree)
+    }
+
+    /// Build a tree from in-memory text; `source =` lines are left unresolved
+    pub fn from_string(content: &str) -> Self {
+        Self {
+            files: vec![SourceFile {
+                path: PathBuf::new(),
+                document: Document::parse(content),
+                includes: Vec::new(),
+                loaded: content.to_string(),
+            }],
+        }
+    }
+
+    /// The root file
+    pub fn root(&self) -> &SourceFile {
+        &self.files[0]
+    }
+
+    /// All files, the root first, in the order they were loaded
+    pub fn files(&self) -> &[SourceFile] {
+        &self.files
+    }
+
+    /// Every line of the configuration in the order Hyprland reads them,
+    /// with included files spliced in at their `source =` line
+    pub fn lines(&self) -> Vec<(&Path, &Line)> {
+        let mut out = Vec::new();
+        self.collect_lines(0, &mut out);
+        out
+    }
+
+    /// Line `number` (1-based) of the loaded file at `path`
+    pub fn line(&self, path: &Path, number: usize) -> Option<&Line> {
+        let file = self.files.iter().find(|f| f.path == path)?;
+        file.document.lines().get(number.checked_sub(1)?)
+    }
+
+    /// Effective value of `path` across all files
+    pub fn get(&self, path: &str) -> Option<&str> {
+        self.occurrences(path).last().and_then(|(file, line)| self.files[*file].document.value_at(*line))
+    }
+
+    /// Set `path` in the file its effective value came from, or in the root file
+    pub fn set(&mut self, path: &str, value: &str) {
+        match self.occurrences(path).last() {
+            Some((file, line)) => self.files[*file].document.set_value(*line, value),
+            None => self.files[0].document.set(path, value),
+        }
+    }
+
+    /// Apply the differences between two renderings of a config to the files.
+    ///
+    /// Only options whose values differ between `before` and `after` are touched,
+    /// so untouched lines keep their formatting and comments.
+    pub fn apply_changes(&mut self, before: &Document, after: &Document) {
+        let old = before.values_by_path();
+        let new = after.values_by_path();
+
+        for (path, new_values) in &new {
+            let old_values = old.get(path).map(Vec::as_slice).unwrap_or(&[]);
+            // Submap markers follow from the binds placed inside them
+            if old_values == new_values.as_slice() || path == "submap" {
+                continue;
+            }
+            if old_values.len() <= 1 && new_values.len() == 1 {
+                self.set(path, &new_values[0]);
+            } else {
+                self.replace_all(path, old_values, new_values);
+            }
+        }
+        for (path, old_values) in &old {
+            if !new.contains_key(path) && path != "submap" {
+                self.replace_all(path, old_values, &[]);
+            }
+        }
+    }
+
+    /// Make `path` hold exactly `values`, where `old` lists its current values in read order.
+    ///
+    /// `old` may be written differently from the files, e.g. in canonical form; it
+    /// is only used to match lines up with `values`. Lines whose value is kept are
+    /// left as written, and a changed value keeps the rest of its line.
+    fn replace_all(&mut self, path: &str, old: &[String], values: &[String]) {
+        let mut occurrences = self.occurrences(path);
+        if occurrences.is_empty() {
+            self.files[0].document.set_all(path, values);
+            return;
+        }
+        // Lines the parser rejected never made it into `old`; they stay as written
+        if old.len() != occurrences.len() {
+            let rejected = self.rejected_lines();
+            occurrences.retain(|occurrence| !rejected.contains(occurrence));
+        }
+        let current;
+        let old = if old.len() == occurrences.len() {
+            old
+        } else {
+            current = self.values(&occurrences);
+            &current
+        };
+
+        // New values go next to the line they follow, in that line's file
+        for edit in diff_values(old, values).into_iter().rev() {
+            match edit {
+                ValueEdit::Keep(_) => {}
+                ValueEdit::Replace { old, new } => {
+                    let (file, line) = occurrences[old];
+                    self.files[file].document.set_value(line, &values[new]);
+                }
+                ValueEdit::Remove(old) => {
+                    let (file, line) = occurrences[old];
+                    self.files[file].document.remove_line(line);
+                }
+                ValueEdit::InsertAfter { after, new } => {
+                    let (file, line) = occurrences[after];
+                    self.files[file].document.insert_value(line + 1, line, &values[new]);
+                }
+                ValueEdit::InsertBefore { before, new } => {
+                    let (file, line) = occurrences[before];
+                    self.files[file].document.insert_value(line, line, &values[new]);
+                }
+            }
+        }
+    }
+
+    /// Write every edited file back to where it was loaded from
+    pub fn save(&self) -> io::Result<()> {
+        for file in &self.files {
+            if file.path.as_os_str().is_empty() || !file.is_modified() {
+                continue;
+            }
+            debug!("Writing {}", file.path.display());
+            fs::write(&file.path, file.document.to_string())?;
+        }
+        Ok(())
+    }
+
+    /// Locations of every assignment to `path` as (file index, line index), in read order
+    fn occurrences(&self, path: &str) -> Vec<(usize, usize)> {
+        let mut out = Vec::new();
+        self.collect_occurrences(0, path, &mut out);
+        out
+    }
+
+    /// Lines with an error the parser could not read past, as (file index, line index)
+    fn rejected_lines(&self) -> Vec<(usize, usize)> {
+        ConfigParser::validate(self)
+            .iter()
+            .filter(|diagnostic| diagnostic.is_error())
+            .filter_map(|diagnostic| {
+                let file = self.files.iter().position(|f| f.path == diagnostic.file)?;
+                Some((file, diagnostic.line.checked_sub(1)?))
+            })
+            .collect()
+    }
+
+    /// Values at `occurrences`, as written
+    fn values(&self, occurrences: &[(usize, usize)]) -> Vec<String> {
+        occurrences
+            .iter()
+            .filter_map(|(file, line)| self.files[*file].document.value_at(*line).map(str::to_string))
+            .collect()
+    }
+
+    fn collect_occurrences(&self, file: usize, path: &str, out: &mut Vec<(usize, usize)>) {
+        let source = &self.files[file];
+        let mut ordinal = 0;
+        for (i, line) in source.document.lines().iter().enumerate() {
+            if line.path().as_deref() == Some(path) {
+                out.push((file, i));
+            }
+            if is_source_line(line) {
+                for (_, included) in source.includes.iter().filter(|(at, _)| *at == ordinal) {
+                    self.collect_occurrences(*included, path, out);
+                }
+                ordinal += 1;
+            }
+        }
+    }
+
+    fn collect_lines<'a>(&'a self, file: usize, out: &mut Vec<(&'a Path, &'a Line)>) {
+        let source = &self.files[file];
+        let mut ordinal = 0;
+        for line in source.document.lines() {
+            out.push((source.path.as_path(), line));
+            if is_source_line(line) {
+                for (_, included) in source.includes.iter().filter(|(at, _)| *at == ordinal) {
+                    self.collect_lines(*included, out);
+                }
+                ordinal += 1;
+            }
+        }
+    }
+
+    /// Load one file and its includes, returning its index or `None` on a cycle
+    fn load_file(&mut self, path: &Path, stack: &mut Vec<PathBuf>) -> Result<Option<usize>> {
+        let canonical = fs::canonicalize(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        if stack.contains(&canonical) {
+            warn!("Skipping cyclic source of {}", path.display());
+            return Ok(None);
+        }
+        if let Some(index) = self.files.iter().position(|f| f.path == canonical) {
+            return Ok(Some(index));
+        }
+
+        let content = fs::read_to_string(&canonical)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let document = Document::parse(&content);
+        let index = self.files.len();
+        self.files.push(SourceFile {
+            path: canonical.clone(),
+            document: document.clone(),
+            includes: Vec::new(),
+            loaded: content,
+        });
+
+        stack.push(canonical.clone());
+        let base_dir = canonical.parent().map(Path::to_path_buf).unwrap_or_default();
+        let source_lines = document.lines().iter().filter(|l| is_source_line(l));
+        for (ordinal, line) in source_lines.enumerate() {
+            let LineKind::Assignment(a) = &line.kind else { continue };
+            for included in expand_source_path(&a.value, &base_dir) {
+                debug!("Sourcing {} from {}:{}", included.display(), canonical.display(), line.number);
+                match self.load_file(&included, stack) {
+                    Ok(Some(child)) => self.files[index].includes.push((ordinal, child)),
+                    Ok(None) => {}
+                    Err(e) => warn!("{}:{}: {:#}", canonical.display(), line.number, e),
+                }
+            }
+        }
+        stack.pop();
+
+        Ok(Some(index))
+    }
+}
+
+/// Resolve the value of a `source =` line to the files it names.
+///
+/// Handles `~`, paths relative to the including file and `*`, `?` and `[...]` globs.
+pub fn expand_source_path(value: &str, base_dir: &Path) -> Vec<PathBuf> {
+    let expanded = shellexpand::tilde(value.trim()).to_string();
+    let path = Path::new(&expanded);
+    let path = if path.is_absolute() { path.to_path_buf() } else { base_dir.join(path) };
+
+    if !is_glob(&path.to_string_lossy()) {
+        return vec![path];
+    }
+
+    let mut matches = vec![PathBuf::new()];
+    for component in path.components() {
+        let part = component.as_os_str().to_string_lossy();
+        if !is_glob(&part) {
+            for m in &mut matches {
+                m.push(component);
+            }
+            continue;
+        }
+
+        let Some(pattern) = glob_to_regex(&part) else { return Vec::new() };
+        let mut next = Vec::new();
+        for dir in &matches {
+            let Ok(entries) = fs::read_dir(dir) else { continue };
+            let mut names: Vec<String> = entries
+                .filter_map(|e| e.ok())
+                .map(|e| e.file_name().to_string_lossy().into_owned())
+                .filter(|name| !name.starts_with('.') || part.starts_with('.'))
+                .filter(|name| pattern.is_match(name))
+                .collect();
+            names.sort();
+            next.extend(names.into_iter().map(|name| dir.join(name)));
+        }
+        matches = next;
+    }
+    matches.retain(|p| p.is_file());
+    matches
+}
+
+fn is_source_line(line: &Line) -> bool {
+    line.section.is_empty() && matches!(&line.kind, LineKind::Assignment(a) if a.key == "source")
+}
+
+fn is_glob(s: &str) -> bool {
+    s.contains(['*', '?', '['])
+}
+
+fn glob_to_regex(glob: &str) -> Option<Regex> {
+    let mut pattern = String::from("^");
+    let mut chars = glob.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '*' => pattern.push_str(".*"),
+            '?' => pattern.push('.'),
+            '[' => {
+                pattern.push('[');
+                for c in chars.by_ref() {
+                    match c {
+                        ']' => break,
+                        '!' if pattern.ends_with('[') => pattern.push('^'),
+                        '\\' => pattern.push_str("\\\\"),
+                        c => pattern.push(c),
+                    }
+                }
+                pattern.push(']');
+            }
+            c => pattern.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    pattern.push('$');
+    Regex::new(&pattern).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(dir: &Path, name: &str, content: &str) {
+        let path = dir.join(name);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    #[test]
+    fn sourced_files_are_read_in_place() {
+        let dir = tempfile::tempdir().unwrap();
+        write(dir.path(), "hyprland.conf", "a = 1\nsource = ./conf.d/*.conf\nd = 4\n");
+        write(dir.path(), "conf.d/b.conf", "b = 2\n");
+        write(dir.path(), "conf.d/c.conf", "c = 3\n");
+        write(dir.path(), "conf.d/.hidden.conf", "hidden = 1\n");
+
+        let tree = SourceTree::load(dir.path().join("hyprland.conf")).unwrap();
+        let keys: Vec<String> = tree.lines().iter().filter_map(|(_, line)| line.path()).collect();
+        assert_eq!(keys, ["a", "source", "b", "c", "d"]);
+        assert_eq!(tree.files().len(), 3);
+    }
+
+    #[test]
+    fn cyclic_source_is_skipped() {
+        let dir = tempfile::tempdir().unwrap();
+        write(dir.path(), "hyprland.conf", "source = ./other.conf\na = 1\n");
+        write(dir.path(), "other.conf", "source = ./hyprland.conf\nb = 2\n");
+
+        let tree = SourceTree::load(dir.path().join("hyprland.conf")).unwrap();
+        assert_eq!(tree.files().len(), 2);
+        assert_eq!(tree.get("a"), Some("1"));
+        assert_eq!(tree.get("b"), Some("2"));
+    }
+
+    #[test]
+    fn set_writes_to_the_file_holding_the_value() {
+        let dir = tempfile::tempdir().unwrap();
+        write(dir.path(), "hyprland.conf", "a = 1\nsource = ./other.conf\n");
+        write(dir.path(), "other.conf", "a = 2 # wins\n");
+
+        let mut tree = SourceTree::load(dir.path().join("hyprland.conf")).unwrap();
+        tree.set("a", "3");
+        tree.set("b", "4");
+        tree.save().unwrap();
+        assert_eq!(fs::read_to_string(dir.path().join("other.conf")).unwrap(), "a = 3 # wins\n");
+        assert_eq!(fs::read_to_string(dir.path().join("hyprland.conf")).unwrap(), "a = 1\nsource = ./other.conf\nb = 4\n");
+    }
+
+    #[test]
+    fn replace_all_adds_values_next_to_the_last_one() {
+        let dir = tempfile::tempdir().unwrap();
+        write(dir.path(), "hyprland.conf", "source = ./binds.conf\nexec-once = waybar\n");
+        write(dir.path(), "binds.conf", "bind = SUPER, Q, killactive,\n");
+
+        let mut tree = SourceTree::load(dir.path().join("hyprland.conf")).unwrap();
+        let old = ["SUPER, Q, killactive,".to_string()];
+        tree.replace_all("bind", &old, &["SUPER, Q, killactive,".to_string(), "SUPER, F, fullscreen,".to_string()]);
+        assert_eq!(tree.files()[1].document.to_string(), "bind = SUPER, Q, killactive,\nbind = SUPER, F, fullscreen,\n");
+        assert!(!tree.files()[0].is_modified());
+    }
+
+    #[test]
+    fn glob_matches_bracket_sets() {
+        let dir = tempfile::tempdir().unwrap();
+        for name in ["a1.conf", "a2.conf", "b1.conf"] {
+            write(dir.path(), name, "");
+        }
+        let found = expand_source_path("./[a]?.conf", dir.path());
+        let names: Vec<_> = found.iter().map(|p| p.file_name().unwrap().to_string_lossy().into_owned()).collect();
+        assert_eq!(names, ["a1.conf", "a2.conf"]);
+    }
+}
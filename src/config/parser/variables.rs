@@ -0,0 +1,181 @@
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use indexmap::IndexMap;
+
+use crate::config::models::binds::is_bind_keyword;
+use super::cst::{Line, LineKind};
+use super::source::SourceTree;
+
+/// A place where a variable is defined or referenced
+#[derive(Debug, Clone, PartialEq)]
+pub struct VariableSite {
+    /// Variable name without the leading `$`
+    pub name: String,
+    pub file: PathBuf,
+    /// 1-based line number
+    pub line: usize,
+}
+
+/// `$name` definitions of a config, resolved the way Hyprland does.
+///
+/// Lines are read in order and a reference is expanded with the definitions
+/// seen so far, so `$b = $a-suffix` picks up the value `$a` had at that point.
+#[derive(Debug, Clone, Default)]
+pub struct Variables {
+    /// Values with references expanded
+    resolved: IndexMap<String, String>,
+    definitions: Vec<VariableSite>,
+    used: HashSet<String>,
+    undefined: Vec<VariableSite>,
+}
+
+impl Variables {
+    /// Collect the variables of a loaded config, across all sourced files
+    pub fn from_sources(sources: &SourceTree) -> Self {
+        Self::from_lines(sources.lines())
+    }
+
+    /// Collect variables from lines in the order Hyprland reads them
+    pub fn from_lines<'a>(lines: impl IntoIterator<Item = (&'a Path, &'a Line)>) -> Self {
+        let mut vars = Self::default();
+        for (file, line) in lines {
+            let LineKind::Assignment(a) = &line.kind else { continue };
+            let site = |name: &str| VariableSite {
+                name: name.to_string(),
+                file: file.to_path_buf(),
+                line: line.number,
+            };
+
+            let (expanded, unresolved) = vars.expand(&a.value);
+            // Unknown `$NAME`s in commands are usually meant for the shell
+            if !runs_command(&a.key, &a.value) {
+                vars.undefined.extend(unresolved.iter().map(|name| site(name)));
+            }
+
+            if let Some(name) = a.key.strip_prefix('$').filter(|_| line.section.is_empty()) {
+                vars.definitions.push(site(name));
+                vars.resolved.insert(name.to_string(), expanded);
+            }
+        }
+        vars
+    }
+
+    /// All variables with their resolved values, in definition order
+    pub fn resolved(&self) -> &IndexMap<String, String> {
+        &self.resolved
+    }
+
+    /// Expand every `$name` in `value` using the final definitions
+    pub fn resolve(&self, value: &str) -> String {
+        expand_with(&self.resolved, value).0
+    }
+
+    /// References to variables that were not defined at that point
+    pub fn undefined(&self) -> &[VariableSite] {
+        &self.undefined
+    }
+
+    /// Definitions that no option or other variable refers to
+    pub fn unused(&self) -> Vec<&VariableSite> {
+        self.definitions.iter().filter(|d| !self.used.contains(&d.name)).collect()
+    }
+
+    /// Expand `value` with the definitions seen so far, marking them used
+    fn expand(&mut self, value: &str) -> (String, Vec<String>) {
+        let (expanded, used, unresolved) = expand_with_usage(&self.resolved, value);
+        self.used.extend(used);
+        (expanded, unresolved)
+    }
+}
+
+/// Expand `$name` references against `vars`, returning the result and any unresolved names
+pub fn expand_with(vars: &IndexMap<String, String>, value: &str) -> (String, Vec<String>) {
+    let (expanded, _, unresolved) = expand_with_usage(vars, value);
+    (expanded, unresolved)
+}
+
+fn expand_with_usage(vars: &IndexMap<String, String>, value: &str) -> (String, Vec<String>, Vec<String>) {
+    let mut out = String::with_capacity(value.len());
+    let mut used = Vec::new();
+    let mut unresolved = Vec::new();
+    let mut rest = value;
+
+    while let Some(dollar) = rest.find('$') {
+        out.push_str(&rest[..dollar]);
+        let after = &rest[dollar + 1..];
+        let ident_len = after
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+            .unwrap_or(after.len());
+        let ident = &after[..ident_len];
+
+        // Like Hyprland, the longest defined name wins, so `$modShift` beats `$mod`
+        let name = (1..=ident.len()).rev().map(|len| &ident[..len]).find(|name| vars.contains_key(*name));
+        match name {
+            Some(name) => {
+                out.push_str(&vars[name]);
+                used.push(name.to_string());
+                rest = &after[name.len()..];
+            }
+            None => {
+                out.push('$');
+                if !ident.is_empty() {
+                    unresolved.push(ident.to_string());
+                }
+                rest = after;
+            }
+        }
+    }
+    out.push_str(rest);
+    (out, used, unresolved)
+}
+
+/// Whether a line hands its value to a shell, where `$NAME` is an environment variable
+fn runs_command(key: &str, value: &str) -> bool {
+    key.starts_with("exec")
+        || (is_bind_keyword(key) && value.split(',').any(|part| part.trim() == "exec"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONFIG: &str = "\
+$mainMod = SUPER
+$mainModShift = $mainMod SHIFT
+$terminal = kitty
+$unused = 1
+general {
+    gaps_in = $gap
+}
+$gap = 5
+bind = $mainModShift, Q, exec, $terminal $HOME
+";
+
+    fn variables(content: &str) -> Variables {
+        Variables::from_sources(&SourceTree::from_string(content))
+    }
+
+    #[test]
+    fn definitions_expand_what_came_before() {
+        let vars = variables(CONFIG);
+        assert_eq!(vars.resolved()["mainModShift"], "SUPER SHIFT");
+        assert_eq!(vars.resolve("$terminal --title $gap"), "kitty --title 5");
+    }
+
+    #[test]
+    fn reports_references_before_definition_and_unused_definitions() {
+        let vars = variables(CONFIG);
+        let undefined: Vec<(&str, usize)> = vars.undefined().iter().map(|s| (s.name.as_str(), s.line)).collect();
+        // `$HOME` is left to the shell that runs the command
+        assert_eq!(undefined, [("gap", 6)]);
+        let unused: Vec<&str> = vars.unused().iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(unused, ["unused", "gap"]);
+    }
+
+    #[test]
+    fn expand_with_returns_unresolved_names() {
+        let vars: IndexMap<String, String> = [("a".to_string(), "1".to_string())].into_iter().collect();
+        assert_eq!(expand_with(&vars, "$a-$b"), ("1-$b".to_string(), vec!["b".to_string()]));
+    }
+}
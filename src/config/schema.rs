@@ -0,0 +1,658 @@
+//! Every Hyprland option hyprconf knows about, with its type and default.
+//!
+//! The parser takes its defaults from here, and `validate` checks values
+//! against the declared types, ranges and versions.
+
+use crate::config::parser::cst::{Document, LineKind};
+use crate::config::utils::{Color, CommentStyle, Gradient, parse_bool_value, parse_value};
+
+/// What kind of value an option takes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OptionType {
+    Bool,
+    Int,
+    Float,
+    String,
+    Color,
+    Gradient,
+    /// Two numbers separated by a space, e.g. `0 0`
+    Vec2,
+    /// One to four integers like CSS margins, e.g. `5 10`
+    Gaps,
+    /// One of a fixed set of words
+    Enum(&'static [&'static str]),
+}
+
+/// Description of a single option
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OptionSchema {
+    /// Full colon path, e.g. `decoration:blur:size`
+    pub path: &'static str,
+    pub ty: OptionType,
+    /// Default value as it would be written in the config
+    pub default: &'static str,
+    /// Inclusive bounds for numeric options
+    pub range: Option<(f64, f64)>,
+    pub description: &'static str,
+    /// Hyprland version that introduced the option, when known
+    pub since: Option<&'static str>,
+    /// Hyprland version that dropped the option
+    pub removed: Option<&'static str>,
+}
+
+impl OptionSchema {
+    const fn new(path: &'static str, ty: OptionType, default: &'static str, description: &'static str) -> Self {
+        Self { path, ty, default, range: None, description, since: None, removed: None }
+    }
+
+    const fn range(mut self, min: f64, max: f64) -> Self {
+        self.range = Some((min, max));
+        self
+    }
+
+    /// Lower bound for numeric options that have no upper one
+    const fn min(mut self, min: f64) -> Self {
+        self.range = Some((min, f64::INFINITY));
+        self
+    }
+
+    const fn since(mut self, version: &'static str) -> Self {
+        self.since = Some(version);
+        self
+    }
+
+    const fn removed(mut self, version: &'static str) -> Self {
+        self.removed = Some(version);
+        self
+    }
+
+    /// Section part of the path, e.g. `decoration:blur`
+    pub fn section(&self) -> &'static str {
+        self.path.rsplit_once(':').map_or("", |(section, _)| section)
+    }
+
+    /// Last part of the path, e.g. `size`
+    pub fn key(&self) -> &'static str {
+        self.path.rsplit_once(':').map_or(self.path, |(_, key)| key)
+    }
+
+    pub fn is_removed(&self) -> bool {
+        self.removed.is_some()
+    }
+
+    /// Whether `value` means the same as the option's default, e.g. `yes` for `true`
+    pub fn is_default(&self, value: &str) -> bool {
+        let (value, default) = (value.trim(), self.default);
+        match self.ty {
+            OptionType::Bool => match (parse_bool_value("", value), parse_bool_value("", default)) {
+                (Ok(a), Ok(b)) => a == b,
+                _ => false,
+            },
+            OptionType::Int | OptionType::Float | OptionType::Vec2 => same_numbers(numbers(value), numbers(default)),
+            OptionType::Gaps => same_numbers(numbers(value).map(expand_gaps), numbers(default).map(expand_gaps)),
+            OptionType::Color => matches!((value.parse::<Color>(), default.parse::<Color>()), (Ok(a), Ok(b)) if a == b),
+            OptionType::Gradient => matches!((value.parse::<Gradient>(), default.parse::<Gradient>()), (Ok(a), Ok(b)) if a == b),
+            OptionType::String | OptionType::Enum(_) => value == default,
+        }
+    }
+
+    /// Check a value against the option's type, range and allowed words
+    pub fn check(&self, value: &str) -> Result<(), String> {
+        let key = self.key();
+        let value = value.trim();
+        let number = match self.ty {
+            OptionType::Bool => return parse_bool_value(key, value).map(drop).map_err(|e| e.to_string()),
+            OptionType::Int => parse_value::<i64>(key, value).map_err(|e| e.to_string())? as f64,
+            OptionType::Float => parse_value::<f64>(key, value).map_err(|e| e.to_string())?,
+            OptionType::String => return Ok(()),
+            OptionType::Color => return parse_value::<Color>(key, value).map(drop).map_err(|e| e.to_string()),
+            OptionType::Gradient => return parse_value::<Gradient>(key, value).map(drop).map_err(|e| e.to_string()),
+            OptionType::Vec2 => {
+                let parts: Vec<&str> = value.split_whitespace().collect();
+                if parts.len() != 2 || parts.iter().any(|p| p.parse::<f64>().is_err()) {
+                    return Err(format!("invalid value `{}` for `{}`: expected two numbers", value, key));
+                }
+                return Ok(());
+            }
+            OptionType::Gaps => {
+                let parts: Vec<&str> = value.split_whitespace().collect();
+                if parts.is_empty() || parts.len() > 4 || parts.iter().any(|p| p.parse::<i64>().is_err()) {
+                    return Err(format!("invalid value `{}` for `{}`: expected one to four integers", value, key));
+                }
+                return Ok(());
+            }
+            OptionType::Enum(words) => {
+                if words.contains(&value) {
+                    return Ok(());
+                }
+                return Err(format!("invalid value `{}` for `{}`: expected one of {}", value, key, words.join(", ")));
+            }
+        };
+
+        match self.range {
+            Some((min, max)) if max == f64::INFINITY && number < min => {
+                Err(format!("`{}` must be at least {}, got {}", key, min, value))
+            }
+            Some((min, max)) if number < min || number > max => {
+                Err(format!("`{}` must be between {} and {}, got {}", key, min, max, value))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// All known options
+pub fn options() -> &'static [OptionSchema] {
+    OPTIONS
+}
+
+/// Look up an option by its full colon path
+pub fn lookup(path: &str) -> Option<&'static OptionSchema> {
+    OPTIONS.iter().find(|option| option.path == path)
+}
+
+/// Check a value about to be set for `path`, rejecting removed options and typos
+/// in sections the schema covers. Other paths, like devices and plugins, are left
+/// to the parser.
+pub fn check_option(path: &str, value: &str) -> Result<(), String> {
+    match lookup(path) {
+        Some(option) => match option.removed {
+            Some(version) => Err(format!("`{}` was removed in Hyprland {}, see `hyprconf migrate`", path, version)),
+            None => option.check(value),
+        },
+        None => {
+            let section = path.split(':').next().unwrap_or_default();
+            if path.contains(':') && OPTIONS.iter().any(|option| option.path.split(':').next() == Some(section)) {
+                Err(format!("unknown option `{}`", path))
+            } else {
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Drop options that are set to their Hyprland default, and any blocks left empty
+pub fn strip_defaults(document: &Document) -> Document {
+    let lines = document.lines();
+    let mut keep = vec![true; lines.len()];
+    // Open blocks as (line index, whether anything inside is kept)
+    let mut open: Vec<(usize, bool)> = Vec::new();
+
+    for (i, line) in lines.iter().enumerate() {
+        match &line.kind {
+            LineKind::BlockOpen(_) => open.push((i, false)),
+            LineKind::BlockClose => match open.pop() {
+                Some((start, false)) => keep[start..=i].iter_mut().for_each(|k| *k = false),
+                Some(_) => {
+                    if let Some(parent) = open.last_mut() {
+                        parent.1 = true;
+                    }
+                }
+                None => {}
+            },
+            LineKind::Assignment(a) => {
+                let is_default = line.path()
+                    .and_then(|path| lookup(&path))
+                    .is_some_and(|option| option.is_default(&a.value));
+                if is_default {
+                    keep[i] = false;
+                } else if let Some(block) = open.last_mut() {
+                    block.1 = true;
+                }
+            }
+            LineKind::Blank | LineKind::Comment(_) => {}
+            LineKind::Unknown => {
+                if let Some(block) = open.last_mut() {
+                    block.1 = true;
+                }
+            }
+        }
+    }
+
+    let mut raw_lines: Vec<&str> = Vec::new();
+    for (line, _) in lines.iter().zip(&keep).filter(|(_, keep)| **keep) {
+        // Removed blocks leave their separating blank lines behind
+        let blank = line.kind == LineKind::Blank;
+        if blank && raw_lines.last().is_none_or(|last| last.trim().is_empty()) {
+            continue;
+        }
+        raw_lines.push(&line.raw);
+    }
+    while raw_lines.last().is_some_and(|last| last.trim().is_empty()) {
+        raw_lines.pop();
+    }
+
+    let mut content = raw_lines.join("\n");
+    if !content.is_empty() {
+        content.push('\n');
+    }
+    Document::parse(&content)
+}
+
+/// Put the description and default of every known option in a comment above it
+pub fn annotate(document: &Document, comment_style: &CommentStyle) -> Document {
+    let mut raw_lines = Vec::with_capacity(document.lines().len() * 2);
+    for line in document.lines() {
+        let option = line.path().and_then(|path| lookup(&path)).filter(|option| !option.is_removed());
+        if let Some(option) = option {
+            let default = if option.default.is_empty() { "empty" } else { option.default };
+            raw_lines.push(format!(
+                "{}{} {} (default: {})",
+                line.indent(), comment_style.prefix(), option.description, default
+            ));
+        }
+        raw_lines.push(line.raw.clone());
+    }
+
+    let mut content = raw_lines.join("\n");
+    content.push('\n');
+    Document::parse(&content)
+}
+
+fn numbers(value: &str) -> Option<Vec<f64>> {
+    value.split_whitespace().map(|part| part.parse().ok()).collect()
+}
+
+/// Gaps given as one to three values apply to all four sides like CSS margins
+fn expand_gaps(values: Vec<f64>) -> Vec<f64> {
+    match values.as_slice() {
+        [all] => vec![*all; 4],
+        [vertical, horizontal] => vec![*vertical, *horizontal, *vertical, *horizontal],
+        [top, horizontal, bottom] => vec![*top, *horizontal, *bottom, *horizontal],
+        _ => values,
+    }
+}
+
+/// Compare numbers loosely enough that `0.0117` read back from an `f32` still matches
+fn same_numbers(a: Option<Vec<f64>>, b: Option<Vec<f64>>) -> bool {
+    match (a, b) {
+        (Some(a), Some(b)) => a.len() == b.len()
+            && a.iter().zip(&b).all(|(x, y)| (x - y).abs() <= 1e-6 * y.abs().max(1.0)),
+        _ => false,
+    }
+}
+
+use table::OPTIONS;
+
+// Kept in its own module so the `OptionType` variants can be used unqualified
+mod table {
+    use super::OptionSchema;
+    use super::OptionType::*;
+
+    pub(super) const OPTIONS: &[OptionSchema] = &[
+        // general
+        OptionSchema::new("general:border_size", Int, "1", "Size of the border around windows").min(0.0),
+        OptionSchema::new("general:no_border_on_floating", Bool, "false", "Disable borders for floating windows"),
+        OptionSchema::new("general:gaps_in", Gaps, "5", "Gaps between windows"),
+        OptionSchema::new("general:gaps_out", Gaps, "20", "Gaps between windows and monitor edges"),
+        OptionSchema::new("general:gaps_workspaces", Int, "0", "Gaps between workspaces, stacked with gaps_out").min(0.0),
+        OptionSchema::new("general:col.inactive_border", Gradient, "rgba(444444ff)", "Border color for inactive windows"),
+        OptionSchema::new("general:col.active_border", Gradient, "rgba(ffffffff)", "Border color for the active window"),
+        OptionSchema::new("general:col.nogroup_border", Gradient, "rgba(ffaaffff)", "Inactive border color for windows that cannot be added to a group"),
+        OptionSchema::new("general:col.nogroup_border_active", Gradient, "rgba(ff00ffff)", "Active border color for windows that cannot be added to a group"),
+        OptionSchema::new("general:layout", String, "dwindle", "Layout to use, `dwindle`, `master` or one added by a plugin"),
+        OptionSchema::new("general:no_focus_fallback", Bool, "false", "Do not fall back to the next window when moving focus in a direction with none"),
+        OptionSchema::new("general:resize_on_border", Bool, "false", "Resize windows by dragging their borders and gaps"),
+        OptionSchema::new("general:extend_border_grab_area", Int, "15", "Extra area around the border that can be grabbed for resizing"),
+        OptionSchema::new("general:hover_icon_on_border", Bool, "true", "Show a cursor icon when hovering over borders"),
+        OptionSchema::new("general:allow_tearing", Bool, "false", "Master switch for allowing tearing"),
+        OptionSchema::new("general:resize_corner", Int, "0", "Force floating windows to resize from one corner, 1-4 clockwise from top left").range(0.0, 4.0),
+        OptionSchema::new("general:cursor_inactive_timeout", Int, "0", "Moved to cursor:inactive_timeout").removed("0.37.0"),
+        OptionSchema::new("general:no_cursor_warps", Bool, "false", "Moved to cursor:no_warps").removed("0.37.0"),
+        OptionSchema::new("general:snap:enabled", Bool, "false", "Snap floating windows to each other and to monitor edges"),
+        OptionSchema::new("general:snap:window_gap", Int, "10", "Distance at which windows snap to each other"),
+        OptionSchema::new("general:snap:monitor_gap", Int, "10", "Distance at which windows snap to monitor edges"),
+        OptionSchema::new("general:snap:border_overlap", Bool, "false", "Snap with one border's width of overlap"),
+        // decoration
+        OptionSchema::new("decoration:rounding", Int, "0", "Radius of rounded window corners").min(0.0),
+        OptionSchema::new("decoration:rounding_power", Float, "2.0", "Curvature of rounded corners, 2 is a circle").range(2.0, 10.0).since("0.47.0"),
+        OptionSchema::new("decoration:active_opacity", Float, "1.0", "Opacity of the active window").range(0.0, 1.0),
+        OptionSchema::new("decoration:inactive_opacity", Float, "1.0", "Opacity of inactive windows").range(0.0, 1.0),
+        OptionSchema::new("decoration:fullscreen_opacity", Float, "1.0", "Opacity of fullscreen windows").range(0.0, 1.0),
+        OptionSchema::new("decoration:dim_inactive", Bool, "false", "Dim inactive windows"),
+        OptionSchema::new("decoration:dim_strength", Float, "0.5", "How much inactive windows are dimmed").range(0.0, 1.0),
+        OptionSchema::new("decoration:dim_special", Float, "0.2", "How much the rest of the screen is dimmed behind a special workspace").range(0.0, 1.0),
+        OptionSchema::new("decoration:dim_around", Float, "0.4", "How much the dimaround window rule dims").range(0.0, 1.0),
+        OptionSchema::new("decoration:screen_shader", String, "", "Path to a fragment shader applied to the whole screen"),
+        OptionSchema::new("decoration:border_part_of_window", Bool, "true", "Treat the border as part of the window"),
+        OptionSchema::new("decoration:drop_shadow", Bool, "true", "Moved to decoration:shadow:enabled").removed("0.45.0"),
+        OptionSchema::new("decoration:shadow_range", Int, "4", "Moved to decoration:shadow:range").removed("0.45.0"),
+        OptionSchema::new("decoration:shadow_render_power", Int, "3", "Moved to decoration:shadow:render_power").removed("0.45.0"),
+        OptionSchema::new("decoration:shadow_ignore_window", Bool, "true", "Moved to decoration:shadow:ignore_window").removed("0.45.0"),
+        OptionSchema::new("decoration:shadow_offset", Vec2, "0 0", "Moved to decoration:shadow:offset").removed("0.45.0"),
+        OptionSchema::new("decoration:shadow_scale", Float, "1.0", "Moved to decoration:shadow:scale").removed("0.45.0"),
+        OptionSchema::new("decoration:col.shadow", Color, "rgba(1a1a1aee)", "Moved to decoration:shadow:color").removed("0.45.0"),
+        OptionSchema::new("decoration:col.shadow_inactive", Color, "rgba(1a1a1aee)", "Moved to decoration:shadow:color_inactive").removed("0.45.0"),
+        OptionSchema::new("decoration:blur:enabled", Bool, "true", "Enable background blur"),
+        OptionSchema::new("decoration:blur:size", Int, "8", "Blur distance").min(0.0),
+        OptionSchema::new("decoration:blur:passes", Int, "1", "Number of blur passes").range(0.0, 10.0),
+        OptionSchema::new("decoration:blur:ignore_opacity", Bool, "true", "Blur behind windows regardless of their opacity"),
+        OptionSchema::new("decoration:blur:new_optimizations", Bool, "true", "Enable further blur optimizations"),
+        OptionSchema::new("decoration:blur:xray", Bool, "false", "Floating windows ignore tiled windows in their blur"),
+        OptionSchema::new("decoration:blur:noise", Float, "0.0117", "Amount of noise added to the blur").range(0.0, 1.0),
+        OptionSchema::new("decoration:blur:contrast", Float, "0.8916", "Contrast modulation for the blur").range(0.0, 2.0),
+        OptionSchema::new("decoration:blur:brightness", Float, "0.8172", "Brightness modulation for the blur").range(0.0, 2.0),
+        OptionSchema::new("decoration:blur:vibrancy", Float, "0.1696", "Increase saturation of blurred colors").range(0.0, 1.0),
+        OptionSchema::new("decoration:blur:vibrancy_darkness", Float, "0.0", "How strongly vibrancy affects dark areas").range(0.0, 1.0),
+        OptionSchema::new("decoration:blur:special", Bool, "false", "Blur behind the special workspace"),
+        OptionSchema::new("decoration:blur:popups", Bool, "false", "Blur popups such as right-click menus"),
+        OptionSchema::new("decoration:blur:popups_ignorealpha", Float, "0.2", "Do not blur popup pixels with lower opacity than this").range(0.0, 1.0),
+        OptionSchema::new("decoration:blur:input_methods", Bool, "false", "Blur input method popups"),
+        OptionSchema::new("decoration:blur:input_methods_ignorealpha", Float, "0.2", "Do not blur input method pixels with lower opacity than this").range(0.0, 1.0),
+        OptionSchema::new("decoration:shadow:enabled", Bool, "true", "Draw shadows for windows").since("0.45.0"),
+        OptionSchema::new("decoration:shadow:range", Int, "4", "Shadow range in layout pixels").min(0.0).since("0.45.0"),
+        OptionSchema::new("decoration:shadow:render_power", Int, "3", "Falloff power of the shadow").range(1.0, 4.0).since("0.45.0"),
+        OptionSchema::new("decoration:shadow:sharp", Bool, "false", "Draw a sharp shadow without falloff").since("0.45.0"),
+        OptionSchema::new("decoration:shadow:ignore_window", Bool, "true", "Only draw the shadow outside the window").since("0.45.0"),
+        OptionSchema::new("decoration:shadow:color", Color, "rgba(1a1a1aee)", "Shadow color").since("0.45.0"),
+        OptionSchema::new("decoration:shadow:color_inactive", Color, "rgba(1a1a1aee)", "Shadow color for inactive windows").since("0.45.0"),
+        OptionSchema::new("decoration:shadow:offset", Vec2, "0 0", "Shadow offset").since("0.45.0"),
+        OptionSchema::new("decoration:shadow:scale", Float, "1.0", "Shadow scale").range(0.0, 1.0).since("0.45.0"),
+        // animations
+        OptionSchema::new("animations:enabled", Bool, "true", "Enable animations"),
+        OptionSchema::new("animations:first_launch_animation", Bool, "true", "Fade in on first launch"),
+        // input
+        OptionSchema::new("input:kb_model", String, "", "XKB keymap model"),
+        OptionSchema::new("input:kb_layout", String, "us", "XKB keymap layout"),
+        OptionSchema::new("input:kb_variant", String, "", "XKB keymap variant"),
+        OptionSchema::new("input:kb_options", String, "", "XKB keymap options"),
+        OptionSchema::new("input:kb_rules", String, "", "XKB keymap rules"),
+        OptionSchema::new("input:kb_file", String, "", "Path to a custom .xkb keymap file"),
+        OptionSchema::new("input:numlock_by_default", Bool, "false", "Engage numlock by default"),
+        OptionSchema::new("input:resolve_binds_by_sym", Bool, "false", "Match binds by symbol rather than by key in the current layout"),
+        OptionSchema::new("input:repeat_rate", Int, "25", "Repeat rate for held keys, in repeats per second").min(0.0),
+        OptionSchema::new("input:repeat_delay", Int, "600", "Delay before a held key starts repeating, in milliseconds").min(0.0),
+        OptionSchema::new("input:sensitivity", Float, "0.0", "Mouse sensitivity").range(-1.0, 1.0),
+        OptionSchema::new("input:accel_profile", String, "", "Cursor acceleration profile: adaptive, flat or custom"),
+        OptionSchema::new("input:force_no_accel", Bool, "false", "Force no cursor acceleration"),
+        OptionSchema::new("input:left_handed", Bool, "false", "Swap the left and right mouse buttons"),
+        OptionSchema::new("input:scroll_points", String, "", "Acceleration points for the custom scroll profile"),
+        OptionSchema::new("input:scroll_method", Enum(&["", "2fg", "edge", "on_button_down", "no_scroll"]), "", "Scroll method"),
+        OptionSchema::new("input:scroll_button", Int, "0", "Button used for on_button_down scrolling"),
+        OptionSchema::new("input:scroll_button_lock", Bool, "false", "Do not need to hold the scroll button"),
+        OptionSchema::new("input:scroll_factor", Float, "1.0", "Multiplier applied to scroll movement").min(0.0),
+        OptionSchema::new("input:natural_scroll", Bool, "false", "Invert scrolling direction for mice"),
+        OptionSchema::new("input:follow_mouse", Int, "1", "How the cursor movement affects window focus").range(0.0, 3.0),
+        OptionSchema::new("input:follow_mouse_threshold", Float, "0.0", "Smallest cursor move that changes focus"),
+        OptionSchema::new("input:focus_on_close", Int, "0", "Focus the next window (0) or the one under the cursor (1) on close").range(0.0, 1.0),
+        OptionSchema::new("input:mouse_refocus", Bool, "true", "Refocus the window under the cursor when crossing window borders"),
+        OptionSchema::new("input:float_switch_override_focus", Int, "1", "Change focus when moving between floating and tiled windows").range(0.0, 2.0),
+        OptionSchema::new("input:special_fallthrough", Bool, "false", "Focus windows below an empty special workspace"),
+        OptionSchema::new("input:off_window_axis_events", Int, "1", "How scroll events outside a window are handled").range(0.0, 3.0),
+        OptionSchema::new("input:emulate_discrete_scroll", Int, "1", "Emulate discrete scrolling from high resolution scroll events").range(0.0, 2.0),
+        OptionSchema::new("input:drag_threshold", Int, "0", "Distance in pixels a click must move before it is treated as a drag"),
+        OptionSchema::new("input:touchpad:disable_while_typing", Bool, "true", "Disable the touchpad while typing"),
+        OptionSchema::new("input:touchpad:natural_scroll", Bool, "false", "Invert scrolling direction for touchpads"),
+        OptionSchema::new("input:touchpad:scroll_factor", Float, "1.0", "Multiplier applied to touchpad scrolling").min(0.0),
+        OptionSchema::new("input:touchpad:middle_button_emulation", Bool, "false", "Pressing left and right together emits a middle click"),
+        OptionSchema::new("input:touchpad:tap_button_map", Enum(&["", "lrm", "lmr"]), "", "Buttons emitted by one, two and three finger taps"),
+        OptionSchema::new("input:touchpad:clickfinger_behavior", Bool, "false", "Pick the button from the number of fingers on a click"),
+        OptionSchema::new("input:touchpad:tap-to-click", Bool, "true", "Tapping emits a click"),
+        OptionSchema::new("input:touchpad:drag_lock", Bool, "false", "Keep dragging for a short time after lifting the finger"),
+        OptionSchema::new("input:touchpad:tap-and-drag", Bool, "false", "Tap and hold to drag"),
+        OptionSchema::new("input:touchpad:flip_x", Bool, "false", "Invert horizontal movement"),
+        OptionSchema::new("input:touchpad:flip_y", Bool, "false", "Invert vertical movement"),
+        OptionSchema::new("input:touchdevice:transform", Int, "-1", "Transform of touch input, -1 to follow the monitor").range(-1.0, 7.0),
+        OptionSchema::new("input:touchdevice:output", String, "[[Auto]]", "Monitor touch input is bound to"),
+        OptionSchema::new("input:touchdevice:enabled", Bool, "true", "Enable touch devices"),
+        OptionSchema::new("input:tablet:transform", Int, "-1", "Transform of tablet input, -1 to follow the monitor").range(-1.0, 7.0),
+        OptionSchema::new("input:tablet:output", String, "", "Monitor tablet input is bound to"),
+        OptionSchema::new("input:tablet:region_position", Vec2, "0 0", "Position of the mapped region in monitor layout"),
+        OptionSchema::new("input:tablet:absolute_region_position", Bool, "false", "Treat region_position as an absolute position"),
+        OptionSchema::new("input:tablet:region_size", Vec2, "0 0", "Size of the mapped region"),
+        OptionSchema::new("input:tablet:relative_input", Bool, "false", "Move the cursor relatively instead of absolutely"),
+        OptionSchema::new("input:tablet:left_handed", Bool, "false", "Rotate tablet input by 180 degrees"),
+        OptionSchema::new("input:tablet:active_area_size", Vec2, "0 0", "Size of the tablet's active area in millimeters"),
+        OptionSchema::new("input:tablet:active_area_position", Vec2, "0 0", "Position of the tablet's active area in millimeters"),
+        // gestures
+        OptionSchema::new("gestures:workspace_swipe", Bool, "false", "Swipe between workspaces, replaced by gesture lines").removed("0.51.0"),
+        OptionSchema::new("gestures:workspace_swipe_fingers", Int, "3", "Fingers used for swiping, replaced by gesture lines").removed("0.51.0"),
+        OptionSchema::new("gestures:workspace_swipe_min_fingers", Bool, "false", "Allow more fingers than configured, replaced by gesture lines").removed("0.51.0"),
+        OptionSchema::new("gestures:workspace_swipe_distance", Int, "300", "Swipe distance in pixels for a full workspace"),
+        OptionSchema::new("gestures:workspace_swipe_touch", Bool, "false", "Swipe workspaces with three fingers on a touchscreen"),
+        OptionSchema::new("gestures:workspace_swipe_invert", Bool, "true", "Invert the touchpad swipe direction"),
+        OptionSchema::new("gestures:workspace_swipe_touch_invert", Bool, "false", "Invert the touchscreen swipe direction"),
+        OptionSchema::new("gestures:workspace_swipe_min_speed_to_force", Int, "30", "Swipe speed that switches workspace regardless of distance"),
+        OptionSchema::new("gestures:workspace_swipe_cancel_ratio", Float, "0.5", "Fraction of a swipe needed to switch workspace").range(0.0, 1.0),
+        OptionSchema::new("gestures:workspace_swipe_create_new", Bool, "true", "Swiping right past the last workspace creates a new one"),
+        OptionSchema::new("gestures:workspace_swipe_direction_lock", Bool, "true", "Lock the swipe direction once it exceeds the threshold"),
+        OptionSchema::new("gestures:workspace_swipe_direction_lock_threshold", Int, "10", "Distance in pixels before the direction locks"),
+        OptionSchema::new("gestures:workspace_swipe_forever", Bool, "false", "Keep swiping past neighbouring workspaces"),
+        OptionSchema::new("gestures:workspace_swipe_use_r", Bool, "false", "Use the r prefix instead of m when finding the next workspace"),
+        // group
+        OptionSchema::new("group:auto_group", Bool, "true", "New windows join the focused group"),
+        OptionSchema::new("group:insert_after_current", Bool, "true", "New windows are added after the current one instead of at the end"),
+        OptionSchema::new("group:focus_removed_window", Bool, "true", "Focus a window when it is removed from its group"),
+        OptionSchema::new("group:drag_into_group", Int, "1", "Whether dragging a window onto a group adds it").range(0.0, 2.0),
+        OptionSchema::new("group:merge_groups_on_drag", Bool, "true", "Dragging a group onto another merges them"),
+        OptionSchema::new("group:merge_groups_on_groupbar", Bool, "true", "Dropping a group on a groupbar merges them"),
+        OptionSchema::new("group:merge_floated_into_tiled_on_groupbar", Bool, "false", "Floating windows dropped on a tiled groupbar join the group"),
+        OptionSchema::new("group:group_on_movetoworkspace", Bool, "false", "Moving a window to a workspace with a group adds it to the group"),
+        OptionSchema::new("group:col.border_active", Gradient, "rgba(ffff0066)", "Border color of the active group"),
+        OptionSchema::new("group:col.border_inactive", Gradient, "rgba(77770066)", "Border color of inactive groups"),
+        OptionSchema::new("group:col.border_locked_active", Gradient, "rgba(ff550066)", "Border color of the active locked group"),
+        OptionSchema::new("group:col.border_locked_inactive", Gradient, "rgba(77550066)", "Border color of inactive locked groups"),
+        OptionSchema::new("group:groupbar:enabled", Bool, "true", "Show the groupbar"),
+        OptionSchema::new("group:groupbar:font_family", String, "", "Font of groupbar titles, misc:font_family when empty"),
+        OptionSchema::new("group:groupbar:font_size", Int, "8", "Font size of groupbar titles"),
+        OptionSchema::new("group:groupbar:gradients", Bool, "false", "Draw gradients behind groupbar titles"),
+        OptionSchema::new("group:groupbar:height", Int, "14", "Height of the groupbar"),
+        OptionSchema::new("group:groupbar:indicator_height", Int, "3", "Height of the groupbar indicator"),
+        OptionSchema::new("group:groupbar:stacked", Bool, "false", "Stack the groupbar vertically"),
+        OptionSchema::new("group:groupbar:priority", Int, "3", "Decoration priority of the groupbar"),
+        OptionSchema::new("group:groupbar:render_titles", Bool, "true", "Render window titles in the groupbar"),
+        OptionSchema::new("group:groupbar:text_offset", Int, "0", "Vertical offset of groupbar titles"),
+        OptionSchema::new("group:groupbar:scrolling", Bool, "true", "Scrolling over the groupbar changes the active window"),
+        OptionSchema::new("group:groupbar:rounding", Int, "1", "Rounding of the groupbar indicator").min(0.0),
+        OptionSchema::new("group:groupbar:gradient_rounding", Int, "2", "Rounding of groupbar gradients").min(0.0),
+        OptionSchema::new("group:groupbar:round_only_edges", Bool, "true", "Round only the outer ends of the indicator"),
+        OptionSchema::new("group:groupbar:gradient_round_only_edges", Bool, "true", "Round only the outer ends of the gradients"),
+        OptionSchema::new("group:groupbar:text_color", Color, "rgba(ffffffff)", "Color of groupbar titles"),
+        OptionSchema::new("group:groupbar:col.active", Gradient, "rgba(ffff0066)", "Groupbar color of the active window"),
+        OptionSchema::new("group:groupbar:col.inactive", Gradient, "rgba(77770066)", "Groupbar color of inactive windows"),
+        OptionSchema::new("group:groupbar:col.locked_active", Gradient, "rgba(ff550066)", "Groupbar color of the active window in a locked group"),
+        OptionSchema::new("group:groupbar:col.locked_inactive", Gradient, "rgba(77550066)", "Groupbar color of inactive windows in a locked group"),
+        OptionSchema::new("group:groupbar:gaps_in", Int, "2", "Gap between groupbar indicators"),
+        OptionSchema::new("group:groupbar:gaps_out", Int, "2", "Gap between the groupbar and the window"),
+        OptionSchema::new("group:groupbar:keep_upper_gap", Bool, "true", "Keep the gap above the groupbar"),
+        // misc
+        OptionSchema::new("misc:disable_hyprland_logo", Bool, "false", "Disable the random Hyprland logo and anime girl wallpaper"),
+        OptionSchema::new("misc:disable_splash_rendering", Bool, "false", "Disable the splash text under the logo"),
+        OptionSchema::new("misc:col.splash", Color, "rgba(ffffffff)", "Color of the splash text"),
+        OptionSchema::new("misc:font_family", String, "Sans", "Font used for the splash and other built-in text"),
+        OptionSchema::new("misc:splash_font_family", String, "", "Font of the splash text, font_family when empty"),
+        OptionSchema::new("misc:force_default_wallpaper", Int, "-1", "Force one of the default wallpapers, -1 for random").range(-1.0, 2.0),
+        OptionSchema::new("misc:vfr", Bool, "true", "Render only when something changed"),
+        OptionSchema::new("misc:vrr", Int, "0", "Adaptive sync: 0 off, 1 on, 2 fullscreen only, 3 fullscreen games and videos").range(0.0, 3.0),
+        OptionSchema::new("misc:mouse_move_enables_dpms", Bool, "false", "Moving the mouse turns monitors back on"),
+        OptionSchema::new("misc:key_press_enables_dpms", Bool, "false", "Pressing a key turns monitors back on"),
+        OptionSchema::new("misc:always_follow_on_dnd", Bool, "true", "Focus follows the cursor while dragging and dropping"),
+        OptionSchema::new("misc:layers_hog_keyboard_focus", Bool, "true", "Layers with keyboard focus keep it when the mouse moves"),
+        OptionSchema::new("misc:animate_manual_resizes", Bool, "false", "Animate resizes done with the resizeactive dispatcher"),
+        OptionSchema::new("misc:animate_mouse_windowdragging", Bool, "false", "Animate windows dragged with the mouse"),
+        OptionSchema::new("misc:disable_autoreload", Bool, "false", "Do not reload the config when it changes"),
+        OptionSchema::new("misc:enable_swallow", Bool, "false", "Let windows swallow the terminal that spawned them"),
+        OptionSchema::new("misc:swallow_regex", String, "", "Class regex of windows that may be swallowed"),
+        OptionSchema::new("misc:swallow_exception_regex", String, "", "Title regex of windows that must not be swallowed"),
+        OptionSchema::new("misc:focus_on_activate", Bool, "false", "Focus windows that request activation"),
+        OptionSchema::new("misc:mouse_move_focuses_monitor", Bool, "true", "Moving the mouse between monitors focuses them"),
+        OptionSchema::new("misc:render_ahead_of_time", Bool, "false", "Start rendering before the next frame is due"),
+        OptionSchema::new("misc:render_ahead_safezone", Int, "1", "Milliseconds of safety margin when rendering ahead of time"),
+        OptionSchema::new("misc:allow_session_lock_restore", Bool, "false", "Allow restarting a crashed lockscreen"),
+        OptionSchema::new("misc:background_color", Color, "rgba(111111ff)", "Color behind the wallpaper"),
+        OptionSchema::new("misc:close_special_on_empty", Bool, "true", "Close a special workspace when its last window closes"),
+        OptionSchema::new("misc:new_window_takes_over_fullscreen", Int, "0", "What a new window does to a fullscreen one on its workspace").range(0.0, 2.0),
+        OptionSchema::new("misc:exit_window_retains_fullscreen", Bool, "false", "The next window keeps fullscreen when a fullscreen window closes"),
+        OptionSchema::new("misc:initial_workspace_tracking", Int, "1", "Open windows on the workspace they were launched from").range(0.0, 2.0),
+        OptionSchema::new("misc:middle_click_paste", Bool, "true", "Paste the primary selection with a middle click"),
+        OptionSchema::new("misc:render_unfocused_fps", Int, "15", "Frame rate reported to windows that are not visible"),
+        OptionSchema::new("misc:disable_xdg_env_checks", Bool, "false", "Do not warn about XDG environment variables"),
+        OptionSchema::new("misc:disable_hyprland_qtutils_check", Bool, "false", "Do not warn when hyprland-qtutils is missing"),
+        OptionSchema::new("misc:lockdead_screen_delay", Int, "1000", "Delay before the lockdead screen appears, in milliseconds"),
+        OptionSchema::new("misc:enable_anr_dialog", Bool, "true", "Show a dialog for applications that stop responding"),
+        OptionSchema::new("misc:anr_missed_pings", Int, "1", "Missed pings before an application counts as not responding"),
+        OptionSchema::new("misc:no_direct_scanout", Bool, "true", "Moved to render:direct_scanout").removed("0.42.0"),
+        OptionSchema::new("misc:hide_cursor_on_touch", Bool, "true", "Moved to cursor:hide_on_touch").removed("0.37.0"),
+        // binds
+        OptionSchema::new("binds:pass_mouse_when_bound", Bool, "false", "Pass mouse events to windows even when bound"),
+        OptionSchema::new("binds:scroll_event_delay", Int, "300", "Milliseconds to wait after a scroll bind before accepting another"),
+        OptionSchema::new("binds:workspace_back_and_forth", Bool, "false", "Switching to the current workspace goes to the previous one"),
+        OptionSchema::new("binds:hide_special_on_workspace_change", Bool, "false", "Hide the special workspace when changing workspace"),
+        OptionSchema::new("binds:allow_workspace_cycles", Bool, "false", "workspace previous cycles between the last two workspaces"),
+        OptionSchema::new("binds:workspace_center_on", Int, "0", "Center the cursor on the workspace (0) or the last active window (1)").range(0.0, 1.0),
+        OptionSchema::new("binds:focus_preferred_method", Int, "0", "Pick the window to focus by history (0) or by length of shared edge (1)").range(0.0, 1.0),
+        OptionSchema::new("binds:ignore_group_lock", Bool, "false", "Let binds move windows in and out of locked groups"),
+        OptionSchema::new("binds:movefocus_cycles_fullscreen", Bool, "false", "movefocus cycles windows on a fullscreen workspace"),
+        OptionSchema::new("binds:movefocus_cycles_groupfirst", Bool, "false", "movefocus cycles through a group before leaving it"),
+        OptionSchema::new("binds:disable_keybind_grabbing", Bool, "false", "Do not let applications grab keybinds"),
+        OptionSchema::new("binds:window_direction_monitor_fallback", Bool, "true", "Moving in a direction with no window goes to the next monitor"),
+        OptionSchema::new("binds:allow_pin_fullscreen", Bool, "false", "Allow pinning fullscreen windows"),
+        // xwayland
+        OptionSchema::new("xwayland:enabled", Bool, "true", "Allow running X11 applications"),
+        OptionSchema::new("xwayland:use_nearest_neighbor", Bool, "true", "Scale X11 applications with nearest neighbor filtering"),
+        OptionSchema::new("xwayland:force_zero_scaling", Bool, "false", "Force a scale of 1 on X11 windows"),
+        OptionSchema::new("xwayland:create_abstract_socket", Bool, "false", "Create the abstract X11 socket"),
+        // opengl
+        OptionSchema::new("opengl:nvidia_anti_flicker", Bool, "true", "Reduce flickering on nvidia at the cost of some latency"),
+        // render
+        OptionSchema::new("render:explicit_sync", Int, "2", "Explicit sync: 0 off, 1 on, 2 auto").range(0.0, 2.0),
+        OptionSchema::new("render:explicit_sync_kms", Int, "2", "Explicit sync for KMS: 0 off, 1 on, 2 auto").range(0.0, 2.0),
+        OptionSchema::new("render:direct_scanout", Int, "0", "Direct scanout for fullscreen windows: 0 off, 1 on, 2 auto").range(0.0, 2.0),
+        OptionSchema::new("render:expand_undersized_textures", Bool, "true", "Stretch textures smaller than their window"),
+        OptionSchema::new("render:xp_mode", Bool, "false", "Disable back buffer and bottom layer rendering"),
+        OptionSchema::new("render:ctm_animation", Int, "2", "Animate color transform changes: 0 off, 1 on, 2 auto").range(0.0, 2.0),
+        OptionSchema::new("render:cm_fs_passthrough", Int, "2", "Pass fullscreen color management through: 0 off, 1 always, 2 HDR only").range(0.0, 2.0),
+        OptionSchema::new("render:cm_enabled", Bool, "true", "Enable color management"),
+        // cursor
+        OptionSchema::new("cursor:sync_gsettings_theme", Bool, "true", "Sync the cursor theme to gsettings"),
+        OptionSchema::new("cursor:no_hardware_cursors", Int, "2", "Hardware cursors: 0 use, 1 disable, 2 auto").range(0.0, 2.0),
+        OptionSchema::new("cursor:no_break_fs_vrr", Int, "2", "Keep VRR when the cursor moves over a fullscreen window").range(0.0, 2.0),
+        OptionSchema::new("cursor:min_refresh_rate", Int, "24", "Lowest refresh rate while the cursor moves with VRR"),
+        OptionSchema::new("cursor:hotspot_padding", Int, "1", "Padding around the cursor hotspot"),
+        OptionSchema::new("cursor:inactive_timeout", Float, "0", "Seconds of inactivity before the cursor hides, 0 to never hide"),
+        OptionSchema::new("cursor:no_warps", Bool, "false", "Do not warp the cursor on focus changes"),
+        OptionSchema::new("cursor:persistent_warps", Bool, "false", "Return the cursor to its last position in a window"),
+        OptionSchema::new("cursor:warp_on_change_workspace", Int, "0", "Warp the cursor to the focused window on workspace change").range(0.0, 2.0),
+        OptionSchema::new("cursor:warp_on_toggle_special", Int, "0", "Warp the cursor to the focused window when toggling a special workspace").range(0.0, 2.0),
+        OptionSchema::new("cursor:default_monitor", String, "", "Monitor the cursor starts on"),
+        OptionSchema::new("cursor:zoom_factor", Float, "1.0", "Zoom around the cursor").range(1.0, 10.0),
+        OptionSchema::new("cursor:zoom_rigid", Bool, "false", "Keep the zoomed view still while the cursor moves"),
+        OptionSchema::new("cursor:enable_hyprcursor", Bool, "true", "Use hyprcursor themes"),
+        OptionSchema::new("cursor:hide_on_key_press", Bool, "false", "Hide the cursor while typing"),
+        OptionSchema::new("cursor:hide_on_touch", Bool, "true", "Hide the cursor on touch input"),
+        OptionSchema::new("cursor:use_cpu_buffer", Int, "2", "Render the cursor through a CPU buffer: 0 off, 1 on, 2 nvidia only").range(0.0, 2.0),
+        OptionSchema::new("cursor:warp_back_after_non_mouse_input", Bool, "false", "Return the cursor after keyboard driven focus changes"),
+        // dwindle
+        OptionSchema::new("dwindle:pseudotile", Bool, "false", "Enable pseudotiling"),
+        OptionSchema::new("dwindle:preserve_split", Bool, "false", "Keep the split direction regardless of container size"),
+        OptionSchema::new("dwindle:smart_split", Bool, "false", "Split by where the cursor is in the window"),
+        OptionSchema::new("dwindle:force_split", Int, "0", "Split to the cursor (0), always left or top (1), always right or bottom (2)").range(0.0, 2.0),
+        OptionSchema::new("dwindle:permanent_direction_override", Bool, "false", "Keep a preselected direction until changed"),
+        OptionSchema::new("dwindle:special_scale_factor", Float, "1.0", "Scale of windows on special workspaces").range(0.0, 1.0),
+        OptionSchema::new("dwindle:split_width_multiplier", Float, "1.0", "Aspect ratio correction for split direction"),
+        OptionSchema::new("dwindle:use_active_for_splits", Bool, "true", "Prefer the active window for splits"),
+        OptionSchema::new("dwindle:default_split_ratio", Float, "1.0", "Split ratio of new splits").range(0.1, 1.9),
+        OptionSchema::new("dwindle:split_bias", Int, "0", "Which window gets the larger share of a split").range(0.0, 2.0),
+        OptionSchema::new("dwindle:smart_resizing", Bool, "true", "Resize by where the cursor is in the window"),
+        OptionSchema::new("dwindle:no_gaps_when_only", Int, "0", "Replaced by workspace rules").removed("0.45.0"),
+        // master
+        OptionSchema::new("master:allow_small_split", Bool, "false", "Allow additional master windows in a horizontal split"),
+        OptionSchema::new("master:special_scale_factor", Float, "1.0", "Scale of windows on special workspaces").range(0.0, 1.0),
+        OptionSchema::new("master:mfact", Float, "0.55", "Share of the screen taken by the master area").range(0.0, 1.0),
+        OptionSchema::new("master:new_status", Enum(&["master", "slave", "inherit"]), "slave", "Whether new windows become master").since("0.41.0"),
+        OptionSchema::new("master:new_on_top", Bool, "false", "Put new windows at the top of the stack"),
+        OptionSchema::new("master:new_on_active", Enum(&["before", "after", "none"]), "none", "Place new windows relative to the focused one"),
+        OptionSchema::new("master:orientation", Enum(&["left", "right", "top", "bottom", "center"]), "left", "Placement of the master area"),
+        OptionSchema::new("master:inherit_fullscreen", Bool, "true", "New windows on a fullscreen workspace inherit fullscreen"),
+        OptionSchema::new("master:slave_count_for_center_master", Int, "2", "Stack windows needed before the master is centered"),
+        OptionSchema::new("master:center_master_slaves_on_right", Bool, "true", "Put the first stack window on the right when centered"),
+        OptionSchema::new("master:smart_resizing", Bool, "true", "Resize by where the cursor is in the window"),
+        OptionSchema::new("master:drop_at_cursor", Bool, "true", "Drop dragged windows at the cursor position"),
+        OptionSchema::new("master:always_keep_position", Bool, "false", "Keep the master in its configured position when alone"),
+        OptionSchema::new("master:new_is_master", Bool, "true", "Replaced by master:new_status").removed("0.41.0"),
+        OptionSchema::new("master:no_gaps_when_only", Int, "0", "Replaced by workspace rules").removed("0.45.0"),
+        // debug
+        OptionSchema::new("debug:overlay", Bool, "false", "Show a performance overlay"),
+        OptionSchema::new("debug:damage_blink", Bool, "false", "Flash damaged areas"),
+        OptionSchema::new("debug:disable_logs", Bool, "true", "Disable logging to a file"),
+        OptionSchema::new("debug:disable_time", Bool, "true", "Leave timestamps out of the log"),
+        OptionSchema::new("debug:damage_tracking", Int, "2", "Damage tracking: 0 none, 1 monitor, 2 full").range(0.0, 2.0),
+        OptionSchema::new("debug:enable_stdout_logs", Bool, "false", "Also log to stdout"),
+        OptionSchema::new("debug:manual_crash", Int, "0", "Set to 1 and then back to 0 to crash Hyprland"),
+        OptionSchema::new("debug:suppress_errors", Bool, "false", "Hide config errors"),
+        OptionSchema::new("debug:watchdog_timeout", Int, "5", "Seconds before the watchdog kills a hung Hyprland, 0 to disable"),
+        OptionSchema::new("debug:disable_scale_checks", Bool, "false", "Allow any monitor scale"),
+        OptionSchema::new("debug:error_limit", Int, "5", "Number of config errors shown"),
+        OptionSchema::new("debug:error_position", Int, "0", "Show config errors at the top (0) or bottom (1)").range(0.0, 1.0),
+        OptionSchema::new("debug:colored_stdout_logs", Bool, "true", "Color stdout logs"),
+        OptionSchema::new("debug:pass", Bool, "false", "Enable render pass debugging"),
+        OptionSchema::new("debug:full_cm_proto", Bool, "false", "Claim support for the full color management protocol"),
+        // ecosystem
+        OptionSchema::new("ecosystem:no_update_news", Bool, "false", "Do not show the update news dialog").since("0.46.0"),
+        OptionSchema::new("ecosystem:no_donation_nag", Bool, "false", "Do not show the donation dialog").since("0.46.0"),
+        OptionSchema::new("ecosystem:enforce_permissions", Bool, "false", "Enforce permission rules").since("0.49.0"),
+        // experimental
+        OptionSchema::new("experimental:xx_color_management_v4", Bool, "false", "Expose the experimental color management protocol"),
+    ];
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::parser::ConfigParser;
+    use serde_json::Value;
+
+    #[test]
+    fn parsed_defaults_match_the_schema() {
+        let defaults = serde_json::to_value(ConfigParser::defaults()).unwrap();
+        let mut mismatched = Vec::new();
+        for option in options().iter().filter(|option| !option.is_removed()) {
+            let mut section = &defaults;
+            for name in option.section().split(':') {
+                section = &section[name];
+            }
+            let field = option.key().replace(['.', '-'], "_");
+            let value = match section.get(&field).unwrap_or(&section["extra"][option.key()]) {
+                Value::Bool(value) => value.to_string(),
+                Value::Number(value) => value.to_string(),
+                Value::String(value) => value.clone(),
+                // Colors and gradients are structs in the model; the parser checks them
+                Value::Object(_) | Value::Array(_) => continue,
+                Value::Null => {
+                    mismatched.push(format!("{}: not in the model", option.path));
+                    continue;
+                }
+            };
+            if !option.is_default(&value) {
+                mismatched.push(format!("{}: model {:?}, schema {:?}", option.path, value, option.default));
+            }
+        }
+        assert!(mismatched.is_empty(), "{:#?}", mismatched);
+        assert_eq!(ConfigParser::defaults().gestures.workspace_swipe_fingers, 3);
+    }
+
+    #[test]
+    fn values_are_checked_against_type_and_range() {
+        assert!(check_option("general:border_size", "40").is_ok());
+        assert!(check_option("general:border_size", "-1").is_err());
+        assert!(check_option("decoration:rounding", "32").is_ok());
+        assert!(check_option("decoration:active_opacity", "1.5").is_err());
+        assert!(check_option("general:layout", "hy3").is_ok());
+        assert!(check_option("input:scroll_method", "sideways").is_err());
+        assert!(check_option("general:gaps_in", "5 10").is_ok());
+        assert!(check_option("general:gaps_in", "5 10 15 20 25").is_err());
+    }
+
+    #[test]
+    fn removed_and_misspelled_options_are_rejected() {
+        assert!(check_option("general:no_cursor_warps", "true").unwrap_err().contains("removed"));
+        assert!(check_option("general:bordersize", "1").unwrap_err().contains("unknown option"));
+        assert!(check_option("plugin:hyprbars:bar_height", "20").is_ok());
+    }
+
+    #[test]
+    fn strip_defaults_drops_default_options_and_empty_blocks() {
+        let document = Document::parse("general {\n    border_size = 1\n    gaps_in = 5\n}\n\ndecoration {\n    rounding = 10\n}\n");
+        assert_eq!(strip_defaults(&document).to_string(), "decoration {\n    rounding = 10\n}\n");
+    }
+}
@@ -1,5 +1,9 @@
 use std::fmt;
 use std::io::{self, Write};
+use std::str::FromStr;
+
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Copy)]
 pub enum CommentStyle {
@@ -16,6 +20,60 @@ impl CommentStyle {
     }
 }
 
+/// How options of a section are laid out when writing a config
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum OptionStyle {
+    /// `general { gaps_in = 5 }`
+    #[default]
+    Block,
+    /// `general:gaps_in = 5`
+    #[value(name = "colon")]
+    ColonPath,
+}
+
+/// Which options are written when a whole config is rendered
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum WriteMode {
+    /// Every option hyprconf models
+    #[default]
+    Full,
+    /// Only options whose value differs from Hyprland's default
+    Minimal,
+    /// Every option, each with its description and default in a comment
+    Explicit,
+}
+
+/// How command line reports are printed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum ReportFormat {
+    /// One line per item, for reading
+    #[default]
+    Text,
+    /// A JSON array, for scripts
+    Json,
+}
+
+/// Structured formats a config can be exported to and imported from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum DataFormat {
+    #[default]
+    Json,
+    Toml,
+    Yaml,
+}
+
+impl DataFormat {
+    /// Format implied by a file's extension
+    pub fn from_path(path: &str) -> Option<Self> {
+        match path.rsplit_once('.')?.1.to_ascii_lowercase().as_str() {
+            "json" => Some(DataFormat::Json),
+            "toml" => Some(DataFormat::Toml),
+            "yaml" | "yml" => Some(DataFormat::Yaml),
+            _ => None,
+        }
+    }
+}
+
 pub fn write_section_header<W: Write>(
     writer: &mut W,
     section_name: &str,
@@ -39,6 +97,19 @@ pub fn write_option<W: Write, T: fmt::Display>(
     }
 }
 
+/// Write options hyprconf has no model for, as they were read
+pub fn write_extra_options<W: Write>(
+    writer: &mut W,
+    indent: &str,
+    extra: &IndexMap<String, String>,
+    comment_style: &CommentStyle,
+) -> io::Result<()> {
+    for (key, value) in extra {
+        write_option(writer, &format!("{}{}", indent, key), value, None, comment_style)?;
+    }
+    Ok(())
+}
+
 pub fn write_boolean_option<W: Write>(
     writer: &mut W,
     option_name: &str,
@@ -59,8 +130,7 @@ pub fn write_rgb_color<W: Write>(
     comment: Option<&str>,
     comment_style: &CommentStyle,
 ) -> io::Result<()> {
-    let rgb = format!("rgb({},{},{})", r, g, b);
-    write_option(writer, option_name, &rgb, comment, comment_style)
+    write_option(writer, option_name, &Color::rgb(r, g, b), comment, comment_style)
 }
 
 pub fn write_rgba_color<W: Write>(
@@ -73,8 +143,237 @@ pub fn write_rgba_color<W: Write>(
     comment: Option<&str>,
     comment_style: &CommentStyle,
 ) -> io::Result<()> {
-    let rgba = format!("rgba({},{},{},{})", r, g, b, a);
-    write_option(writer, option_name, &rgba, comment, comment_style)
+    write_option(writer, option_name, &Color::rgba(r, g, b, a), comment, comment_style)
+}
+
+/// A color option value.
+///
+/// Reads every form Hyprland takes: `rgba(33ccffee)`, `rgba(51, 204, 255, 0.93)`,
+/// `rgb(33ccff)`, `rgb(51, 204, 255)`, `0xee33ccff`, a plain ARGB integer and
+/// `$variables`. Colors are written back as `rgb(rrggbb)` or `rgba(rrggbbaa)`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(into = "String", try_from = "String")]
+pub enum Color {
+    Rgba { r: u8, g: u8, b: u8, a: u8 },
+    /// A `$variable` reference, resolved by Hyprland
+    Variable(String),
+}
+
+impl Default for Color {
+    fn default() -> Self {
+        Color::rgb(0, 0, 0)
+    }
+}
+
+impl Color {
+    pub fn rgb(r: u8, g: u8, b: u8) -> Self {
+        Color::Rgba { r, g, b, a: 255 }
+    }
+
+    /// A color with alpha given from 0.0 to 1.0
+    pub fn rgba(r: u8, g: u8, b: u8, a: f32) -> Self {
+        Color::Rgba { r, g, b, a: (a.clamp(0.0, 1.0) * 255.0).round() as u8 }
+    }
+
+    /// A color from a `0xAARRGGBB` integer
+    pub fn from_argb(argb: u32) -> Self {
+        let [a, r, g, b] = argb.to_be_bytes();
+        Color::Rgba { r, g, b, a }
+    }
+
+    /// Convert for GTK color pickers; variables have no color of their own
+    pub fn to_gdk(&self) -> Option<gdk::RGBA> {
+        match self {
+            Color::Rgba { r, g, b, a } => Some(gdk::RGBA::new(
+                *r as f64 / 255.0,
+                *g as f64 / 255.0,
+                *b as f64 / 255.0,
+                *a as f64 / 255.0,
+            )),
+            Color::Variable(_) => None,
+        }
+    }
+
+    /// Take the color chosen in a GTK color picker
+    pub fn from_gdk(rgba: &gdk::RGBA) -> Self {
+        let channel = |v: f64| (v.clamp(0.0, 1.0) * 255.0).round() as u8;
+        Color::Rgba {
+            r: channel(rgba.red()),
+            g: channel(rgba.green()),
+            b: channel(rgba.blue()),
+            a: channel(rgba.alpha()),
+        }
+    }
+}
+
+impl FromStr for Color {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let invalid = || format!("`{}` is not a color", s);
+
+        if let Some(name) = s.strip_prefix('$') {
+            if name.is_empty() {
+                return Err(invalid());
+            }
+            return Ok(Color::Variable(name.to_string()));
+        }
+        if let Some(hex) = s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")) {
+            return u32::from_str_radix(hex, 16).map(Color::from_argb).map_err(|_| invalid());
+        }
+        if let Ok(argb) = s.parse::<i64>() {
+            // Hyprland reads plain integers as ARGB, so `-1` is opaque white
+            return Ok(Color::from_argb(argb as u32));
+        }
+
+        let (args, has_alpha) = match (s.strip_prefix("rgba("), s.strip_prefix("rgb(")) {
+            (Some(rest), _) => (rest.strip_suffix(')').ok_or_else(invalid)?, true),
+            (None, Some(rest)) => (rest.strip_suffix(')').ok_or_else(invalid)?, false),
+            (None, None) => return Err(invalid()),
+        };
+        let args = args.trim();
+
+        if !args.contains(',') {
+            let hex = match (has_alpha, args.len()) {
+                (true, 8) => args.to_string(),
+                (false, 6) => format!("{}ff", args),
+                _ => return Err(invalid()),
+            };
+            let rgba = u32::from_str_radix(&hex, 16).map_err(|_| invalid())?;
+            let [r, g, b, a] = rgba.to_be_bytes();
+            return Ok(Color::Rgba { r, g, b, a });
+        }
+
+        let parts: Vec<&str> = args.split(',').map(str::trim).collect();
+        let channel = |part: &str| part.parse::<u8>().map_err(|_| invalid());
+        match (has_alpha, parts.as_slice()) {
+            (true, [r, g, b, a]) => {
+                let a: f32 = a.parse().map_err(|_| invalid())?;
+                if !(0.0..=1.0).contains(&a) {
+                    return Err(invalid());
+                }
+                Ok(Color::rgba(channel(r)?, channel(g)?, channel(b)?, a))
+            }
+            (false, [r, g, b]) => Ok(Color::rgb(channel(r)?, channel(g)?, channel(b)?)),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+impl fmt::Display for Color {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Color::Rgba { r, g, b, a: 255 } => write!(f, "rgb({:02x}{:02x}{:02x})", r, g, b),
+            Color::Rgba { r, g, b, a } => write!(f, "rgba({:02x}{:02x}{:02x}{:02x})", r, g, b, a),
+            Color::Variable(name) => write!(f, "${}", name),
+        }
+    }
+}
+
+impl From<Color> for String {
+    fn from(color: Color) -> Self {
+        color.to_string()
+    }
+}
+
+impl TryFrom<String> for Color {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+/// A border gradient: one or more colors and an optional angle, e.g.
+/// `rgba(33ccffee) rgba(00ff99ee) 45deg`
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(into = "String", try_from = "String")]
+pub struct Gradient {
+    pub colors: Vec<Color>,
+    /// Angle in degrees
+    pub angle: Option<i32>,
+}
+
+impl Gradient {
+    pub fn solid(color: Color) -> Self {
+        Gradient { colors: vec![color], angle: None }
+    }
+}
+
+impl FromStr for Gradient {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut gradient = Gradient::default();
+        let mut tokens = split_color_tokens(s).into_iter().peekable();
+        while let Some(token) = tokens.next() {
+            match token.strip_suffix("deg") {
+                Some(angle) if tokens.peek().is_none() && !gradient.colors.is_empty() => {
+                    let angle = angle.parse().map_err(|_| format!("`{}` is not an angle", token))?;
+                    gradient.angle = Some(angle);
+                }
+                _ => gradient.colors.push(token.parse()?),
+            }
+        }
+        if gradient.colors.is_empty() {
+            return Err("a gradient needs at least one color".to_string());
+        }
+        Ok(gradient)
+    }
+}
+
+impl fmt::Display for Gradient {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let colors: Vec<String> = self.colors.iter().map(ToString::to_string).collect();
+        write!(f, "{}", colors.join(" "))?;
+        if let Some(angle) = self.angle {
+            write!(f, " {}deg", angle)?;
+        }
+        Ok(())
+    }
+}
+
+impl From<Gradient> for String {
+    fn from(gradient: Gradient) -> Self {
+        gradient.to_string()
+    }
+}
+
+impl TryFrom<String> for Gradient {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        if s.trim().is_empty() {
+            return Ok(Gradient::default());
+        }
+        s.parse()
+    }
+}
+
+/// Split a gradient into its colors and angle, keeping `rgba(51, 204, 255, 0.9)` whole
+fn split_color_tokens(s: &str) -> Vec<&str> {
+    let mut tokens = Vec::new();
+    let mut depth = 0;
+    let mut start = None;
+    for (i, c) in s.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            c if c.is_whitespace() && depth <= 0 => {
+                if let Some(at) = start.take() {
+                    tokens.push(&s[at..i]);
+                }
+                continue;
+            }
+            _ => {}
+        }
+        start.get_or_insert(i);
+    }
+    if let Some(at) = start {
+        tokens.push(&s[at..]);
+    }
+    tokens
 }
 
 use std::path::Path;
@@ -110,3 +409,130 @@ pub fn parse_bool(value: &str) -> bool {
     }
 }
 
+
+/// An option value that does not fit the option's type
+#[derive(Debug, Clone, PartialEq)]
+pub struct InvalidValue {
+    pub key: String,
+    pub value: String,
+    /// What the option takes, e.g. "an integer"
+    pub expected: &'static str,
+}
+
+impl fmt::Display for InvalidValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid value `{}` for `{}`: expected {}", self.value, self.key, self.expected)
+    }
+}
+
+impl std::error::Error for InvalidValue {}
+
+/// A type an option value can be parsed into
+pub trait ConfigValue: FromStr {
+    /// What the option takes, shown when a value does not parse
+    const EXPECTED: &'static str;
+}
+
+macro_rules! config_value {
+    ($expected:literal: $($ty:ty),*) => {
+        $(impl ConfigValue for $ty {
+            const EXPECTED: &'static str = $expected;
+        })*
+    };
+}
+
+config_value!("an integer": i8, i16, i32, i64, u8, u16, u32, u64, usize);
+config_value!("a number": f32, f64);
+
+impl ConfigValue for Color {
+    const EXPECTED: &'static str = "a color";
+}
+
+impl ConfigValue for Gradient {
+    const EXPECTED: &'static str = "a gradient";
+}
+
+/// Parse a typed option value, reporting it instead of falling back to a default
+pub fn parse_value<T: ConfigValue>(key: &str, value: &str) -> Result<T, InvalidValue> {
+    value.trim().parse().map_err(|_| InvalidValue {
+        key: key.to_string(),
+        value: value.trim().to_string(),
+        expected: T::EXPECTED,
+    })
+}
+
+/// Parse a boolean option value the way Hyprland does, reporting anything it would reject
+pub fn parse_bool_value(key: &str, value: &str) -> Result<bool, InvalidValue> {
+    let lower = value.trim().to_lowercase();
+    if ["true", "yes", "on"].iter().any(|p| lower.starts_with(p)) {
+        return Ok(true);
+    }
+    if ["false", "no", "off"].iter().any(|p| lower.starts_with(p)) {
+        return Ok(false);
+    }
+    lower.parse::<i64>().map(|n| n != 0).map_err(|_| InvalidValue {
+        key: key.to_string(),
+        value: value.trim().to_string(),
+        expected: "a boolean",
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn colors_parse_every_form_hyprland_takes() {
+        let expected = Color::Rgba { r: 0x33, g: 0xcc, b: 0xff, a: 0xee };
+        for text in ["rgba(33ccffee)", "0xee33ccff", "rgba( 51, 204, 255, 0.933 )"] {
+            assert_eq!(text.parse::<Color>().unwrap(), expected, "{}", text);
+        }
+        assert_eq!("rgb(33ccff)".parse::<Color>().unwrap(), Color::rgb(0x33, 0xcc, 0xff));
+        assert_eq!("rgb(51, 204, 255)".parse::<Color>().unwrap(), Color::rgb(0x33, 0xcc, 0xff));
+        assert_eq!("-1".parse::<Color>().unwrap(), Color::Rgba { r: 255, g: 255, b: 255, a: 255 });
+        assert_eq!("$accent".parse::<Color>().unwrap(), Color::Variable("accent".to_string()));
+    }
+
+    #[test]
+    fn bad_colors_are_rejected() {
+        for text in ["rgba(33ccff)", "rgb(33ccffee)", "rgb(300, 0, 0)", "rgba(1, 2, 3, 1.5)", "blue", "$", "rgb(33ccff"] {
+            assert!(text.parse::<Color>().is_err(), "{}", text);
+        }
+    }
+
+    #[test]
+    fn colors_are_normalised_on_write() {
+        assert_eq!("0xff33ccff".parse::<Color>().unwrap().to_string(), "rgb(33ccff)");
+        assert_eq!("rgba(51, 204, 255, 0.5)".parse::<Color>().unwrap().to_string(), "rgba(33ccff80)");
+    }
+
+    #[test]
+    fn gdk_round_trip_keeps_the_channels() {
+        let color = Color::Rgba { r: 0x33, g: 0xcc, b: 0xff, a: 0xee };
+        assert_eq!(Color::from_gdk(&color.to_gdk().unwrap()), color);
+        assert!(Color::Variable("accent".to_string()).to_gdk().is_none());
+    }
+
+    #[test]
+    fn gradients_take_colors_and_a_trailing_angle() {
+        let gradient: Gradient = "rgba(33ccffee) rgba(00ff99ee) 45deg".parse().unwrap();
+        assert_eq!(gradient.colors.len(), 2);
+        assert_eq!(gradient.angle, Some(45));
+        assert_eq!(gradient.to_string(), "rgba(33ccffee) rgba(00ff99ee) 45deg");
+
+        assert_eq!("$accent".parse::<Gradient>().unwrap(), Gradient::solid(Color::Variable("accent".to_string())));
+        assert!("45deg".parse::<Gradient>().is_err());
+        assert!("".parse::<Gradient>().is_err());
+    }
+
+    #[test]
+    fn invalid_values_say_what_was_expected() {
+        assert_eq!(parse_value::<i32>("gaps_in", " 5 ").unwrap(), 5);
+        assert_eq!(parse_value::<i32>("gaps_in", "1O").unwrap_err().expected, "an integer");
+        assert_eq!(parse_value::<f32>("active_opacity", "x").unwrap_err().expected, "a number");
+        assert_eq!(parse_value::<Color>("col.shadow", "x").unwrap_err().expected, "a color");
+        assert_eq!(parse_value::<Gradient>("col.active_border", "x").unwrap_err().expected, "a gradient");
+        assert_eq!(parse_bool_value("vfr", "maybe").unwrap_err().expected, "a boolean");
+        assert!(parse_bool_value("vfr", "on").unwrap());
+    }
+}
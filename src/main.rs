@@ -3,11 +3,19 @@ mod config;
 mod safety;
 mod ui;
 
+use std::fs::File;
 use std::process;
 use gio::prelude::*;
 use clap::{Parser, Subcommand};
+use config::ConfigManager;
+use config::diff;
+use config::export;
+use config::format;
+use config::lint;
+use config::migrate;
 use config::parser::ConfigParser;
-use config::models::core::HyprlandConfig;
+use config::parser::source::SourceTree;
+use config::utils::{CommentStyle, DataFormat, OptionStyle, ReportFormat, WriteMode};
 
 #[derive(Parser)]
 #[command(name = "hyprconf")]
@@ -30,6 +38,106 @@ enum Commands {
         /// Path to output the generated config file
         #[arg(default_value = "~/.config/hypr/hyprland.conf")]
         path: String,
+        /// Write section options as blocks or as `section:key = value` lines
+        #[arg(long, value_enum, default_value_t = OptionStyle::Block)]
+        style: OptionStyle,
+        /// Write every option, only those that differ from Hyprland's defaults, or
+        /// every option with its description
+        #[arg(long, value_enum, default_value_t = WriteMode::Full)]
+        mode: WriteMode,
+    },
+    /// Rewrite options Hyprland has deprecated into their current form
+    Migrate {
+        /// Path to the Hyprland config file
+        #[arg(default_value = "~/.config/hypr/hyprland.conf")]
+        path: String,
+        /// Hyprland version to migrate to; defaults to the newest known
+        #[arg(long)]
+        to: Option<String>,
+    },
+    /// Print the value of an option, e.g. `decoration:blur:size`
+    Get {
+        /// Colon path of the option
+        option: String,
+        /// Path to the Hyprland config file
+        #[arg(long, default_value = "~/.config/hypr/hyprland.conf")]
+        file: String,
+    },
+    /// Set an option, keeping the rest of the file as it is
+    Set {
+        /// Colon path of the option
+        option: String,
+        /// New value
+        value: String,
+        /// Path to the Hyprland config file
+        #[arg(long, default_value = "~/.config/hypr/hyprland.conf")]
+        file: String,
+        /// Also apply the value to the running Hyprland session with `hyprctl keyword`
+        #[arg(long)]
+        apply: bool,
+    },
+    /// Compare two config files option by option; exits with 1 when they differ
+    Diff {
+        /// The config to compare from
+        before: String,
+        /// The config to compare to
+        after: String,
+        /// Print one line per difference or a JSON array
+        #[arg(long, value_enum, default_value_t = ReportFormat::Text)]
+        format: ReportFormat,
+    },
+    /// Rewrite config files in canonical formatting
+    Fmt {
+        /// Config files to format; sourced files are not followed
+        #[arg(default_value = "~/.config/hypr/hyprland.conf")]
+        paths: Vec<String>,
+        /// Only report files that are not formatted, exiting with 1 if there are any
+        #[arg(long)]
+        check: bool,
+    },
+    /// Print a config as structured data
+    Export {
+        /// Path to the Hyprland config file
+        #[arg(default_value = "~/.config/hypr/hyprland.conf")]
+        path: String,
+        #[arg(long, value_enum, default_value_t = DataFormat::Json)]
+        format: DataFormat,
+        /// Write to a file instead of standard output
+        #[arg(long)]
+        output: Option<String>,
+    },
+    /// Write a Hyprland config from structured data; missing fields take Hyprland's defaults
+    Import {
+        /// JSON, TOML or YAML file to read
+        file: String,
+        /// Format of the file; taken from its extension when omitted
+        #[arg(long, value_enum)]
+        format: Option<DataFormat>,
+        /// Path to write the Hyprland config file to
+        #[arg(long, default_value = "~/.config/hypr/hyprland.conf")]
+        output: String,
+        /// Write section options as blocks or as `section:key = value` lines
+        #[arg(long, value_enum, default_value_t = OptionStyle::Block)]
+        style: OptionStyle,
+        /// Write every option, only those that differ from Hyprland's defaults, or
+        /// every option with its description
+        #[arg(long, value_enum, default_value_t = WriteMode::Full)]
+        mode: WriteMode,
+    },
+    /// Check a config for likely mistakes that are still valid syntax
+    Lint {
+        /// Path to the Hyprland config file
+        #[arg(default_value = "~/.config/hypr/hyprland.conf")]
+        path: String,
+        /// Skip a lint by its ID; may be repeated
+        #[arg(long, value_name = "ID")]
+        allow: Vec<String>,
+        /// Exit with 1 on warnings as well as errors
+        #[arg(long)]
+        deny_warnings: bool,
+        /// List the available lints and exit
+        #[arg(long)]
+        list: bool,
     },
 }
 
@@ -41,26 +149,44 @@ fn main() {
             let path = shellexpand::tilde(&path).to_string();
             println!("Validating config file: {}", path);
             
-            match ConfigParser::parse_file(&path) {
-                Ok(_) => {
-                    println!("Configuration file is valid!");
-                    process::exit(0);
-                }
+            let sources = match SourceTree::load(&path) {
+                Ok(sources) => sources,
                 Err(e) => {
-                    eprintln!("Error validating configuration: {}", e);
+                    eprintln!("Error validating configuration: {:#}", e);
                     process::exit(1);
                 }
+            };
+
+            let diagnostics = ConfigParser::validate(&sources);
+            for diagnostic in &diagnostics {
+                let source = sources.line(&diagnostic.file, diagnostic.line).map_or("", |l| l.raw.as_str());
+                eprintln!("{}", diagnostic.render(source));
+            }
+
+            let errors = diagnostics.iter().filter(|d| d.is_error()).count();
+            let warnings = diagnostics.len() - errors;
+            if errors > 0 {
+                eprintln!("Configuration has {} error(s) and {} warning(s)", errors, warnings);
+                process::exit(1);
+            }
+            if warnings > 0 {
+                println!("Configuration file is valid, with {} warning(s)", warnings);
+            } else {
+                println!("Configuration file is valid!");
             }
+            process::exit(0);
         }
-        Some(Commands::Generate { path }) => {
+        Some(Commands::Generate { path, style, mode }) => {
             let path = shellexpand::tilde(&path).to_string();
             println!("Generating default config file: {}", path);
             
             // Generate a default config
-            let default_config = HyprlandConfig::default();
+            let default_config = ConfigParser::defaults();
             
             // Write the config directly to the specified path
-            match default_config.write_to_file(&path) {
+            let written = File::create(&path)
+                .and_then(|mut file| default_config.write_styled(&mut file, &CommentStyle::Hash, style, mode));
+            match written {
                 Ok(_) => {
                     println!("Default configuration file generated!");
                     process::exit(0);
@@ -71,10 +197,270 @@ fn main() {
                 }
             }
         }
+        Some(Commands::Migrate { path, to }) => {
+            let path = shellexpand::tilde(&path).to_string();
+            let target = match migrate::target_version(to.as_deref()) {
+                Ok(target) => target,
+                Err(e) => {
+                    eprintln!("{:#}", e);
+                    process::exit(1);
+                }
+            };
+            println!("Migrating config file to Hyprland {}: {}", target, path);
+
+            let mut manager = match ConfigManager::from_file(&path) {
+                Ok(manager) => manager,
+                Err(e) => {
+                    eprintln!("Error loading configuration: {}", e);
+                    process::exit(1);
+                }
+            };
+            let changes = migrate::migrate(manager.get_config_mut(), target);
+            if changes.is_empty() {
+                println!("Nothing to migrate");
+                process::exit(0);
+            }
+            for change in &changes {
+                println!("  {}", change);
+            }
+
+            if let Err(e) = manager.save() {
+                eprintln!("Error saving configuration: {}", e);
+                process::exit(1);
+            }
+            let manual = changes.iter().filter(|c| c.manual.is_some()).count();
+            println!("Applied {} change(s), {} need manual attention", changes.len() - manual, manual);
+            process::exit(0);
+        }
+        Some(Commands::Get { option, file }) => {
+            let path = shellexpand::tilde(&file).to_string();
+            let manager = match ConfigManager::from_file(&path) {
+                Ok(manager) => manager,
+                Err(e) => {
+                    eprintln!("Error loading configuration: {}", e);
+                    process::exit(1);
+                }
+            };
+            match manager.get_option_effective(&option) {
+                Ok(Some(value)) => {
+                    println!("{}", value);
+                    process::exit(0);
+                }
+                Ok(None) => {
+                    eprintln!("`{}` is not set and has no known default", option);
+                    process::exit(1);
+                }
+                Err(e) => {
+                    eprintln!("Error reading configuration: {}", e);
+                    process::exit(1);
+                }
+            }
+        }
+        Some(Commands::Set { option, value, file, apply }) => {
+            let path = shellexpand::tilde(&file).to_string();
+            let mut manager = match ConfigManager::from_file(&path) {
+                Ok(manager) => manager,
+                Err(e) => {
+                    eprintln!("Error loading configuration: {}", e);
+                    process::exit(1);
+                }
+            };
+            if let Err(e) = manager.set_option(&option, &value) {
+                eprintln!("Cannot set `{}`: {}", option, e);
+                process::exit(1);
+            }
+            if let Err(e) = manager.save() {
+                eprintln!("Error saving configuration: {}", e);
+                process::exit(1);
+            }
+            println!("Set {} = {}", option, value);
+
+            if apply {
+                let resolved = manager.get_option_resolved(&option).ok().flatten().unwrap_or(value);
+                if let Err(e) = hyprctl_keyword(&option, &resolved) {
+                    eprintln!("Saved, but applying to Hyprland failed: {}", e);
+                    process::exit(1);
+                }
+                println!("Applied to the running session");
+            }
+            process::exit(0);
+        }
+        Some(Commands::Diff { before, after, format }) => {
+            let mut configs = Vec::new();
+            for path in [&before, &after] {
+                let path = shellexpand::tilde(path).to_string();
+                match ConfigManager::from_file(&path) {
+                    Ok(manager) => configs.push(manager),
+                    Err(e) => {
+                        eprintln!("Error loading {}: {}", path, e);
+                        process::exit(2);
+                    }
+                }
+            }
+
+            let differences = match diff::diff(configs[0].get_config(), configs[1].get_config()) {
+                Ok(differences) => differences,
+                Err(e) => {
+                    eprintln!("Error comparing configurations: {}", e);
+                    process::exit(2);
+                }
+            };
+            match format {
+                ReportFormat::Text => {
+                    for difference in &differences {
+                        println!("{}", difference);
+                    }
+                }
+                ReportFormat::Json => match serde_json::to_string_pretty(&differences) {
+                    Ok(json) => println!("{}", json),
+                    Err(e) => {
+                        eprintln!("Error writing JSON: {}", e);
+                        process::exit(2);
+                    }
+                },
+            }
+            process::exit(if differences.is_empty() { 0 } else { 1 });
+        }
+        Some(Commands::Fmt { paths, check }) => {
+            let mut unformatted = 0;
+            for path in &paths {
+                let path = shellexpand::tilde(path).to_string();
+                let content = match std::fs::read_to_string(&path) {
+                    Ok(content) => content,
+                    Err(e) => {
+                        eprintln!("Error reading {}: {}", path, e);
+                        process::exit(2);
+                    }
+                };
+                let formatted = format::format(&content);
+                if formatted == content {
+                    continue;
+                }
+
+                unformatted += 1;
+                if check {
+                    println!("Would reformat {}", path);
+                } else if let Err(e) = std::fs::write(&path, formatted) {
+                    eprintln!("Error writing {}: {}", path, e);
+                    process::exit(2);
+                } else {
+                    println!("Formatted {}", path);
+                }
+            }
+            process::exit(if check && unformatted > 0 { 1 } else { 0 });
+        }
+        Some(Commands::Export { path, format, output }) => {
+            let path = shellexpand::tilde(&path).to_string();
+            let manager = match ConfigManager::from_file(&path) {
+                Ok(manager) => manager,
+                Err(e) => {
+                    eprintln!("Error loading configuration: {}", e);
+                    process::exit(1);
+                }
+            };
+            let content = match export::export(manager.get_config(), format) {
+                Ok(content) => content,
+                Err(e) => {
+                    eprintln!("Error exporting configuration: {:#}", e);
+                    process::exit(1);
+                }
+            };
+            match output {
+                Some(output) => {
+                    let output = shellexpand::tilde(&output).to_string();
+                    if let Err(e) = std::fs::write(&output, content) {
+                        eprintln!("Error writing {}: {}", output, e);
+                        process::exit(1);
+                    }
+                }
+                None => print!("{}", content),
+            }
+            process::exit(0);
+        }
+        Some(Commands::Import { file, format, output, style, mode }) => {
+            let file = shellexpand::tilde(&file).to_string();
+            let Some(format) = format.or_else(|| DataFormat::from_path(&file)) else {
+                eprintln!("Cannot tell the format of {}; pass --format", file);
+                process::exit(1);
+            };
+            let config = match std::fs::read_to_string(&file).map_err(anyhow::Error::from)
+                .and_then(|content| export::import(&content, format))
+            {
+                Ok((config, warnings)) => {
+                    for warning in warnings {
+                        eprintln!("warning: {}", warning);
+                    }
+                    config
+                }
+                Err(e) => {
+                    eprintln!("Error importing {}: {:#}", file, e);
+                    process::exit(1);
+                }
+            };
+
+            let output = shellexpand::tilde(&output).to_string();
+            let written = File::create(&output)
+                .and_then(|mut out| config.write_styled(&mut out, &CommentStyle::Hash, style, mode));
+            if let Err(e) = written {
+                eprintln!("Error writing {}: {}", output, e);
+                process::exit(1);
+            }
+            println!("Wrote {}", output);
+            process::exit(0);
+        }
+        Some(Commands::Lint { path, allow, deny_warnings, list }) => {
+            let lints = lint::lints();
+            if list {
+                for lint in &lints {
+                    println!("{:<28} {:<8} {}", lint.id(), lint.severity().to_string(), lint.description());
+                }
+                process::exit(0);
+            }
+            if let Some(unknown) = allow.iter().find(|id| !lints.iter().any(|lint| lint.id() == id.as_str())) {
+                eprintln!("Unknown lint `{}`; see `hyprconf lint --list`", unknown);
+                process::exit(2);
+            }
+
+            let path = shellexpand::tilde(&path).to_string();
+            let sources = match SourceTree::load(&path) {
+                Ok(sources) => sources,
+                Err(e) => {
+                    eprintln!("Error loading configuration: {:#}", e);
+                    process::exit(2);
+                }
+            };
+            let diagnostics = lint::run(&sources, &lints, &allow);
+            for diagnostic in &diagnostics {
+                let source = sources.line(&diagnostic.file, diagnostic.line).map_or("", |l| l.raw.as_str());
+                eprintln!("{}", diagnostic.render(source));
+            }
+
+            let errors = diagnostics.iter().filter(|d| d.is_error()).count();
+            let warnings = diagnostics.len() - errors;
+            if diagnostics.is_empty() {
+                println!("No problems found");
+            } else {
+                println!("Found {} error(s) and {} warning(s)", errors, warnings);
+            }
+            let failed = errors > 0 || (deny_warnings && warnings > 0);
+            process::exit(if failed { 1 } else { 0 });
+        }
         None => {
             // Run the GUI application
             let app = app::build_app();
             app.run();
         }
     }
-}
\ No newline at end of file
+}
+
+/// Set an option in the running Hyprland session
+fn hyprctl_keyword(option: &str, value: &str) -> Result<(), std::io::Error> {
+    let out = std::process::Command::new("hyprctl").args(["keyword", option, value]).output()?;
+    // hyprctl reports rejected keywords on stdout with a zero exit status
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    if !out.status.success() || stdout.trim() != "ok" {
+        let err = if stdout.trim().is_empty() { String::from_utf8_lossy(&out.stderr) } else { stdout };
+        return Err(std::io::Error::other(err.trim().to_string()));
+    }
+    Ok(())
+}